@@ -1,7 +1,9 @@
+use std::path::PathBuf;
+
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use passforge::{
-    Generator, Length, PassphraseConfig, PassphraseGenerator, PasswordConfig, PasswordGenerator,
-    StrengthEvaluator, WordList, ZxcvbnAnalysis,
+    Generator, Length, LoadedWordList, PassphraseConfig, PassphraseGenerator, PasswordConfig,
+    PasswordGenerator, StrengthEvaluator, WordList, ZxcvbnAnalysis,
 };
 
 fn generate_passwords(c: &mut Criterion) {
@@ -22,6 +24,30 @@ fn generate_passphrases(c: &mut Criterion) {
     });
 }
 
+fn generate_passphrases_word_list_loading(c: &mut Criterion) {
+    let file = WordList::Custom(PathBuf::from("resources/eff_large_wordlist.txt"));
+    let loaded = LoadedWordList::load(&file).unwrap();
+
+    let mut group = c.benchmark_group("generate 100 single passphrases");
+    let config = PassphraseConfig::new(5, "-".to_string(), file);
+    group.bench_function("reading the word list file per passphrase", |b| {
+        b.iter(|| {
+            for _ in 0..100 {
+                black_box(PassphraseGenerator::generate(&config).unwrap());
+            }
+        })
+    });
+    let config = PassphraseConfig::new(5, "-".to_string(), WordList::Loaded(loaded));
+    group.bench_function("with a loaded word list", |b| {
+        b.iter(|| {
+            for _ in 0..100 {
+                black_box(PassphraseGenerator::generate(&config).unwrap());
+            }
+        })
+    });
+    group.finish();
+}
+
 fn evaluate_password_strength(c: &mut Criterion) {
     let config = PasswordConfig::new(Length::Single(16), true, true, true);
     let passwords = PasswordGenerator::generate_multiple(&config, 100).unwrap();
@@ -38,6 +64,7 @@ criterion_group!(
     benches,
     generate_passwords,
     generate_passphrases,
+    generate_passphrases_word_list_loading,
     evaluate_password_strength
 );
 criterion_main!(benches);
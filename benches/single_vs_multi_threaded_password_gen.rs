@@ -1,14 +1,14 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use passforge::{Generator, Length, PasswordConfig, PasswordGenerator};
+use passforge::{Generator, Length, PasswordConfig, PasswordGenerator, SecretString};
 use rayon::prelude::*;
 
-fn sequential_generation(config: &PasswordConfig, count: usize) -> Vec<String> {
+fn sequential_generation(config: &PasswordConfig, count: usize) -> Vec<SecretString> {
     (0..count)
         .map(|_| PasswordGenerator::generate(config).unwrap())
         .collect()
 }
 
-fn parallel_generation(config: &PasswordConfig, count: usize) -> Vec<String> {
+fn parallel_generation(config: &PasswordConfig, count: usize) -> Vec<SecretString> {
     (0..count)
         .into_par_iter()
         .map(|_| PasswordGenerator::generate(config).unwrap())
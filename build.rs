@@ -0,0 +1,20 @@
+//! Generates the C header for the `ffi` module when the `ffi` feature is enabled.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "ffi")]
+    generate_header();
+}
+
+/// Writes the declarations of `src/ffi.rs` to `passforge.h` in `OUT_DIR`. The
+/// `test_ffi_header_is_up_to_date` test checks that `include/passforge.h` matches it.
+#[cfg(feature = "ffi")]
+fn generate_header() {
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("Cargo sets CARGO_MANIFEST_DIR");
+    let out_dir = std::env::var("OUT_DIR").expect("Cargo sets OUT_DIR");
+    cbindgen::generate(&crate_dir)
+        .expect("Unable to generate the C header")
+        .write_to_file(std::path::Path::new(&out_dir).join("passforge.h"));
+}
@@ -0,0 +1,6 @@
+//! Builds the PassForge C ABI as a shared and a static library.
+//!
+//! The functions are defined in the `ffi` module of `passforge` and declared in
+//! `include/passforge.h`; this crate only links them into `libpassforge_ffi`.
+
+pub use passforge::ffi::*;
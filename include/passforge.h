@@ -0,0 +1,226 @@
+/* PassForge C API. See src/ffi.rs for the ownership rules. */
+
+#ifndef PASSFORGE_H
+#define PASSFORGE_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The result of a fallible function. Every value other than `Ok` is an error.
+typedef enum passforge_error_code {
+  // The call succeeded.
+  PASSFORGE_ERROR_CODE_OK = 0,
+  // A required pointer argument was null.
+  PASSFORGE_ERROR_CODE_NULL_POINTER = 1,
+  // A string argument was not valid UTF-8.
+  PASSFORGE_ERROR_CODE_INVALID_UTF8 = 2,
+  // Reading a file failed.
+  PASSFORGE_ERROR_CODE_IO = 3,
+  // The password length is invalid.
+  PASSFORGE_ERROR_CODE_INVALID_LENGTH = 4,
+  // The passphrase word count is invalid.
+  PASSFORGE_ERROR_CODE_INVALID_WORD_COUNT = 5,
+  // The number of items to generate is invalid.
+  PASSFORGE_ERROR_CODE_INVALID_GEN_AMOUNT = 6,
+  // The configuration is invalid.
+  PASSFORGE_ERROR_CODE_INVALID_CONFIG = 7,
+  // The word list could not be loaded or has no words.
+  PASSFORGE_ERROR_CODE_WORD_LIST = 8,
+  // A dice roll is invalid.
+  PASSFORGE_ERROR_CODE_INVALID_DICE_ROLL = 9,
+  // Strength evaluation failed.
+  PASSFORGE_ERROR_CODE_STRENGTH_EVALUATION = 10,
+  // A number could not be parsed.
+  PASSFORGE_ERROR_CODE_PARSE = 11,
+  // Random number generation failed.
+  PASSFORGE_ERROR_CODE_RANDOM = 12,
+  // An unexpected error occurred.
+  PASSFORGE_ERROR_CODE_UNKNOWN = 13,
+  // The library panicked. This is a bug, and the call had no effect.
+  PASSFORGE_ERROR_CODE_PANIC = 14,
+  // A token does not match its configuration or checksum.
+  PASSFORGE_ERROR_CODE_INVALID_TOKEN = 15,
+} passforge_error_code;
+
+// The HMAC hash function used to compute one-time passwords.
+typedef struct OtpAlgorithm OtpAlgorithm;
+
+// Represents the configuration options for passphrase generation.
+typedef struct passforge_passphrase_config passforge_passphrase_config;
+
+// Represents the configuration options for password generation.
+typedef struct passforge_password_config passforge_password_config;
+
+// The text encoding of the random bytes in a token. Padding is never written, since the
+// number of bytes is fixed by the configuration.
+typedef struct TokenEncoding TokenEncoding;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Creates a password configuration with a fixed length and lowercase letters, optionally
+// with capitals, numbers and symbols.
+//
+// # Returns
+//
+// Returns a handle to release with `passforge_password_config_free`.
+struct passforge_password_config *passforge_password_config_new(size_t length,
+                                                                bool capitals,
+                                                                bool numbers,
+                                                                bool symbols);
+
+// Makes the configuration pick a random length between `min` and `max`, inclusive.
+//
+// # Errors
+//
+// Returns `InvalidLength` if `max` is smaller than `min`.
+//
+// # Safety
+//
+// `config` must be a handle from `passforge_password_config_new` that has not been freed.
+enum passforge_error_code passforge_password_config_set_length_range(struct passforge_password_config *config,
+                                                                     size_t min,
+                                                                     size_t max);
+
+// Sets the characters that never appear in generated passwords.
+//
+// # Safety
+//
+// `config` must be a handle from `passforge_password_config_new` that has not been freed,
+// and `chars` a NUL-terminated string.
+enum passforge_error_code passforge_password_config_set_excluded_chars(struct passforge_password_config *config,
+                                                                       const char *chars);
+
+// Releases a password configuration. Does nothing if `config` is null.
+//
+// # Safety
+//
+// `config` must be null or a handle from `passforge_password_config_new` that has not been
+// freed.
+void passforge_password_config_free(struct passforge_password_config *config);
+
+// Generates a password and stores it in `out`.
+//
+// # Errors
+//
+// Returns an error code if the configuration is invalid, and leaves `out` unchanged.
+//
+// # Safety
+//
+// `config` must be a handle from `passforge_password_config_new` that has not been freed,
+// and `out` valid for writing a pointer. The password must be released with
+// `passforge_string_free`.
+enum passforge_error_code passforge_password_generate(const struct passforge_password_config *config,
+                                                      char **out);
+
+// Creates a passphrase configuration using the default word list.
+//
+// # Returns
+//
+// Returns a handle to release with `passforge_passphrase_config_free`, or null if
+// `separator` is null or not UTF-8.
+//
+// # Safety
+//
+// `separator` must be a NUL-terminated string.
+struct passforge_passphrase_config *passforge_passphrase_config_new(size_t words,
+                                                                    const char *separator);
+
+// Loads a word list file for the configuration, with one word or a dice roll followed by a
+// word per line. The file is read once, by this call.
+//
+// # Errors
+//
+// Returns `Io` if the file cannot be read, or `WordList` if it has no words.
+//
+// # Safety
+//
+// `config` must be a handle from `passforge_passphrase_config_new` that has not been freed,
+// and `path` a NUL-terminated string.
+enum passforge_error_code passforge_passphrase_config_set_word_list_file(struct passforge_passphrase_config *config,
+                                                                         const char *path);
+
+// Selects a bundled word list by name, such as `default`.
+//
+// # Errors
+//
+// Returns `WordList` if the name is unknown.
+//
+// # Safety
+//
+// `config` must be a handle from `passforge_passphrase_config_new` that has not been freed,
+// and `name` a NUL-terminated string.
+enum passforge_error_code passforge_passphrase_config_set_word_list_name(struct passforge_passphrase_config *config,
+                                                                         const char *name);
+
+// Releases a passphrase configuration. Does nothing if `config` is null.
+//
+// # Safety
+//
+// `config` must be null or a handle from `passforge_passphrase_config_new` that has not been
+// freed.
+void passforge_passphrase_config_free(struct passforge_passphrase_config *config);
+
+// Generates a passphrase and stores it in `out`.
+//
+// # Errors
+//
+// Returns an error code if the configuration is invalid, and leaves `out` unchanged.
+//
+// # Safety
+//
+// `config` must be a handle from `passforge_passphrase_config_new` that has not been freed,
+// and `out` valid for writing a pointer. The passphrase must be released with
+// `passforge_string_free`.
+enum passforge_error_code passforge_passphrase_generate(const struct passforge_passphrase_config *config,
+                                                        char **out);
+
+// Evaluates the strength of a password with zxcvbn and stores a description of the score
+// and crack time in `out`.
+//
+// # Errors
+//
+// Returns `InvalidLength` if the password is empty.
+//
+// # Safety
+//
+// `password` must be a NUL-terminated string, and `out` valid for writing a pointer. The
+// description must be released with `passforge_string_free`.
+enum passforge_error_code passforge_evaluate_strength(const char *password, char **out);
+
+// Checks whether a password reaches a zxcvbn score of at least 3, storing the answer in
+// `passes`.
+//
+// # Errors
+//
+// Returns `InvalidLength` if the password is empty.
+//
+// # Safety
+//
+// `password` must be a NUL-terminated string, and `passes` valid for writing a `bool`.
+enum passforge_error_code passforge_passes_threshold(const char *password, bool *passes);
+
+// Wipes and releases a string returned by this library. Does nothing if `value` is null.
+//
+// # Safety
+//
+// `value` must be null or a string returned through an out parameter of this library that
+// has not been freed.
+void passforge_string_free(char *value);
+
+// Returns a description of the last error on the calling thread, or null if the last call
+// succeeded. The message stays valid until the next call into this library on the thread,
+// and must not be freed.
+const char *passforge_last_error_message(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* PASSFORGE_H */
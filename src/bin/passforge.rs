@@ -1,17 +1,29 @@
-
 //! PassForge CLI
 //!
 //! This is the main entry point for the PassForge command-line interface.
 //! It parses command-line arguments and calls the appropriate PassForge
 //! library functions to generate passwords or passphrases.
 
-use std::{fmt::Display, path::PathBuf, process};
+use std::{
+    fmt::Display,
+    io::{self, BufRead, IsTerminal, Write},
+    path::PathBuf,
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
+#[cfg(feature = "parallel")]
+use passforge::ParallelGenerator;
 use passforge::{
-    config::{ConfigPreset, PassphraseConfigBuilder, PasswordConfigBuilder},
-    Generator, Length, PassForgeError, PassphraseConfig, PassphraseGenerator, PasswordConfig,
-    PasswordGenerator, StrengthEvaluator, WordList, ZxcvbnAnalysis,
+    config::{ClassCount, ConfigPreset, PassphraseConfigBuilder, PasswordConfigBuilder},
+    hotp_code, otpauth_uri, totp_code, validate_token, Capitalization, DerivedConfig,
+    DerivedGenerator, DerivedTemplate, Generator, Insertion, Length, MaskConfig, MaskGenerator,
+    MaskSyntax, OtpAlgorithm, OtpConfig, OtpGenerator, PassForgeError, PassphraseConfig,
+    PassphraseGenerator, PasswordConfig, PasswordGenerator, PasswordPolicy, PinConfig,
+    PinGenerator, PronounceableConfig, PronounceableGenerator, RecoveryCodesConfig,
+    RecoveryCodesGenerator, Sampling, SecretString, StrengthEvaluator, TokenConfig, TokenEncoding,
+    TokenGenerator, WordList, WordListReport, ZxcvbnAnalysis,
 };
 
 /// CLI argument structure
@@ -37,6 +49,10 @@ struct Cli {
     #[arg(short, long, default_value_t = 1)]
     count: usize,
 
+    /// Exclude lowercase letters from the password
+    #[arg(long = "no-lowercase")]
+    no_lowercase: bool,
+
     /// Exclude uppercase letters from the password
     #[arg(short = 'u', long = "no-capitals", alias = "nc")]
     no_capitals: bool,
@@ -49,6 +65,72 @@ struct Cli {
     #[arg(short = 's', long = "no-symbols", alias = "ns")]
     no_symbols: bool,
 
+    /// Minimum number of lowercase letters in the password
+    #[arg(long = "min-lowercase", value_name = "N", default_value_t = 0)]
+    min_lowercase: usize,
+
+    /// Minimum number of uppercase letters in the password
+    #[arg(
+        long = "min-uppercase",
+        alias = "min-capitals",
+        value_name = "N",
+        default_value_t = 0
+    )]
+    min_uppercase: usize,
+
+    /// Minimum number of digits in the password
+    #[arg(
+        long = "min-digits",
+        alias = "min-numbers",
+        value_name = "N",
+        default_value_t = 0
+    )]
+    min_digits: usize,
+
+    /// Minimum number of symbols in the password
+    #[arg(long = "min-symbols", value_name = "N", default_value_t = 0)]
+    min_symbols: usize,
+
+    /// Maximum number of lowercase letters in the password
+    #[arg(long = "max-lowercase", value_name = "N")]
+    max_lowercase: Option<usize>,
+
+    /// Maximum number of uppercase letters in the password
+    #[arg(long = "max-uppercase", alias = "max-capitals", value_name = "N")]
+    max_uppercase: Option<usize>,
+
+    /// Maximum number of digits in the password
+    #[arg(long = "max-digits", alias = "max-numbers", value_name = "N")]
+    max_digits: Option<usize>,
+
+    /// Maximum number of symbols in the password
+    #[arg(long = "max-symbols", value_name = "N")]
+    max_symbols: Option<usize>,
+
+    /// Only draw password characters from this set, e.g. "ABCDEF0123456789"
+    #[arg(long = "charset", value_name = "CHARS")]
+    charset: Option<String>,
+
+    /// Symbols to use instead of the default symbol set, e.g. "!@#$"
+    #[arg(long = "symbols-set", value_name = "CHARS", conflicts_with = "charset")]
+    symbols_set: Option<String>,
+
+    /// Characters that must never appear in the password
+    #[arg(long = "exclude-chars", value_name = "CHARS")]
+    exclude_chars: Option<String>,
+
+    /// Exclude visually ambiguous characters such as l, 1, I, O, 0 and |
+    #[arg(long = "no-ambiguous", alias = "unambiguous")]
+    no_ambiguous: bool,
+
+    /// Maximum number of times the same character may appear in a row
+    #[arg(long = "max-consecutive", value_name = "N")]
+    max_consecutive: Option<usize>,
+
+    /// Make the password start with a letter
+    #[arg(long = "start-with-letter")]
+    start_with_letter: bool,
+
     /// Generate passphrase instead (Supports -c/--count -w/--words, --seperator --word-list and --evaluate)
     #[arg(short = 'p', long)]
     passphrase: bool,
@@ -65,6 +147,198 @@ struct Cli {
     #[arg(long = "word-list", value_name = "FILE")]
     word_list: Option<PathBuf>,
 
+    /// Name of a bundled word list for passphrase generation. Choices: default, eff-large
+    #[arg(
+        long = "word-list-name",
+        value_name = "NAME",
+        conflicts_with = "word_list"
+    )]
+    word_list_name: Option<String>,
+
+    /// Allow words to repeat in the passphrase, drawing every word from the whole list
+    /// (only applicable with --passphrase)
+    #[arg(long = "with-replacement")]
+    with_replacement: bool,
+
+    /// Capitalize the passphrase words (only applicable with --passphrase and --dice).
+    /// Choices: none, first-letter, random-word, all. Default = none
+    #[arg(long = "capitalize", value_name = "MODE")]
+    capitalize: Option<String>,
+
+    /// Number of random digits to insert into the passphrase (only applicable with --passphrase)
+    #[arg(long = "add-digits", value_name = "N", default_value_t = 0)]
+    add_digits: usize,
+
+    /// Number of random symbols to insert into the passphrase (only applicable with --passphrase)
+    #[arg(long = "add-symbols", value_name = "N", default_value_t = 0)]
+    add_symbols: usize,
+
+    /// Symbols to insert with --add-symbols
+    #[arg(long = "insert-symbols-set", value_name = "CHARS", default_value = PassphraseConfig::DEFAULT_SYMBOL_SET)]
+    insert_symbols_set: String,
+
+    /// Where digits and symbols are inserted into the passphrase.
+    /// Choices: anywhere (inside words too), tokens (between words). Default = anywhere
+    #[arg(long = "insert", value_name = "MODE")]
+    insert: Option<String>,
+
+    /// Separate the passphrase words with a random character from this set per gap,
+    /// instead of --separator
+    #[arg(
+        long = "random-separators",
+        value_name = "CHARS",
+        allow_hyphen_values = true
+    )]
+    random_separators: Option<String>,
+
+    /// Build the passphrase from physical dice rolls, one roll of five dice per word,
+    /// e.g. "16655 15143 ...". Prompts for the rolls when no value is given
+    /// (Supports -w/--words, --separator, --word-list and --evaluate-strength)
+    #[arg(
+        long = "dice",
+        value_name = "ROLLS",
+        num_args = 0..=1,
+        default_missing_value = "",
+        conflicts_with_all = ["count", "preset", "show_entropy", "pronounceable", "pin", "mask", "rules", "token", "recovery_codes"]
+    )]
+    dice: Option<String>,
+
+    /// Generate a pronounceable password instead (Supports -c/--count, -l/--length,
+    /// --max-length, --capitals, --numbers, --no-capitals, --no-numbers and --evaluate-strength)
+    #[arg(
+        long = "pronounceable",
+        conflicts_with_all = ["passphrase", "mask", "min_uppercase", "min_digits"]
+    )]
+    pronounceable: bool,
+
+    /// Exact number of capitals in a pronounceable password. Default = 1
+    #[arg(
+        long = "capitals",
+        value_name = "N",
+        requires = "pronounceable",
+        conflicts_with = "no_capitals"
+    )]
+    capitals: Option<usize>,
+
+    /// Exact number of digits in a pronounceable password. Default = 1
+    #[arg(
+        long = "numbers",
+        value_name = "N",
+        requires = "pronounceable",
+        conflicts_with = "no_numbers"
+    )]
+    numbers: Option<usize>,
+
+    /// Generate a numeric PIN with the given number of digits instead, rejecting weak patterns
+    /// such as 1111, 1234, 1212, dates and common PINs (Supports -c/--count). Default = 6
+    #[arg(
+        long = "pin",
+        value_name = "DIGITS",
+        num_args = 0..=1,
+        default_missing_value = "6",
+        conflicts_with_all = ["passphrase", "pronounceable", "mask"]
+    )]
+    pin: Option<usize>,
+
+    /// Allow weak PINs (only applicable with --pin)
+    #[arg(long = "allow-weak-pins", requires = "pin")]
+    allow_weak_pins: bool,
+
+    /// Generate a password from a hashcat-style mask instead, e.g. "?u?l?l?l-?d?d?d?d"
+    /// (Supports -c/--count, -1/-2/-3/-4, --template and --evaluate-strength)
+    #[arg(long = "mask", value_name = "MASK", conflicts_with = "passphrase")]
+    mask: Option<String>,
+
+    /// Read the mask as a template where A is an uppercase letter, a a lowercase letter
+    /// and 9 a digit, e.g. "Aaaa-9999"
+    #[arg(
+        long = "template",
+        requires = "mask",
+        conflicts_with_all = ["custom_charset1", "custom_charset2", "custom_charset3", "custom_charset4"]
+    )]
+    template: bool,
+
+    /// Custom charset referenced by ?1 in the mask
+    #[arg(short = '1', long = "custom-charset1", value_name = "CHARS")]
+    custom_charset1: Option<String>,
+
+    /// Custom charset referenced by ?2 in the mask
+    #[arg(short = '2', long = "custom-charset2", value_name = "CHARS")]
+    custom_charset2: Option<String>,
+
+    /// Custom charset referenced by ?3 in the mask
+    #[arg(short = '3', long = "custom-charset3", value_name = "CHARS")]
+    custom_charset3: Option<String>,
+
+    /// Custom charset referenced by ?4 in the mask
+    #[arg(short = '4', long = "custom-charset4", value_name = "CHARS")]
+    custom_charset4: Option<String>,
+
+    /// Generate a password following a site's passwordrules, e.g.
+    /// "minlength: 20; required: lower; required: upper; allowed: [-_]"
+    /// (Supports -c/--count and --evaluate-strength)
+    #[arg(
+        long = "rules",
+        value_name = "RULES",
+        conflicts_with_all = ["passphrase", "pronounceable", "pin", "mask", "preset"]
+    )]
+    rules: Option<String>,
+
+    /// Generate an API token or raw key from the given number of random bytes instead
+    /// (Supports -c/--count, --encoding, --prefix and --checksum). Default = 32
+    #[arg(
+        long = "token",
+        value_name = "BYTES",
+        num_args = 0..=1,
+        default_missing_value = "32",
+        conflicts_with_all = ["passphrase", "pronounceable", "pin", "mask", "rules", "preset"]
+    )]
+    token: Option<usize>,
+
+    /// Encoding of the token bytes (only applicable with --token).
+    /// Choices: hex, base32, crockford, base64, base64url, base58. Default = base64url
+    #[arg(long = "encoding", value_name = "ENCODING", requires = "token")]
+    encoding: Option<String>,
+
+    /// Text placed before the token to identify it, e.g. "pf_live_" (only applicable with --token)
+    #[arg(long = "prefix", value_name = "PREFIX", requires = "token")]
+    prefix: Option<String>,
+
+    /// Append a CRC32 checksum to the token so secret scanners can recognise it
+    /// (only applicable with --token)
+    #[arg(long = "checksum", requires = "token")]
+    checksum: bool,
+
+    /// Generate a set of distinct one-time recovery codes such as "7kq4-m2xz" instead
+    /// (Supports --groups, --group-length, --charset, --separator, --hash-codes and
+    /// --show-entropy). Default = 10
+    #[arg(
+        long = "recovery-codes",
+        value_name = "COUNT",
+        num_args = 0..=1,
+        default_missing_value = "10",
+        conflicts_with_all = ["count", "passphrase", "pronounceable", "pin", "mask", "rules", "token", "preset", "evaluate_strength"]
+    )]
+    recovery_codes: Option<usize>,
+
+    /// Number of groups in a recovery code (only applicable with --recovery-codes). Default = 2
+    #[arg(long = "groups", value_name = "N", requires = "recovery_codes")]
+    groups: Option<usize>,
+
+    /// Number of characters in a group of a recovery code (only applicable with
+    /// --recovery-codes). Default = 4
+    #[arg(long = "group-length", value_name = "N", requires = "recovery_codes")]
+    group_length: Option<usize>,
+
+    /// Print a salted SHA-256 hash after each recovery code, separated by a tab, for storing
+    /// on the server (only applicable with --recovery-codes)
+    #[arg(long = "hash-codes", requires = "recovery_codes")]
+    hash_codes: bool,
+
+    /// Show the entropy and keyspace of the configuration after the generated output
+    #[arg(long = "show-entropy")]
+    show_entropy: bool,
+
     /// Show password strength evaluation
     #[arg(short = 'e', long = "evaluate-strength")]
     evaluate_strength: bool,
@@ -73,6 +347,220 @@ struct Cli {
     /// -e/--evaluate-strength. Choices: Weak, Average, Strong
     #[arg(long = "preset")]
     preset: Option<String>,
+
+    /// Generate passwords or passphrases on N threads, 0 for one per core. The output does
+    /// not depend on the number of threads
+    #[cfg(feature = "parallel")]
+    #[arg(
+        long = "threads",
+        value_name = "N",
+        conflicts_with_all = ["dice", "pronounceable", "pin", "mask", "rules", "token", "recovery_codes"]
+    )]
+    threads: Option<usize>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Subcommands that do not generate passwords
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Work with passphrase word lists
+    Wordlist {
+        #[command(subcommand)]
+        action: WordListAction,
+    },
+    /// Work with API tokens
+    Token {
+        #[command(subcommand)]
+        action: TokenAction,
+    },
+    /// Work with HOTP/TOTP one-time password secrets
+    Otp {
+        #[command(subcommand)]
+        action: OtpAction,
+    },
+    /// Derive the same password for a site every time from a master secret, prompted for
+    /// without echo (or read from standard input when it is not a terminal)
+    Derive {
+        /// The site the password is for, e.g. example.com. Used exactly as given
+        site: String,
+
+        /// The login on the site
+        #[arg(long = "login", value_name = "LOGIN", default_value = "")]
+        login: String,
+
+        /// Increment to get a new password for the same site and login. Default = 1
+        #[arg(long = "counter", default_value_t = DerivedConfig::DEFAULT_COUNTER)]
+        counter: u32,
+
+        #[command(flatten)]
+        template: DerivedTemplateArgs,
+    },
+}
+
+/// Word list subcommands
+#[derive(Subcommand, Debug)]
+enum WordListAction {
+    /// Report skipped lines, duplicates, ambiguous words, word lengths and entropy of a word
+    /// list, failing when lines are skipped or repeated
+    Check {
+        /// Path to the word list file
+        file: PathBuf,
+
+        /// Also fail when a word is a prefix or suffix of another word
+        #[arg(long = "require-unambiguous")]
+        require_unambiguous: bool,
+
+        /// Also fail when each word adds fewer than this many bits of entropy
+        #[arg(long = "min-entropy", value_name = "BITS")]
+        min_entropy: Option<f64>,
+    },
+}
+
+/// Token subcommands
+#[derive(Subcommand, Debug)]
+enum TokenAction {
+    /// Check that a token has the prefix, encoding, number of bytes and checksum of a token
+    /// configuration, failing when it does not
+    Check {
+        /// The token to check. Read from standard input when not given, which keeps it out
+        /// of the shell history
+        token: Option<String>,
+
+        /// Number of random bytes in the token. Default = 32
+        #[arg(long = "bytes", default_value_t = TokenConfig::DEFAULT_BYTES)]
+        bytes: usize,
+
+        /// Encoding of the token bytes. Choices: hex, base32, crockford, base64, base64url,
+        /// base58. Default = base64url
+        #[arg(long = "encoding", value_name = "ENCODING")]
+        encoding: Option<String>,
+
+        /// Text the token starts with
+        #[arg(long = "prefix", value_name = "PREFIX")]
+        prefix: Option<String>,
+
+        /// The token ends with a CRC32 checksum
+        #[arg(long = "checksum")]
+        checksum: bool,
+    },
+}
+
+/// OTP subcommands
+#[derive(Subcommand, Debug)]
+enum OtpAction {
+    /// Generate a base32 secret and print it with its otpauth:// provisioning URI
+    New {
+        /// Account name shown in the authenticator app, such as a user or service account
+        #[arg(long = "account", value_name = "NAME")]
+        account: String,
+
+        /// Service the account belongs to
+        #[arg(long = "issuer", value_name = "NAME", default_value = "")]
+        issuer: String,
+
+        /// Number of random bytes in the secret. Default = 20
+        #[arg(long = "bytes", default_value_t = OtpConfig::DEFAULT_SECRET_BYTES)]
+        bytes: usize,
+
+        #[command(flatten)]
+        code: OtpCodeArgs,
+    },
+    /// Print the code for a base32 secret, at the current time unless --time or --counter
+    /// is given
+    Code {
+        /// The base32 secret. Read from standard input when not given, which keeps it out of
+        /// the shell history
+        secret: Option<String>,
+
+        /// Compute the TOTP code at this Unix time instead of now
+        #[arg(long = "time", value_name = "SECONDS", conflicts_with = "counter")]
+        time: Option<u64>,
+
+        /// Compute the HOTP code for this counter instead of a TOTP code
+        #[arg(long = "counter")]
+        counter: Option<u64>,
+
+        #[command(flatten)]
+        code: OtpCodeArgs,
+    },
+}
+
+/// Code parameters shared by the OTP subcommands
+#[derive(Args, Debug)]
+struct OtpCodeArgs {
+    /// Number of digits in a code, from 6 to 8. Default = 6
+    #[arg(long = "digits", default_value_t = OtpConfig::DEFAULT_DIGITS)]
+    digits: u32,
+
+    /// TOTP time step in seconds. Default = 30
+    #[arg(long = "period", value_name = "SECONDS", default_value_t = OtpConfig::DEFAULT_PERIOD)]
+    period: u64,
+
+    /// HMAC algorithm. Choices: sha1, sha256, sha512. Default = sha1
+    #[arg(long = "algorithm", value_name = "ALGORITHM")]
+    algorithm: Option<String>,
+}
+
+/// What the derive subcommand renders
+#[derive(Args, Debug)]
+struct DerivedTemplateArgs {
+    /// Length of the derived password. Default = 18
+    #[arg(short = 'l', long = "length", default_value_t = DerivedConfig::DEFAULT_LENGTH)]
+    length: usize,
+
+    /// Exclude uppercase letters from the derived password
+    #[arg(long = "no-capitals")]
+    no_capitals: bool,
+
+    /// Exclude numbers from the derived password
+    #[arg(long = "no-numbers")]
+    no_numbers: bool,
+
+    /// Exclude symbols from the derived password
+    #[arg(long = "no-symbols")]
+    no_symbols: bool,
+
+    /// Characters that must never appear in the derived password
+    #[arg(long = "exclude-chars", value_name = "CHARS")]
+    exclude_chars: Option<String>,
+
+    /// Exclude visually ambiguous characters such as l, 1, I, O, 0 and |
+    #[arg(long = "no-ambiguous")]
+    no_ambiguous: bool,
+
+    /// Minimum number of lowercase letters in the derived password. Default = 1
+    #[arg(long = "min-lowercase", value_name = "N")]
+    min_lowercase: Option<usize>,
+
+    /// Minimum number of uppercase letters in the derived password. Default = 1
+    #[arg(
+        long = "min-uppercase",
+        value_name = "N",
+        conflicts_with = "no_capitals"
+    )]
+    min_uppercase: Option<usize>,
+
+    /// Minimum number of digits in the derived password. Default = 1
+    #[arg(long = "min-digits", value_name = "N", conflicts_with = "no_numbers")]
+    min_digits: Option<usize>,
+
+    /// Minimum number of symbols in the derived password. Default = 1
+    #[arg(long = "min-symbols", value_name = "N", conflicts_with = "no_symbols")]
+    min_symbols: Option<usize>,
+
+    /// Derive a passphrase instead
+    #[arg(short = 'p', long = "passphrase")]
+    passphrase: bool,
+
+    /// Number of words in the derived passphrase. Default = 6
+    #[arg(short = 'w', long = "words", default_value_t = DerivedConfig::DEFAULT_WORDS)]
+    words: usize,
+
+    /// Separator for words in the derived passphrase
+    #[arg(long = "separator", default_value = DerivedConfig::DEFAULT_SEPARATOR)]
+    separator: String,
 }
 
 // Helper functions
@@ -87,6 +575,102 @@ fn parse_preset(preset_str: &str) -> Result<ConfigPreset, PassForgeError> {
     }
 }
 
+fn parse_capitalization(mode: &str) -> Result<Capitalization, PassForgeError> {
+    match mode.to_lowercase().as_str() {
+        "none" => Ok(Capitalization::None),
+        "first-letter" => Ok(Capitalization::FirstLetter),
+        "random-word" => Ok(Capitalization::RandomWord),
+        "all" => Ok(Capitalization::All),
+        _ => Err(PassForgeError::InvalidConfig(
+            "Invalid capitalization. Choices are: none, first-letter, random-word, all".into(),
+        )),
+    }
+}
+
+fn parse_insertion(mode: &str) -> Result<Insertion, PassForgeError> {
+    match mode.to_lowercase().as_str() {
+        "anywhere" => Ok(Insertion::Anywhere),
+        "tokens" => Ok(Insertion::Tokens),
+        _ => Err(PassForgeError::InvalidConfig(
+            "Invalid insertion mode. Choices are: anywhere, tokens".into(),
+        )),
+    }
+}
+
+fn parse_word_list(path: Option<PathBuf>, name: Option<&str>) -> Result<WordList, PassForgeError> {
+    match (path, name) {
+        (Some(path), _) => Ok(WordList::Custom(path)),
+        (None, Some(name)) => WordList::from_name(name),
+        (None, None) => Ok(WordList::Default),
+    }
+}
+
+fn parse_token_config(
+    bytes: usize,
+    encoding: Option<&str>,
+    prefix: Option<String>,
+    checksum: bool,
+) -> Result<TokenConfig, PassForgeError> {
+    let mut builder = TokenConfig::builder().bytes(bytes).checksum(checksum);
+    if let Some(encoding) = encoding {
+        builder = builder.encoding(TokenEncoding::from_name(encoding)?);
+    }
+    if let Some(prefix) = prefix {
+        builder = builder.prefix(prefix);
+    }
+    Ok(builder.build())
+}
+
+fn parse_otp_config(bytes: usize, code: OtpCodeArgs) -> Result<OtpConfig, PassForgeError> {
+    let mut builder = OtpConfig::builder()
+        .secret_bytes(bytes)
+        .digits(code.digits)
+        .period(code.period);
+    if let Some(algorithm) = code.algorithm {
+        builder = builder.algorithm(OtpAlgorithm::from_name(&algorithm)?);
+    }
+    Ok(builder.build())
+}
+
+fn parse_derived_template(args: DerivedTemplateArgs) -> Result<DerivedTemplate, PassForgeError> {
+    if args.passphrase {
+        return Ok(DerivedTemplate::Passphrase {
+            words: args.words,
+            separator: args.separator,
+        });
+    }
+    // Every enabled class needs one character unless set otherwise, which renders the same
+    // as `DerivedTemplate::Password`.
+    let min_of = |enabled: bool, min: Option<usize>| if enabled { min.unwrap_or(1) } else { 0 };
+    let config = PasswordConfig::builder()
+        .length(Length::Single(args.length))
+        .capitals(!args.no_capitals)
+        .numbers(!args.no_numbers)
+        .symbols(!args.no_symbols)
+        .exclude_chars(args.exclude_chars.unwrap_or_default())
+        .unambiguous(args.no_ambiguous)
+        .min_lowercase(min_of(true, args.min_lowercase))
+        .min_capitals(min_of(!args.no_capitals, args.min_uppercase))
+        .min_numbers(min_of(!args.no_numbers, args.min_digits))
+        .min_symbols(min_of(!args.no_symbols, args.min_symbols))
+        .build();
+    DerivedTemplate::from_password_config(&config)
+}
+
+/// Reads one line from standard input, without the line ending. Other whitespace is kept, as
+/// it is when the secret is typed at the prompt.
+fn read_secret_line() -> Result<SecretString, PassForgeError> {
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+    Ok(SecretString::new(line))
+}
+
 fn parse_length(min: usize, max: Option<usize>) -> Result<Length, PassForgeError> {
     match max {
         Some(max) if max > min => Ok(Length::Range(min..=max)),
@@ -105,23 +689,59 @@ fn gen_password(input: Cli) -> Result<(), PassForgeError> {
         PasswordConfigBuilder::default().build_from_preset(preset)
     } else {
         let length = parse_length(input.min_length, input.max_length)?;
-        PasswordConfig::new(
+        let mut config = PasswordConfig::new(
             length,
             !input.no_capitals,
             !input.no_numbers,
             !input.no_symbols,
-        ) 
+        );
+        config.lowercase = !input.no_lowercase;
+        config.lowercase_count = ClassCount::new(input.min_lowercase, input.max_lowercase);
+        config.capitals_count = ClassCount::new(input.min_uppercase, input.max_uppercase);
+        config.numbers_count = ClassCount::new(input.min_digits, input.max_digits);
+        config.symbols_count = ClassCount::new(input.min_symbols, input.max_symbols);
+        config.charset = input.charset;
+        config.symbol_set = input.symbols_set;
+        config.excluded_chars = input.exclude_chars.unwrap_or_default();
+        config.unambiguous = input.no_ambiguous;
+        config.max_consecutive = input.max_consecutive;
+        config.start_with_letter = input.start_with_letter;
+        config
     };
 
     let generator = PasswordGenerator;
     let strength_evaluator = ZxcvbnAnalysis;
+    #[cfg(feature = "parallel")]
+    if let Some(threads) = input.threads {
+        generate_items_par(
+            &generator,
+            &config,
+            input.count,
+            threads,
+            input.evaluate_strength,
+            &strength_evaluator,
+        )?;
+    } else {
+        generate_items(
+            &generator,
+            &config,
+            input.count,
+            input.evaluate_strength,
+            &strength_evaluator,
+        )?;
+    }
+    #[cfg(not(feature = "parallel"))]
     generate_items(
         &generator,
         &config,
         input.count,
         input.evaluate_strength,
         &strength_evaluator,
-    )
+    )?;
+    if input.show_entropy {
+        print_entropy(config.entropy_bits()?, config.keyspace()?);
+    }
+    Ok(())
 }
 
 fn gen_passphrase(input: Cli) -> Result<(), PassForgeError> {
@@ -129,22 +749,382 @@ fn gen_passphrase(input: Cli) -> Result<(), PassForgeError> {
         let preset = parse_preset(&preset_str)?;
         PassphraseConfigBuilder::default().build_from_preset(preset)
     } else {
-        let word_list = match input.word_list {
-            Some(path) => WordList::Custom(path),
-            None => WordList::Default,
-        };
-        PassphraseConfig::new(input.words, input.separator, word_list)
+        let word_list = parse_word_list(input.word_list, input.word_list_name.as_deref())?;
+        let mut config = PassphraseConfig::new(input.words, input.separator, word_list);
+        if input.with_replacement {
+            config.sampling = Sampling::WithReplacement;
+        }
+        if let Some(mode) = &input.capitalize {
+            config.capitalization = parse_capitalization(mode)?;
+        }
+        if let Some(mode) = &input.insert {
+            config.insertion = parse_insertion(mode)?;
+        }
+        config.digits = input.add_digits;
+        config.symbols = input.add_symbols;
+        config.symbol_set = input.insert_symbols_set;
+        config.separators = input.random_separators;
+        config
     };
 
     let generator = PassphraseGenerator;
     let strength_evaluator = ZxcvbnAnalysis;
+    #[cfg(feature = "parallel")]
+    if let Some(threads) = input.threads {
+        generate_items_par(
+            &generator,
+            &config,
+            input.count,
+            threads,
+            input.evaluate_strength,
+            &strength_evaluator,
+        )?;
+    } else {
+        generate_items(
+            &generator,
+            &config,
+            input.count,
+            input.evaluate_strength,
+            &strength_evaluator,
+        )?;
+    }
+    #[cfg(not(feature = "parallel"))]
+    generate_items(
+        &generator,
+        &config,
+        input.count,
+        input.evaluate_strength,
+        &strength_evaluator,
+    )?;
+    if input.show_entropy {
+        print_entropy(config.entropy_bits()?, config.keyspace()?);
+    }
+    Ok(())
+}
+
+fn gen_dice(input: Cli, rolls: String) -> Result<(), PassForgeError> {
+    let word_list = parse_word_list(input.word_list, input.word_list_name.as_deref())?;
+    let mut config = PassphraseConfig::new(input.words, input.separator, word_list);
+    if let Some(mode) = &input.capitalize {
+        config.capitalization = parse_capitalization(mode)?;
+    }
+
+    let mut rolls: Vec<String> = rolls.split_whitespace().map(String::from).collect();
+    if rolls.is_empty() {
+        eprintln!(
+            "Roll the dice for each of the {} words and enter the faces in order, e.g. 16655",
+            config.words
+        );
+        let mut lines = io::stdin().lock().lines();
+        while rolls.len() < config.words {
+            eprint!("Word {}: ", rolls.len() + 1);
+            io::stderr().flush()?;
+            let line = lines.next().ok_or_else(|| {
+                PassForgeError::InvalidDiceRoll("Input ended before all rolls were entered".into())
+            })??;
+            rolls.extend(line.split_whitespace().map(String::from));
+        }
+    }
+
+    let passphrase = PassphraseGenerator::from_dice_rolls(&config, &rolls)?;
+    println!("{}", passphrase.expose_secret());
+    if input.evaluate_strength {
+        println!("Strength: {}", ZxcvbnAnalysis::evaluate(&passphrase)?);
+    }
+    Ok(())
+}
+
+fn gen_pronounceable(input: Cli) -> Result<(), PassForgeError> {
+    let length = parse_length(input.min_length, input.max_length)?;
+    let count_or_default = |disabled: bool, requested: Option<usize>, default: usize| {
+        if disabled {
+            0
+        } else {
+            requested.unwrap_or(default)
+        }
+    };
+    let config = PronounceableConfig::new(
+        length,
+        count_or_default(
+            input.no_capitals,
+            input.capitals,
+            PronounceableConfig::DEFAULT_CAPITALS,
+        ),
+        count_or_default(
+            input.no_numbers,
+            input.numbers,
+            PronounceableConfig::DEFAULT_NUMBERS,
+        ),
+    );
+
+    let generator = PronounceableGenerator;
+    let strength_evaluator = ZxcvbnAnalysis;
     generate_items(
         &generator,
         &config,
         input.count,
         input.evaluate_strength,
         &strength_evaluator,
-    )
+    )?;
+    if input.show_entropy {
+        print_entropy(config.entropy_bits()?, None);
+    }
+    Ok(())
+}
+
+fn gen_pin(input: Cli, length: usize) -> Result<(), PassForgeError> {
+    let mut builder = PinConfig::builder().length(length);
+    if input.allow_weak_pins {
+        builder = builder.reject(Vec::new());
+    }
+    let config = builder.build();
+
+    let generator = PinGenerator;
+    let strength_evaluator = ZxcvbnAnalysis;
+    generate_items(
+        &generator,
+        &config,
+        input.count,
+        input.evaluate_strength,
+        &strength_evaluator,
+    )?;
+    if input.show_entropy {
+        print_entropy(config.entropy_bits()?, Some(config.keyspace()?.into()));
+    }
+    Ok(())
+}
+
+fn gen_token(input: Cli, bytes: usize) -> Result<(), PassForgeError> {
+    let config = parse_token_config(
+        bytes,
+        input.encoding.as_deref(),
+        input.prefix,
+        input.checksum,
+    )?;
+
+    let generator = TokenGenerator;
+    let strength_evaluator = ZxcvbnAnalysis;
+    generate_items(
+        &generator,
+        &config,
+        input.count,
+        input.evaluate_strength,
+        &strength_evaluator,
+    )?;
+    if input.show_entropy {
+        print_entropy(config.entropy_bits()?, config.keyspace()?);
+    }
+    Ok(())
+}
+
+fn gen_recovery_codes(input: Cli, count: usize) -> Result<(), PassForgeError> {
+    let mut builder = RecoveryCodesConfig::builder()
+        .count(count)
+        .separator(input.separator)
+        .hashed(input.hash_codes);
+    if let Some(groups) = input.groups {
+        builder = builder.groups(groups);
+    }
+    if let Some(group_length) = input.group_length {
+        builder = builder.group_length(group_length);
+    }
+    if let Some(charset) = input.charset {
+        builder = builder.alphabet(charset);
+    }
+    let config = builder.build();
+
+    let set = RecoveryCodesGenerator::generate(&config)?;
+    let mut stdout = io::BufWriter::new(io::stdout().lock());
+    match &set.hashes {
+        Some(hashes) => {
+            for (code, hash) in set.codes.iter().zip(hashes) {
+                writeln!(stdout, "{}\t{}", code.expose_secret(), hash)?;
+            }
+        }
+        None => {
+            for code in &set.codes {
+                writeln!(stdout, "{}", code.expose_secret())?;
+            }
+        }
+    }
+    stdout.flush()?;
+    if input.show_entropy {
+        print_entropy(config.entropy_bits()?, config.keyspace()?);
+    }
+    Ok(())
+}
+
+fn gen_mask(input: Cli, mask: String) -> Result<(), PassForgeError> {
+    let custom_charsets = [
+        input.custom_charset1,
+        input.custom_charset2,
+        input.custom_charset3,
+        input.custom_charset4,
+    ];
+    let syntax = if input.template {
+        MaskSyntax::Template
+    } else {
+        MaskSyntax::Hashcat
+    };
+    let mut builder = MaskConfig::builder().mask(mask).syntax(syntax);
+    for (index, charset) in custom_charsets.into_iter().enumerate() {
+        if let Some(charset) = charset {
+            builder = builder.custom_charset(index + 1, charset);
+        }
+    }
+    let config = builder.build()?;
+
+    let generator = MaskGenerator;
+    let strength_evaluator = ZxcvbnAnalysis;
+    generate_items(
+        &generator,
+        &config,
+        input.count,
+        input.evaluate_strength,
+        &strength_evaluator,
+    )?;
+    if input.show_entropy {
+        print_entropy(config.entropy_bits(), config.keyspace());
+    }
+    Ok(())
+}
+
+fn gen_from_rules(input: Cli, rules: String) -> Result<(), PassForgeError> {
+    let config = PasswordPolicy::from_rules(&rules)?.to_config()?;
+
+    let generator = PasswordGenerator;
+    let strength_evaluator = ZxcvbnAnalysis;
+    #[cfg(feature = "parallel")]
+    if let Some(threads) = input.threads {
+        generate_items_par(
+            &generator,
+            &config,
+            input.count,
+            threads,
+            input.evaluate_strength,
+            &strength_evaluator,
+        )?;
+    } else {
+        generate_items(
+            &generator,
+            &config,
+            input.count,
+            input.evaluate_strength,
+            &strength_evaluator,
+        )?;
+    }
+    #[cfg(not(feature = "parallel"))]
+    generate_items(
+        &generator,
+        &config,
+        input.count,
+        input.evaluate_strength,
+        &strength_evaluator,
+    )?;
+    if input.show_entropy {
+        print_entropy(config.entropy_bits()?, config.keyspace()?);
+    }
+    Ok(())
+}
+
+fn check_word_list(
+    file: PathBuf,
+    require_unambiguous: bool,
+    min_entropy: Option<f64>,
+) -> Result<(), PassForgeError> {
+    let report = WordListReport::check(&WordList::Custom(file))?;
+    print!("{}", report);
+    let mut failures = Vec::new();
+    if !report.is_clean() {
+        failures.push("the list has skipped lines, duplicates or no words".to_string());
+    }
+    if require_unambiguous && !report.is_unambiguous() {
+        failures.push("words are prefixes or suffixes of other words".to_string());
+    }
+    if let Some(min_entropy) = min_entropy.filter(|min| report.entropy_per_word < *min) {
+        failures.push(format!(
+            "each word adds less than {:.2} bits of entropy",
+            min_entropy
+        ));
+    }
+    if failures.is_empty() {
+        println!("Verdict: PASS");
+        Ok(())
+    } else {
+        println!("Verdict: FAIL");
+        Err(PassForgeError::WordListError(format!(
+            "Check failed: {}",
+            failures.join("; ")
+        )))
+    }
+}
+
+fn check_token(token: Option<String>, config: TokenConfig) -> Result<(), PassForgeError> {
+    let token = match token {
+        Some(token) => SecretString::new(token),
+        None => read_secret_line()?,
+    };
+    match validate_token(&config, token.expose_secret()) {
+        Ok(()) => {
+            println!("Verdict: PASS");
+            Ok(())
+        }
+        Err(e) => {
+            println!("Verdict: FAIL");
+            Err(e)
+        }
+    }
+}
+
+fn new_otp_secret(config: OtpConfig, issuer: &str, account: &str) -> Result<(), PassForgeError> {
+    let secret = OtpGenerator::generate(&config)?;
+    let uri = otpauth_uri(&config, secret.expose_secret(), issuer, account)?;
+    println!("Secret: {}", secret.expose_secret());
+    println!("URI: {}", uri.expose_secret());
+    Ok(())
+}
+
+fn print_otp_code(
+    config: OtpConfig,
+    secret: Option<String>,
+    time: Option<u64>,
+    counter: Option<u64>,
+) -> Result<(), PassForgeError> {
+    let secret = match secret {
+        Some(secret) => SecretString::new(secret),
+        None => read_secret_line()?,
+    };
+    let code = match (counter, time) {
+        (Some(counter), _) => hotp_code(&config, secret.expose_secret(), counter)?,
+        (None, Some(time)) => totp_code(&config, secret.expose_secret(), time)?,
+        (None, None) => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_err(|_| PassForgeError::InvalidConfig("System clock is before 1970".into()))?
+                .as_secs();
+            totp_code(&config, secret.expose_secret(), now)?
+        }
+    };
+    println!("{}", code);
+    Ok(())
+}
+
+fn derive_password(mut config: DerivedConfig) -> Result<(), PassForgeError> {
+    config.master = if io::stdin().is_terminal() {
+        SecretString::new(rpassword::prompt_password("Master secret: ")?)
+    } else {
+        read_secret_line()?
+    };
+    let password = DerivedGenerator::generate(&config)?;
+    println!("{}", password.expose_secret());
+    Ok(())
+}
+
+fn print_entropy(bits: f64, keyspace: Option<u128>) {
+    println!("Entropy: {:.2} bits", bits);
+    if let Some(keyspace) = keyspace {
+        println!("Keyspace: {}", keyspace);
+    }
 }
 
 fn generate_items<G, S>(
@@ -156,43 +1136,159 @@ fn generate_items<G, S>(
 ) -> Result<(), PassForgeError>
 where
     G: Generator,
-    G::Output: Display,
-    S: StrengthEvaluator<Input = String>,
+    G::Output: Into<SecretString>,
+    S: StrengthEvaluator<Input = SecretString>,
     S::Output: Display,
 {
-    let items = match count {
-        0 => {
-            return Err(PassForgeError::InvalidGenAmount(
-                "Count cannot be smaller than 1".into(),
-            ))
-        }
-        1 => vec![G::generate(config)?],
-        _ => G::generate_multiple(config, count)?,
-    };
+    if count == 0 {
+        return Err(PassForgeError::InvalidGenAmount(
+            "Count cannot be smaller than 1".into(),
+        ));
+    }
+    if !evaluate_strength {
+        let mut stdout = io::BufWriter::new(io::stdout().lock());
+        G::generate_into(config, &mut stdout, count)?;
+        stdout.flush()?;
+        return Ok(());
+    }
+
+    for item in G::iter(config)?.take(count) {
+        print_evaluated::<S>(&item?.into());
+    }
 
+    Ok(())
+}
+
+/// Generates the items on a dedicated thread pool before printing them in order.
+#[cfg(feature = "parallel")]
+fn generate_items_par<G, S>(
+    generator: &G,
+    config: &G::Config,
+    count: usize,
+    threads: usize,
+    evaluate_strength: bool,
+    strength_evaluator: &S,
+) -> Result<(), PassForgeError>
+where
+    G: ParallelGenerator,
+    G::Config: Sync,
+    G::Output: Into<SecretString> + Send,
+    S: StrengthEvaluator<Input = SecretString>,
+    S::Output: Display,
+{
+    if count <= 1 {
+        return generate_items(
+            generator,
+            config,
+            count,
+            evaluate_strength,
+            strength_evaluator,
+        );
+    }
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(io::Error::other)?;
+    let items = pool.install(|| G::generate_multiple_par(config, count))?;
+
+    let mut stdout = io::BufWriter::new(io::stdout().lock());
     for item in items {
-        println!("{}", item);
+        let item: SecretString = item.into();
         if evaluate_strength {
-            match item.to_string().parse() {
-                Ok(password) => match S::evaluate(&password) {
-                    Ok(evaluation) => println!("Strength: {}", evaluation),
-                    Err(e) => eprintln!("Error evaluating strength: {}", e),
-                },
-                Err(_) => eprintln!("Unable to evaluate strength for this type of output"),
-            }
+            print_evaluated::<S>(&item);
+        } else {
+            writeln!(stdout, "{}", item.expose_secret())?;
         }
     }
-
+    stdout.flush()?;
     Ok(())
 }
 
-
+/// Prints an item followed by its strength evaluation.
+fn print_evaluated<S>(item: &SecretString)
+where
+    S: StrengthEvaluator<Input = SecretString>,
+    S::Output: Display,
+{
+    println!("{}", item.expose_secret());
+    match S::evaluate(item) {
+        Ok(evaluation) => println!("Strength: {}", evaluation),
+        Err(e) => eprintln!("Error evaluating strength: {}", e),
+    }
+}
 
 fn main() {
     let cli = Cli::parse();
 
-    let result = if cli.passphrase {
+    let result = if let Some(Command::Wordlist { action }) = cli.command {
+        match action {
+            WordListAction::Check {
+                file,
+                require_unambiguous,
+                min_entropy,
+            } => check_word_list(file, require_unambiguous, min_entropy),
+        }
+    } else if let Some(Command::Token { action }) = cli.command {
+        match action {
+            TokenAction::Check {
+                token,
+                bytes,
+                encoding,
+                prefix,
+                checksum,
+            } => parse_token_config(bytes, encoding.as_deref(), prefix, checksum)
+                .and_then(|config| check_token(token, config)),
+        }
+    } else if let Some(Command::Otp { action }) = cli.command {
+        match action {
+            OtpAction::New {
+                account,
+                issuer,
+                bytes,
+                code,
+            } => parse_otp_config(bytes, code)
+                .and_then(|config| new_otp_secret(config, &issuer, &account)),
+            OtpAction::Code {
+                secret,
+                time,
+                counter,
+                code,
+            } => parse_otp_config(OtpConfig::DEFAULT_SECRET_BYTES, code)
+                .and_then(|config| print_otp_code(config, secret, time, counter)),
+        }
+    } else if let Some(Command::Derive {
+        site,
+        login,
+        counter,
+        template,
+    }) = cli.command
+    {
+        parse_derived_template(template).and_then(|template| {
+            derive_password(
+                DerivedConfig::builder()
+                    .site(site)
+                    .login(login)
+                    .counter(counter)
+                    .template(template)
+                    .build(),
+            )
+        })
+    } else if let Some(rolls) = cli.dice.clone() {
+        gen_dice(cli, rolls)
+    } else if cli.passphrase {
         gen_passphrase(cli)
+    } else if cli.pronounceable {
+        gen_pronounceable(cli)
+    } else if let Some(length) = cli.pin {
+        gen_pin(cli, length)
+    } else if let Some(mask) = cli.mask.clone() {
+        gen_mask(cli, mask)
+    } else if let Some(rules) = cli.rules.clone() {
+        gen_from_rules(cli, rules)
+    } else if let Some(bytes) = cli.token {
+        gen_token(cli, bytes)
+    } else if let Some(count) = cli.recovery_codes {
+        gen_recovery_codes(cli, count)
     } else {
         gen_password(cli)
     };
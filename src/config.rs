@@ -3,16 +3,62 @@
 //! It provides flexible options for customizing the generation process, including
 //! length specifications, character set inclusions, word counts, and more.
 
-use std::{ops::RangeInclusive, path::PathBuf};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::ops::RangeInclusive;
+#[cfg(feature = "std")]
+use std::path::PathBuf;
+
 use rand::Rng;
 
+use crate::generator::{PassphraseGenerator, PasswordGenerator};
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::PassForgeError;
+
 /// Specifies the word list to use for passphrase generation.
 #[derive(Clone, Debug)]
 pub enum WordList {
     /// Use the default built-in word list.
     Default,
     /// Use a custom word list from the specified file path.
+    #[cfg(feature = "std")]
     Custom(PathBuf),
+    /// Use a word list that has already been loaded, so it is not parsed again.
+    Loaded(LoadedWordList),
+}
+
+impl WordList {
+    /// The names of the bundled word lists.
+    pub const NAMES: [&'static str; 2] = ["default", "eff-large"];
+
+    /// Looks up a bundled word list by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the word list, e.g. `default`. See `WordList::NAMES`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the `WordList` if successful,
+    /// or a `PassForgeError` if no such list is bundled.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the name is unknown.
+    pub fn from_name(name: &str) -> Result<Self, PassForgeError> {
+        match name.to_lowercase().as_str() {
+            "default" | "eff-large" => Ok(WordList::Default),
+            name => Err(PassForgeError::WordListError(format!(
+                "Unknown word list \"{}\". Choices are: {}",
+                name,
+                Self::NAMES.join(", ")
+            ))),
+        }
+    }
 }
 
 /// Represents preset configurations for quick setup.
@@ -26,7 +72,6 @@ pub enum ConfigPreset {
     Strong,
 }
 
-
 /// Specifies the length of a password, either as a single value or a range.
 #[derive(Debug, Clone)]
 pub enum Length {
@@ -36,39 +81,109 @@ pub enum Length {
     Range(RangeInclusive<usize>),
 }
 
-
 impl Length {
     /// Gets a specific length value. If a range is specified, a random value within the range is returned.
     ///
     /// # Returns
     ///
     /// A `usize` representing the length.
+    #[cfg(feature = "std")]
     pub fn get_length(&self) -> usize {
+        self.get_length_with_rng(&mut rand::thread_rng())
+    }
+
+    /// Gets a specific length value, drawing from the supplied random number generator
+    /// if a range is specified.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator used to pick a length within the range.
+    ///
+    /// # Returns
+    ///
+    /// A `usize` representing the length.
+    pub fn get_length_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
         match self {
             Length::Single(length) => *length,
-            Length::Range(range) => {
-                rand::thread_rng().gen_range(range.clone())
-            }
+            Length::Range(range) => rng.gen_range(range.clone()),
         }
     }
 }
 
+/// Bounds on how many characters of a single character class may appear in a password.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ClassCount {
+    /// The minimum number of characters that must be drawn from the class.
+    pub min: usize,
+    /// The maximum number of characters that may be drawn from the class, if limited.
+    pub max: Option<usize>,
+}
+
+impl ClassCount {
+    /// Creates a new `ClassCount` with the specified bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The minimum number of characters drawn from the class.
+    /// * `max` - The maximum number of characters drawn from the class, or `None` for no limit.
+    pub fn new(min: usize, max: Option<usize>) -> Self {
+        Self { min, max }
+    }
+
+    /// Creates a `ClassCount` that only requires at least `min` characters from the class.
+    pub fn at_least(min: usize) -> Self {
+        Self { min, max: None }
+    }
+
+    /// Returns `true` if the bounds do not restrict the class in any way.
+    pub fn is_unconstrained(&self) -> bool {
+        self.min == 0 && self.max.is_none()
+    }
+}
+
 /// Represents the configuration options for password generation.
 #[derive(Debug, Clone)]
 pub struct PasswordConfig {
     /// The length specification for the password.
     pub length: Length,
+    /// Whether to include lowercase letters in the password.
+    pub lowercase: bool,
     /// Whether to include capital letters in the password.
     pub capitals: bool,
     /// Whether to include numbers in the password.
     pub numbers: bool,
     /// Whether to include symbols in the password.
     pub symbols: bool,
+    /// How many lowercase letters the password must (or may) contain.
+    pub lowercase_count: ClassCount,
+    /// How many capital letters the password must (or may) contain.
+    pub capitals_count: ClassCount,
+    /// How many numbers the password must (or may) contain.
+    pub numbers_count: ClassCount,
+    /// How many symbols the password must (or may) contain.
+    pub symbols_count: ClassCount,
+    /// Explicit set of allowed characters, replacing the default character sets when set.
+    pub charset: Option<String>,
+    /// Symbols to use instead of the default symbol set.
+    pub symbol_set: Option<String>,
+    /// Characters that must never appear in the password.
+    pub excluded_chars: String,
+    /// Additional user-defined character classes. Characters they share with the built-in
+    /// classes count towards the custom class.
+    pub custom_classes: Vec<CharClass>,
+    /// Whether to leave out visually ambiguous characters such as `l`, `1`, `O` and `0`.
+    pub unambiguous: bool,
+    /// The maximum number of times the same character may appear in a row, if limited.
+    pub max_consecutive: Option<usize>,
+    /// Whether the first character of the password must be a letter.
+    pub start_with_letter: bool,
 }
 
 impl PasswordConfig {
     /// The default length for generated passwords.
     pub const DEFAULT_LENGTH: usize = 18;
+    /// The default setting for including lowercase letters.
+    pub const DEFAULT_LOWERCASE: bool = true;
     /// The default setting for including capital letters.
     pub const DEFAULT_CAPITALS: bool = true;
     /// The default setting for including numbers.
@@ -84,12 +199,27 @@ impl PasswordConfig {
     /// * `capitals` - Whether to include capital letters.
     /// * `numbers` - Whether to include numbers.
     /// * `symbols` - Whether to include symbols.
+    ///
+    /// Lowercase letters are always included and no per-class minimum or maximum is applied;
+    /// use the builder to change those.
     pub fn new(length: Length, capitals: bool, numbers: bool, symbols: bool) -> Self {
         Self {
             length,
+            lowercase: Self::DEFAULT_LOWERCASE,
             capitals,
             numbers,
             symbols,
+            lowercase_count: ClassCount::default(),
+            capitals_count: ClassCount::default(),
+            numbers_count: ClassCount::default(),
+            symbols_count: ClassCount::default(),
+            charset: None,
+            symbol_set: None,
+            excluded_chars: String::new(),
+            custom_classes: Vec::new(),
+            unambiguous: false,
+            max_consecutive: None,
+            start_with_letter: false,
         }
     }
 
@@ -97,15 +227,68 @@ impl PasswordConfig {
     pub fn builder() -> PasswordConfigBuilder {
         PasswordConfigBuilder::default()
     }
+
+    /// Resolves the deduplicated alphabet that passwords are drawn from.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the `Alphabet` if successful,
+    /// or a `PassForgeError` if the character restrictions are contradictory.
+    pub fn alphabet(&self) -> Result<Alphabet, PassForgeError> {
+        Alphabet::from_config(self)
+    }
+
+    /// Returns the entropy of the generated passwords in bits.
+    ///
+    /// Generated passwords are uniformly distributed over all passwords of the chosen length
+    /// that satisfy the class bounds and positional constraints, and a length range is
+    /// sampled uniformly, so this is the base 2 logarithm of the number of lengths plus the
+    /// average of the base 2 logarithm of the number of passwords of each length.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the configuration cannot produce a password.
+    pub fn entropy_bits(&self) -> Result<f64, PassForgeError> {
+        let ln_counts = PasswordGenerator::ln_counts(self)?;
+        let lengths = ln_counts.len() as f64;
+        let average = ln_counts.iter().sum::<f64>() / lengths / core::f64::consts::LN_2;
+        Ok(lengths.log2() + average)
+    }
+
+    /// Returns the number of distinct passwords the configuration can produce, across every
+    /// possible length, or `None` if it does not fit in a `u128`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the configuration cannot produce a password.
+    pub fn keyspace(&self) -> Result<Option<u128>, PassForgeError> {
+        Ok(PasswordGenerator::counts(self)?
+            .into_iter()
+            .try_fold(0u128, |sum, count| {
+                count.and_then(|count| sum.checked_add(count))
+            }))
+    }
 }
 
 /// A builder for creating `PasswordConfig` instances.
 #[derive(Default)]
 pub struct PasswordConfigBuilder {
     length: Option<Length>,
+    lowercase: Option<bool>,
     capitals: Option<bool>,
     numbers: Option<bool>,
     symbols: Option<bool>,
+    lowercase_count: ClassCount,
+    capitals_count: ClassCount,
+    numbers_count: ClassCount,
+    symbols_count: ClassCount,
+    charset: Option<String>,
+    symbol_set: Option<String>,
+    excluded_chars: String,
+    custom_classes: Vec<CharClass>,
+    unambiguous: bool,
+    max_consecutive: Option<usize>,
+    start_with_letter: bool,
 }
 
 impl PasswordConfigBuilder {
@@ -115,6 +298,12 @@ impl PasswordConfigBuilder {
         self
     }
 
+    /// Sets whether to include lowercase letters.
+    pub fn lowercase(mut self, include: bool) -> Self {
+        self.lowercase = Some(include);
+        self
+    }
+
     /// Sets whether to include capital letters.
     pub fn capitals(mut self, include: bool) -> Self {
         self.capitals = Some(include);
@@ -133,43 +322,184 @@ impl PasswordConfigBuilder {
         self
     }
 
+    /// Sets the minimum number of lowercase letters.
+    pub fn min_lowercase(mut self, min: usize) -> Self {
+        self.lowercase_count.min = min;
+        self
+    }
+
+    /// Sets the maximum number of lowercase letters.
+    pub fn max_lowercase(mut self, max: usize) -> Self {
+        self.lowercase_count.max = Some(max);
+        self
+    }
+
+    /// Sets the minimum number of capital letters.
+    pub fn min_capitals(mut self, min: usize) -> Self {
+        self.capitals_count.min = min;
+        self
+    }
+
+    /// Sets the maximum number of capital letters.
+    pub fn max_capitals(mut self, max: usize) -> Self {
+        self.capitals_count.max = Some(max);
+        self
+    }
+
+    /// Sets the minimum number of numbers.
+    pub fn min_numbers(mut self, min: usize) -> Self {
+        self.numbers_count.min = min;
+        self
+    }
+
+    /// Sets the maximum number of numbers.
+    pub fn max_numbers(mut self, max: usize) -> Self {
+        self.numbers_count.max = Some(max);
+        self
+    }
+
+    /// Sets the minimum number of symbols.
+    pub fn min_symbols(mut self, min: usize) -> Self {
+        self.symbols_count.min = min;
+        self
+    }
+
+    /// Sets the maximum number of symbols.
+    pub fn max_symbols(mut self, max: usize) -> Self {
+        self.symbols_count.max = Some(max);
+        self
+    }
+
+    /// Sets an explicit set of allowed characters, replacing the default character sets.
+    pub fn charset(mut self, chars: String) -> Self {
+        self.charset = Some(chars);
+        self
+    }
+
+    /// Sets the symbols to use instead of the default symbol set.
+    pub fn symbol_set(mut self, chars: String) -> Self {
+        self.symbol_set = Some(chars);
+        self
+    }
+
+    /// Sets the characters that must never appear in the password.
+    pub fn exclude_chars(mut self, chars: String) -> Self {
+        self.excluded_chars = chars;
+        self
+    }
+
+    /// Adds a user-defined character class.
+    pub fn custom_class(mut self, class: CharClass) -> Self {
+        self.custom_classes.push(class);
+        self
+    }
+
+    /// Sets whether to leave out visually ambiguous characters.
+    pub fn unambiguous(mut self, enabled: bool) -> Self {
+        self.unambiguous = enabled;
+        self
+    }
+
+    /// Sets the maximum number of times the same character may appear in a row.
+    pub fn max_consecutive(mut self, max: usize) -> Self {
+        self.max_consecutive = Some(max);
+        self
+    }
+
+    /// Sets whether the first character of the password must be a letter.
+    pub fn start_with_letter(mut self, enabled: bool) -> Self {
+        self.start_with_letter = enabled;
+        self
+    }
+
     /// Builds a `PasswordConfig` from the current builder state.
     pub fn build(self) -> PasswordConfig {
         PasswordConfig {
             length: self
                 .length
                 .unwrap_or(Length::Single(PasswordConfig::DEFAULT_LENGTH)),
+            lowercase: self.lowercase.unwrap_or(PasswordConfig::DEFAULT_LOWERCASE),
             capitals: self.capitals.unwrap_or(PasswordConfig::DEFAULT_CAPITALS),
             numbers: self.numbers.unwrap_or(PasswordConfig::DEFAULT_NUMBERS),
             symbols: self.symbols.unwrap_or(PasswordConfig::DEFAULT_SYMBOLS),
+            lowercase_count: self.lowercase_count,
+            capitals_count: self.capitals_count,
+            numbers_count: self.numbers_count,
+            symbols_count: self.symbols_count,
+            charset: self.charset,
+            symbol_set: self.symbol_set,
+            excluded_chars: self.excluded_chars,
+            custom_classes: self.custom_classes,
+            unambiguous: self.unambiguous,
+            max_consecutive: self.max_consecutive,
+            start_with_letter: self.start_with_letter,
         }
     }
 
     /// Builds a `PasswordConfig` from a preset configuration.
+    ///
+    /// Every preset requires at least one character from each class it enables.
     pub fn build_from_preset(self, preset: ConfigPreset) -> PasswordConfig {
         match preset {
             ConfigPreset::Weak => PasswordConfig {
-                length: Length::Single(8),
-                capitals: true,
-                numbers: true,
-                symbols: false,
+                lowercase_count: ClassCount::at_least(1),
+                capitals_count: ClassCount::at_least(1),
+                numbers_count: ClassCount::at_least(1),
+                symbols_count: ClassCount::default(),
+                ..PasswordConfig::new(Length::Single(8), true, true, false)
             },
             ConfigPreset::Average => PasswordConfig {
-                length: Length::Single(16),
-                capitals: true,
-                numbers: true,
-                symbols: true,
+                lowercase_count: ClassCount::at_least(1),
+                capitals_count: ClassCount::at_least(1),
+                numbers_count: ClassCount::at_least(1),
+                symbols_count: ClassCount::at_least(1),
+                ..PasswordConfig::new(Length::Single(16), true, true, true)
             },
             ConfigPreset::Strong => PasswordConfig {
-                length: Length::Single(32),
-                capitals: true,
-                numbers: true,
-                symbols: true,
+                lowercase_count: ClassCount::at_least(2),
+                capitals_count: ClassCount::at_least(2),
+                numbers_count: ClassCount::at_least(2),
+                symbols_count: ClassCount::at_least(2),
+                ..PasswordConfig::new(Length::Single(32), true, true, true)
             },
         }
     }
 }
 
+/// Specifies how the words of a passphrase are capitalized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Capitalization {
+    /// Keep the words as they appear in the word list.
+    #[default]
+    None,
+    /// Capitalize the first letter of every word.
+    FirstLetter,
+    /// Write one randomly chosen word in capitals.
+    RandomWord,
+    /// Write every word in capitals.
+    All,
+}
+
+/// Specifies where the extra digits and symbols of a passphrase are inserted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Insertion {
+    /// Insert each character at a random position anywhere in the passphrase.
+    #[default]
+    Anywhere,
+    /// Insert each character as a token of its own between the words.
+    Tokens,
+}
+
+/// Specifies whether a word may appear more than once in a passphrase.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Sampling {
+    /// Every word is distinct, so there can be no more words than the word list holds.
+    #[default]
+    WithoutReplacement,
+    /// Every word is drawn from the whole list, as with rolling dice for each word.
+    WithReplacement,
+}
+
 /// Represents the configuration options for passphrase generation.
 #[derive(Debug, Clone)]
 pub struct PassphraseConfig {
@@ -179,6 +509,20 @@ pub struct PassphraseConfig {
     pub separator: String,
     /// The word list to use for generating the passphrase.
     pub word_list: WordList,
+    /// Whether words may repeat.
+    pub sampling: Sampling,
+    /// How the words are capitalized.
+    pub capitalization: Capitalization,
+    /// The number of random digits to insert.
+    pub digits: usize,
+    /// The number of random symbols to insert.
+    pub symbols: usize,
+    /// The symbols to draw inserted symbols from.
+    pub symbol_set: String,
+    /// Where the digits and symbols are inserted.
+    pub insertion: Insertion,
+    /// Characters to draw a separator from for every gap, replacing `separator` when set.
+    pub separators: Option<String>,
 }
 
 impl PassphraseConfig {
@@ -186,6 +530,9 @@ impl PassphraseConfig {
     pub const DEFAULT_WORDS: usize = 6;
     /// The default separator for generated passphrases.
     pub const DEFAULT_SEPARATOR: &'static str = "-";
+    /// The default symbols inserted into passphrases. The hyphen is left out because it
+    /// appears in words and as the default separator.
+    pub const DEFAULT_SYMBOL_SET: &'static str = "!#$%&*+=?@^~";
 
     /// Creates a new `PassphraseConfig` with the specified options.
     ///
@@ -194,11 +541,20 @@ impl PassphraseConfig {
     /// * `words` - The number of words to include in the passphrase.
     /// * `separator` - The separator to use between words.
     /// * `word_list` - The word list to use for generating the passphrase.
+    ///
+    /// No styling is applied; use the builder to capitalize words or insert digits and symbols.
     pub fn new(words: usize, separator: String, word_list: WordList) -> Self {
         Self {
             words,
             separator,
             word_list,
+            sampling: Sampling::WithoutReplacement,
+            capitalization: Capitalization::None,
+            digits: 0,
+            symbols: 0,
+            symbol_set: Self::DEFAULT_SYMBOL_SET.to_string(),
+            insertion: Insertion::Anywhere,
+            separators: None,
         }
     }
 
@@ -206,6 +562,29 @@ impl PassphraseConfig {
     pub fn builder() -> PassphraseConfigBuilder {
         PassphraseConfigBuilder::default()
     }
+
+    /// Returns the entropy of the generated passphrases in bits.
+    ///
+    /// Without replacement each word has one candidate fewer than the word before it, while
+    /// with replacement every word has the whole list to choose from. The choice of capitalized
+    /// word, separators and inserted characters and their positions is included as well.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the configuration is invalid or the word list cannot be loaded.
+    pub fn entropy_bits(&self) -> Result<f64, PassForgeError> {
+        PassphraseGenerator::entropy_bits(self)
+    }
+
+    /// Returns the number of distinct passphrases the configuration can produce,
+    /// or `None` if it does not fit in a `u128`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the configuration is invalid or the word list cannot be loaded.
+    pub fn keyspace(&self) -> Result<Option<u128>, PassForgeError> {
+        PassphraseGenerator::keyspace(self)
+    }
 }
 
 /// A builder for creating `PassphraseConfig` instances.
@@ -214,6 +593,13 @@ pub struct PassphraseConfigBuilder {
     words: Option<usize>,
     separator: Option<String>,
     word_list: Option<WordList>,
+    sampling: Sampling,
+    capitalization: Capitalization,
+    digits: usize,
+    symbols: usize,
+    symbol_set: Option<String>,
+    insertion: Insertion,
+    separators: Option<String>,
 }
 
 impl PassphraseConfigBuilder {
@@ -235,6 +621,48 @@ impl PassphraseConfigBuilder {
         self
     }
 
+    /// Sets whether words may repeat.
+    pub fn sampling(mut self, sampling: Sampling) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// Sets how the words are capitalized.
+    pub fn capitalization(mut self, capitalization: Capitalization) -> Self {
+        self.capitalization = capitalization;
+        self
+    }
+
+    /// Sets the number of random digits to insert.
+    pub fn digits(mut self, count: usize) -> Self {
+        self.digits = count;
+        self
+    }
+
+    /// Sets the number of random symbols to insert.
+    pub fn symbols(mut self, count: usize) -> Self {
+        self.symbols = count;
+        self
+    }
+
+    /// Sets the symbols to draw inserted symbols from.
+    pub fn symbol_set(mut self, chars: String) -> Self {
+        self.symbol_set = Some(chars);
+        self
+    }
+
+    /// Sets where the digits and symbols are inserted.
+    pub fn insertion(mut self, insertion: Insertion) -> Self {
+        self.insertion = insertion;
+        self
+    }
+
+    /// Sets the characters to draw a separator from for every gap.
+    pub fn separators(mut self, chars: String) -> Self {
+        self.separators = Some(chars);
+        self
+    }
+
     /// Builds a `PassphraseConfig` from the current builder state.
     pub fn build(self) -> PassphraseConfig {
         PassphraseConfig {
@@ -243,27 +671,47 @@ impl PassphraseConfigBuilder {
                 .separator
                 .unwrap_or(PassphraseConfig::DEFAULT_SEPARATOR.to_string()),
             word_list: self.word_list.unwrap_or(WordList::Default),
+            sampling: self.sampling,
+            capitalization: self.capitalization,
+            digits: self.digits,
+            symbols: self.symbols,
+            symbol_set: self
+                .symbol_set
+                .unwrap_or(PassphraseConfig::DEFAULT_SYMBOL_SET.to_string()),
+            insertion: self.insertion,
+            separators: self.separators,
         }
     }
 
     /// Builds a `PassphraseConfig` from a preset configuration.
     pub fn build_from_preset(self, preset: ConfigPreset) -> PassphraseConfig {
         match preset {
-            ConfigPreset::Weak => PassphraseConfig {
-                words: 4,
-                separator: "-".into(),
-                word_list: WordList::Default,
-            },
-            ConfigPreset::Average => PassphraseConfig {
-                words: 8,
-                separator: "-".into(),
-                word_list: WordList::Default,
-            },
-            ConfigPreset::Strong => PassphraseConfig {
-                words: 16,
-                separator: "-".into(),
-                word_list: WordList::Default,
-            },
+            ConfigPreset::Weak => PassphraseConfig::new(4, "-".into(), WordList::Default),
+            ConfigPreset::Average => PassphraseConfig::new(8, "-".into(), WordList::Default),
+            ConfigPreset::Strong => PassphraseConfig::new(16, "-".into(), WordList::Default),
         }
     }
-}
\ No newline at end of file
+}
+
+pub mod alphabet;
+pub mod derived;
+pub mod mask;
+pub mod otp;
+pub mod pin;
+pub mod policy;
+pub mod pronounceable;
+pub mod recovery;
+pub mod rules;
+pub mod token;
+pub mod word_list;
+
+pub use alphabet::{Alphabet, CharClass};
+pub use derived::{DerivedConfig, DerivedConfigBuilder, DerivedTemplate};
+pub use mask::{MaskConfig, MaskConfigBuilder, MaskSyntax, MaskToken};
+pub use otp::{OtpAlgorithm, OtpConfig, OtpConfigBuilder};
+pub use pin::{PinConfig, PinConfigBuilder, WeakPinPattern};
+pub use policy::{PasswordPolicy, PasswordPolicyBuilder, PolicyViolation};
+pub use pronounceable::{PronounceableConfig, PronounceableConfigBuilder};
+pub use recovery::{RecoveryCodesConfig, RecoveryCodesConfigBuilder};
+pub use token::{TokenConfig, TokenConfigBuilder, TokenEncoding};
+pub use word_list::{LoadedWordList, WordListReport};
@@ -0,0 +1,355 @@
+//! This module resolves the character classes of a `PasswordConfig` into an alphabet.
+//!
+//! It combines the built-in classes, user-defined classes, an explicit allowed set and an
+//! exclusion list into a single deduplicated set of characters, grouped by class.
+
+use alloc::{
+    collections::BTreeSet,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::config::{ClassCount, PasswordConfig};
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::PassForgeError;
+
+/// Lowercase letters used in password generation.
+pub const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+/// Uppercase letters used in password generation.
+pub const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// Numbers used in password generation.
+pub const NUMBERS: &str = "0123456789";
+/// Symbols used in password generation, unless a custom symbol set is configured.
+pub const SYMBOLS: &str = "!@#$%^&*()-_=+[]{}|;:,.<>?";
+/// Characters that are easily mistaken for one another when read off a screen or paper,
+/// removed when `PasswordConfig::unambiguous` is set. The groups of lookalikes are
+/// `0 O o`, `1 l I i | !`, `5 S`, `2 Z`, `8 B` and the quotes `` ` ' " ``.
+pub const AMBIGUOUS: &str = "0Oo1lIi|!5S2Z8B`'\"";
+
+/// A named set of characters together with the bounds on how often it may be drawn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharClass {
+    /// Human readable name of the class, used in error messages.
+    pub name: String,
+    /// The characters belonging to the class.
+    pub chars: Vec<char>,
+    /// The minimum and maximum number of characters drawn from the class.
+    pub count: ClassCount,
+}
+
+impl CharClass {
+    /// Creates a new `CharClass` with the specified options.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Human readable name of the class.
+    /// * `chars` - The characters belonging to the class.
+    /// * `count` - The minimum and maximum number of characters drawn from the class.
+    pub fn new(name: impl Into<String>, chars: &str, count: ClassCount) -> Self {
+        Self {
+            name: name.into(),
+            chars: chars.chars().collect(),
+            count,
+        }
+    }
+}
+
+/// The deduplicated set of characters a password is drawn from, grouped by class.
+///
+/// Every character belongs to exactly one class. A character shared by a custom class and a
+/// built-in class belongs to the custom class; otherwise it is kept in the first class that
+/// contains it.
+#[derive(Debug, Clone)]
+pub struct Alphabet {
+    classes: Vec<CharClass>,
+}
+
+impl Alphabet {
+    /// Resolves the alphabet described by a `PasswordConfig`.
+    ///
+    /// The built-in classes are taken from `charset` when it is set, split into lowercase
+    /// letters, capital letters, numbers and symbols, and from the default character sets
+    /// (with `symbol_set` replacing the default symbols) otherwise. Custom classes are appended
+    /// and restricted to `charset` as well, and their characters are removed from the built-in
+    /// classes. Finally `excluded_chars` (and the `AMBIGUOUS` characters, if `unambiguous` is
+    /// set) are removed and duplicates are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the `PasswordConfig` describing the character classes.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the resolved `Alphabet` if successful,
+    /// or a `PassForgeError` if the configuration is contradictory.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if a class maximum is smaller than its minimum, if a minimum is set on
+    /// a disabled or emptied class, if a class contains control characters, if `charset` and
+    /// `symbol_set` are both set, or if no characters remain.
+    pub fn from_config(config: &PasswordConfig) -> Result<Self, PassForgeError> {
+        let sources = match &config.charset {
+            None => [
+                LOWERCASE.to_string(),
+                UPPERCASE.to_string(),
+                NUMBERS.to_string(),
+                config
+                    .symbol_set
+                    .clone()
+                    .unwrap_or_else(|| SYMBOLS.to_string()),
+            ],
+            Some(charset) => {
+                if config.symbol_set.is_some() {
+                    return Err(PassForgeError::InvalidConfig(
+                        "A symbol set cannot be combined with an explicit character set".into(),
+                    ));
+                }
+                let pick = |keep: fn(&char) -> bool| charset.chars().filter(keep).collect();
+                [
+                    pick(|c| c.is_lowercase()),
+                    pick(|c| c.is_uppercase()),
+                    pick(|c| c.is_ascii_digit()),
+                    pick(|c| !c.is_lowercase() && !c.is_uppercase() && !c.is_ascii_digit()),
+                ]
+            }
+        };
+        let [lowercase, uppercase, numbers, symbols] = sources;
+        let builtin = [
+            (
+                "lowercase letters",
+                config.lowercase,
+                lowercase,
+                config.lowercase_count,
+            ),
+            (
+                "capital letters",
+                config.capitals,
+                uppercase,
+                config.capitals_count,
+            ),
+            ("numbers", config.numbers, numbers, config.numbers_count),
+            ("symbols", config.symbols, symbols, config.symbols_count),
+        ];
+
+        let mut custom = Vec::with_capacity(config.custom_classes.len());
+        for class in &config.custom_classes {
+            Self::check_bounds(&class.name, class.count)?;
+            let mut class = class.clone();
+            if let Some(charset) = &config.charset {
+                class.chars.retain(|c| charset.contains(*c));
+            }
+            custom.push(class);
+        }
+        let custom_chars: BTreeSet<char> = custom
+            .iter()
+            .flat_map(|class| class.chars.iter().copied())
+            .collect();
+
+        let mut candidates = Vec::with_capacity(builtin.len() + custom.len());
+        for (name, enabled, chars, count) in builtin {
+            Self::check_bounds(name, count)?;
+            if !enabled {
+                if count.min > 0 {
+                    return Err(PassForgeError::InvalidConfig(format!(
+                        "A minimum number of {} requires {} to be enabled",
+                        name, name
+                    )));
+                }
+                continue;
+            }
+            let mut class = CharClass::new(name, &chars, count);
+            class.chars.retain(|c| !custom_chars.contains(c));
+            candidates.push(class);
+        }
+        candidates.extend(custom);
+
+        let is_excluded = |c: char| {
+            config.excluded_chars.contains(c) || (config.unambiguous && AMBIGUOUS.contains(c))
+        };
+        let mut seen = BTreeSet::new();
+        let mut classes = Vec::with_capacity(candidates.len());
+        for mut class in candidates {
+            if class.chars.iter().any(|c| c.is_control()) {
+                return Err(PassForgeError::InvalidConfig(format!(
+                    "The {} cannot contain control characters",
+                    class.name
+                )));
+            }
+            class.chars.retain(|c| !is_excluded(*c) && seen.insert(*c));
+            if class.chars.is_empty() {
+                if class.count.min > 0 {
+                    return Err(PassForgeError::InvalidConfig(format!(
+                        "No {} remain after applying the character restrictions",
+                        class.name
+                    )));
+                }
+                continue;
+            }
+            classes.push(class);
+        }
+
+        if classes.is_empty() {
+            return Err(PassForgeError::InvalidConfig(
+                "No characters remain after applying the character restrictions".into(),
+            ));
+        }
+        Ok(Self { classes })
+    }
+
+    /// Returns the non-empty character classes making up the alphabet.
+    pub fn classes(&self) -> &[CharClass] {
+        &self.classes
+    }
+
+    /// Returns an iterator over every character in the alphabet.
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.classes
+            .iter()
+            .flat_map(|class| class.chars.iter().copied())
+    }
+
+    /// Returns the number of distinct characters in the alphabet.
+    pub fn len(&self) -> usize {
+        self.classes.iter().map(|class| class.chars.len()).sum()
+    }
+
+    /// Returns the entropy, in bits, of a single character drawn uniformly from the alphabet.
+    ///
+    /// This reflects every restriction applied to the alphabet, so for example enabling
+    /// `unambiguous` lowers the value accordingly.
+    pub fn bits_per_char(&self) -> f64 {
+        (self.len() as f64).log2()
+    }
+
+    /// Returns `true` if the alphabet contains no characters.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the alphabet contains the given character.
+    pub fn contains(&self, c: char) -> bool {
+        self.classes.iter().any(|class| class.chars.contains(&c))
+    }
+
+    /// Checks that the maximum of a class is not smaller than its minimum.
+    fn check_bounds(name: &str, count: ClassCount) -> Result<(), PassForgeError> {
+        if count.max.is_some_and(|max| max < count.min) {
+            return Err(PassForgeError::InvalidConfig(format!(
+                "Maximum number of {} cannot be smaller than the minimum",
+                name
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Length;
+
+    mod alphabet_tests {
+        use super::*;
+
+        #[test]
+        fn test_default_alphabet() {
+            let config = PasswordConfig::new(Length::Single(16), true, true, true);
+            let alphabet = Alphabet::from_config(&config).unwrap();
+            assert_eq!(alphabet.len(), 26 + 26 + 10 + SYMBOLS.len());
+            assert_eq!(alphabet.classes().len(), 4);
+        }
+
+        #[test]
+        fn test_symbol_set_and_exclusions() {
+            let config = PasswordConfig::builder()
+                .symbol_set("!@#$".into())
+                .exclude_chars("abc$".into())
+                .build();
+            let alphabet = Alphabet::from_config(&config).unwrap();
+            assert!(alphabet.contains('!'));
+            assert!(!alphabet.contains('$'));
+            assert!(!alphabet.contains('<'));
+            assert!(!alphabet.contains('a'));
+            assert_eq!(alphabet.len(), 23 + 26 + 10 + 3);
+        }
+
+        #[test]
+        fn test_unambiguous_alphabet() {
+            let config = PasswordConfig::builder().unambiguous(true).build();
+            let alphabet = Alphabet::from_config(&config).unwrap();
+            assert!(AMBIGUOUS.chars().all(|c| !alphabet.contains(c)));
+            assert_eq!(alphabet.len(), 26 + 26 + 10 + SYMBOLS.len() - 15);
+            assert!(alphabet.bits_per_char() < (26.0 + 26.0 + 10.0 + 26.0f64).log2());
+        }
+
+        #[test]
+        fn test_charset_is_split_into_classes() {
+            let config = PasswordConfig::builder()
+                .charset("ABCDEF0123456789~~".into())
+                .build();
+            let alphabet = Alphabet::from_config(&config).unwrap();
+            let names: Vec<&str> = alphabet.classes().iter().map(|c| c.name.as_str()).collect();
+            assert_eq!(names, ["capital letters", "numbers", "symbols"]);
+            assert_eq!(alphabet.len(), 17);
+        }
+
+        #[test]
+        fn test_custom_classes_are_deduplicated() {
+            let config = PasswordConfig::builder()
+                .capitals(false)
+                .numbers(false)
+                .symbols(false)
+                .custom_class(CharClass::new("vowels", "aeiouäö", ClassCount::at_least(1)))
+                .build();
+            let alphabet = Alphabet::from_config(&config).unwrap();
+            assert_eq!(alphabet.classes()[0].chars.len(), 21);
+            assert_eq!(alphabet.classes()[1].chars.len(), 7);
+            assert_eq!(alphabet.len(), 28);
+        }
+
+        #[test]
+        fn test_custom_class_takes_overlapping_symbols() {
+            let config = PasswordConfig::builder()
+                .custom_class(CharClass::new(
+                    "brackets",
+                    "()[]{}<>",
+                    ClassCount::at_least(2),
+                ))
+                .build();
+            let alphabet = Alphabet::from_config(&config).unwrap();
+            let symbols = &alphabet.classes()[3];
+            assert_eq!(symbols.name, "symbols");
+            assert!(symbols.chars.iter().all(|c| !"()[]{}<>".contains(*c)));
+            assert_eq!(symbols.chars.len(), SYMBOLS.len() - 8);
+            let brackets = &alphabet.classes()[4];
+            assert_eq!(brackets.chars, "()[]{}<>".chars().collect::<Vec<_>>());
+            assert_eq!(alphabet.len(), 26 + 26 + 10 + SYMBOLS.len());
+        }
+
+        #[test]
+        fn test_invalid_alphabets() {
+            let emptied = PasswordConfig::builder()
+                .capitals(false)
+                .numbers(false)
+                .symbols(false)
+                .exclude_chars(LOWERCASE.into())
+                .build();
+            assert!(Alphabet::from_config(&emptied).is_err());
+
+            let conflicting = PasswordConfig::builder()
+                .charset("abc".into())
+                .symbol_set("!".into())
+                .build();
+            assert!(Alphabet::from_config(&conflicting).is_err());
+
+            let required_but_excluded = PasswordConfig::builder()
+                .min_numbers(1)
+                .exclude_chars(NUMBERS.into())
+                .build();
+            assert!(Alphabet::from_config(&required_but_excluded).is_err());
+        }
+    }
+}
@@ -0,0 +1,446 @@
+//! This module defines the configuration for deterministic site password derivation.
+//!
+//! A derived password is computed from a master secret, a site name, a login and a counter,
+//! so the same inputs always give the same password and nothing needs to be stored. The
+//! derivation is described on `DerivedGenerator`.
+
+use alloc::{format, string::String, vec::Vec};
+
+use crate::config::{CharClass, Length, PasswordConfig};
+use crate::{PassForgeError, SecretString};
+
+/// What a derived secret looks like.
+///
+/// The templates are rendered by `DerivedGenerator` itself rather than by the password and
+/// passphrase generators, so a derived password does not change when those do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DerivedTemplate {
+    /// A password of `length` characters from the lowercase letters and, when enabled, the
+    /// capital letters, numbers and symbols, with at least one character of each.
+    Password {
+        /// The number of characters.
+        length: usize,
+        /// Whether to include capital letters.
+        capitals: bool,
+        /// Whether to include numbers.
+        numbers: bool,
+        /// Whether to include symbols.
+        symbols: bool,
+    },
+    /// A password of `length` characters from `classes`, with at least `count.min` characters
+    /// of each class. Use `DerivedTemplate::from_password_config` to take the classes from a
+    /// `PasswordConfig`.
+    Classes {
+        /// The number of characters.
+        length: usize,
+        /// The character classes, in the order they are drawn from.
+        classes: Vec<CharClass>,
+    },
+    /// A passphrase of `words` words from the default word list, joined by `separator`.
+    Passphrase {
+        /// The number of words.
+        words: usize,
+        /// The text placed between the words.
+        separator: String,
+    },
+}
+
+impl DerivedTemplate {
+    /// Creates a `Classes` template from the character classes of a `PasswordConfig`, after
+    /// its character set, symbol set, exclusions and custom classes are applied, keeping the
+    /// per-class minimums.
+    ///
+    /// The `Password` template renders the same as the classes of a config that only sets the
+    /// enabled classes, with a minimum of one character for each of them.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The `PasswordConfig` to take the length and classes from.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the `DerivedTemplate` if successful,
+    /// or a `PassForgeError` if the configuration cannot be derived.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the length is a range, if a class has a maximum, if
+    /// `max_consecutive` or `start_with_letter` is set, or if the alphabet cannot be resolved.
+    pub fn from_password_config(config: &PasswordConfig) -> Result<Self, PassForgeError> {
+        let Length::Single(length) = config.length else {
+            return Err(PassForgeError::InvalidLength(
+                "A derived password needs a single length, not a range".into(),
+            ));
+        };
+        if config.max_consecutive.is_some() || config.start_with_letter {
+            return Err(PassForgeError::InvalidConfig(
+                "Derived passwords cannot limit repeats or require a leading letter".into(),
+            ));
+        }
+        let alphabet = config.alphabet()?;
+        if let Some(class) = alphabet
+            .classes()
+            .iter()
+            .find(|class| class.count.max.is_some())
+        {
+            return Err(PassForgeError::InvalidConfig(format!(
+                "Derived passwords cannot limit the number of {}",
+                class.name
+            )));
+        }
+        Ok(DerivedTemplate::Classes {
+            length,
+            classes: alphabet.classes().to_vec(),
+        })
+    }
+}
+
+/// Represents the configuration options for password derivation.
+///
+/// Changing any field other than `master` changes every derived password, so the site,
+/// login, template and cost parameters must be kept exactly as they were.
+#[derive(Debug)]
+pub struct DerivedConfig {
+    /// The master secret every password is derived from.
+    pub master: SecretString,
+    /// The site the password is for, such as `example.com`. Used exactly as given.
+    pub site: String,
+    /// The login on the site. May be empty.
+    pub login: String,
+    /// Incremented to get a new password for the same site and login.
+    pub counter: u32,
+    /// What the derived password looks like.
+    pub template: DerivedTemplate,
+    /// The Argon2id memory cost in KiB.
+    pub memory_kib: u32,
+    /// The Argon2id number of passes over the memory.
+    pub iterations: u32,
+    /// The Argon2id degree of parallelism.
+    pub parallelism: u32,
+}
+
+impl DerivedConfig {
+    /// The default counter.
+    pub const DEFAULT_COUNTER: u32 = 1;
+    /// The default Argon2id memory cost, 64 MiB.
+    pub const DEFAULT_MEMORY_KIB: u32 = 64 * 1024;
+    /// The default Argon2id number of passes.
+    pub const DEFAULT_ITERATIONS: u32 = 3;
+    /// The default Argon2id degree of parallelism.
+    pub const DEFAULT_PARALLELISM: u32 = 1;
+    /// The default length of a derived password.
+    pub const DEFAULT_LENGTH: usize = 18;
+    /// The maximum length of a derived password.
+    pub const MAX_LENGTH: usize = 128;
+    /// The default number of words in a derived passphrase.
+    pub const DEFAULT_WORDS: usize = 6;
+    /// The maximum number of words in a derived passphrase.
+    pub const MAX_WORDS: usize = 32;
+    /// The default separator between the words of a derived passphrase.
+    pub const DEFAULT_SEPARATOR: &'static str = "-";
+
+    /// Creates a new `DerivedConfig` that renders a password of `DEFAULT_LENGTH` characters
+    /// with every character class, with the default counter and Argon2id parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `master` - The master secret.
+    /// * `site` - The site the password is for.
+    /// * `login` - The login on the site.
+    pub fn new(master: SecretString, site: String, login: String) -> Self {
+        DerivedConfig::builder()
+            .master(master)
+            .site(site)
+            .login(login)
+            .build()
+    }
+
+    /// Returns a new `DerivedConfigBuilder` for creating a `DerivedConfig`.
+    pub fn builder() -> DerivedConfigBuilder {
+        DerivedConfigBuilder::default()
+    }
+
+    /// Checks that the master secret and the site are not empty, that a password has room for
+    /// the minimum of every class and at most `MAX_LENGTH` characters, and that a passphrase
+    /// has between 1 and `MAX_WORDS` words and a separator. The Argon2id parameters are checked
+    /// when deriving.
+    pub(crate) fn check(&self) -> Result<(), PassForgeError> {
+        if self.master.expose_secret().is_empty() {
+            return Err(PassForgeError::InvalidConfig(
+                "Master secret cannot be empty".into(),
+            ));
+        }
+        if self.site.is_empty() {
+            return Err(PassForgeError::InvalidConfig("Site cannot be empty".into()));
+        }
+        match &self.template {
+            DerivedTemplate::Password {
+                length,
+                capitals,
+                numbers,
+                symbols,
+            } => {
+                let classes = 1 + [capitals, numbers, symbols]
+                    .into_iter()
+                    .filter(|enabled| **enabled)
+                    .count();
+                if *length < classes || *length > Self::MAX_LENGTH {
+                    return Err(PassForgeError::InvalidLength(format!(
+                        "A derived password with {} character classes must be between {} and {} characters long",
+                        classes,
+                        classes,
+                        Self::MAX_LENGTH
+                    )));
+                }
+            }
+            DerivedTemplate::Classes { length, classes } => {
+                if classes.is_empty() || classes.iter().any(|class| class.chars.is_empty()) {
+                    return Err(PassForgeError::InvalidConfig(
+                        "A derived password needs at least one class, and no empty classes".into(),
+                    ));
+                }
+                if classes.iter().any(|class| class.count.max.is_some()) {
+                    return Err(PassForgeError::InvalidConfig(
+                        "Derived passwords cannot limit the number of characters of a class".into(),
+                    ));
+                }
+                let required = classes
+                    .iter()
+                    .map(|class| class.count.min)
+                    .sum::<usize>()
+                    .max(1);
+                if *length < required || *length > Self::MAX_LENGTH {
+                    return Err(PassForgeError::InvalidLength(format!(
+                        "A derived password with {} required characters must be between {} and {} characters long",
+                        required,
+                        required,
+                        Self::MAX_LENGTH
+                    )));
+                }
+            }
+            DerivedTemplate::Passphrase { words, separator } => {
+                if *words < 1 || *words > Self::MAX_WORDS {
+                    return Err(PassForgeError::InvalidWordCount(format!(
+                        "A derived passphrase must have between 1 and {} words",
+                        Self::MAX_WORDS
+                    )));
+                }
+                if separator.is_empty() {
+                    return Err(PassForgeError::InvalidConfig(
+                        "A derived passphrase needs a separator between its words".into(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A builder for creating `DerivedConfig` instances.
+#[derive(Default)]
+pub struct DerivedConfigBuilder {
+    master: Option<SecretString>,
+    site: Option<String>,
+    login: Option<String>,
+    counter: Option<u32>,
+    template: Option<DerivedTemplate>,
+    memory_kib: Option<u32>,
+    iterations: Option<u32>,
+    parallelism: Option<u32>,
+}
+
+impl DerivedConfigBuilder {
+    /// Sets the master secret.
+    pub fn master(mut self, master: SecretString) -> Self {
+        self.master = Some(master);
+        self
+    }
+
+    /// Sets the site the password is for.
+    pub fn site(mut self, site: String) -> Self {
+        self.site = Some(site);
+        self
+    }
+
+    /// Sets the login on the site.
+    pub fn login(mut self, login: String) -> Self {
+        self.login = Some(login);
+        self
+    }
+
+    /// Sets the counter.
+    pub fn counter(mut self, counter: u32) -> Self {
+        self.counter = Some(counter);
+        self
+    }
+
+    /// Sets what the derived password looks like.
+    pub fn template(mut self, template: DerivedTemplate) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    /// Sets the Argon2id memory cost in KiB.
+    pub fn memory_kib(mut self, memory_kib: u32) -> Self {
+        self.memory_kib = Some(memory_kib);
+        self
+    }
+
+    /// Sets the Argon2id number of passes.
+    pub fn iterations(mut self, iterations: u32) -> Self {
+        self.iterations = Some(iterations);
+        self
+    }
+
+    /// Sets the Argon2id degree of parallelism.
+    pub fn parallelism(mut self, parallelism: u32) -> Self {
+        self.parallelism = Some(parallelism);
+        self
+    }
+
+    /// Builds a `DerivedConfig` from the current builder state.
+    /// Unless set otherwise, the counter is 1, the template is a password of 18 characters
+    /// with every character class, and Argon2id uses 64 MiB, 3 passes and no parallelism.
+    /// Without a master secret or site, deriving fails.
+    pub fn build(self) -> DerivedConfig {
+        DerivedConfig {
+            master: self
+                .master
+                .unwrap_or_else(|| SecretString::new(String::new())),
+            site: self.site.unwrap_or_default(),
+            login: self.login.unwrap_or_default(),
+            counter: self.counter.unwrap_or(DerivedConfig::DEFAULT_COUNTER),
+            template: self.template.unwrap_or(DerivedTemplate::Password {
+                length: DerivedConfig::DEFAULT_LENGTH,
+                capitals: true,
+                numbers: true,
+                symbols: true,
+            }),
+            memory_kib: self.memory_kib.unwrap_or(DerivedConfig::DEFAULT_MEMORY_KIB),
+            iterations: self.iterations.unwrap_or(DerivedConfig::DEFAULT_ITERATIONS),
+            parallelism: self
+                .parallelism
+                .unwrap_or(DerivedConfig::DEFAULT_PARALLELISM),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod derived_config_tests {
+        use alloc::{format, vec};
+
+        use crate::config::ClassCount;
+
+        use super::*;
+
+        #[test]
+        fn test_builder_defaults() {
+            let config = DerivedConfig::new("master".into(), "example.com".into(), "".into());
+            assert_eq!(config.counter, 1);
+            assert_eq!(config.memory_kib, 65536);
+            assert_eq!(config.iterations, 3);
+            assert_eq!(config.parallelism, 1);
+            assert_eq!(
+                config.template,
+                DerivedTemplate::Password {
+                    length: 18,
+                    capitals: true,
+                    numbers: true,
+                    symbols: true,
+                }
+            );
+            assert!(config.check().is_ok());
+        }
+
+        #[test]
+        fn test_invalid_config() {
+            let config = DerivedConfig::new("".into(), "example.com".into(), "alice".into());
+            assert!(config.check().is_err());
+            let config = DerivedConfig::new("master".into(), "".into(), "alice".into());
+            assert!(config.check().is_err());
+
+            let with_template = |template| {
+                DerivedConfig::builder()
+                    .master("master".into())
+                    .site("example.com".into())
+                    .template(template)
+                    .build()
+            };
+            let password = |length, symbols| DerivedTemplate::Password {
+                length,
+                capitals: true,
+                numbers: true,
+                symbols,
+            };
+            assert!(with_template(password(3, false)).check().is_ok());
+            assert!(with_template(password(3, true)).check().is_err());
+            assert!(with_template(password(129, true)).check().is_err());
+            let passphrase = |words| DerivedTemplate::Passphrase {
+                words,
+                separator: " ".into(),
+            };
+            assert!(with_template(passphrase(0)).check().is_err());
+            assert!(with_template(passphrase(33)).check().is_err());
+            let no_separator = DerivedTemplate::Passphrase {
+                words: 4,
+                separator: "".into(),
+            };
+            assert!(with_template(no_separator).check().is_err());
+
+            let digits = |length, min| DerivedTemplate::Classes {
+                length,
+                classes: vec![CharClass::new(
+                    "numbers",
+                    "0123456789",
+                    ClassCount::at_least(min),
+                )],
+            };
+            assert!(with_template(digits(4, 4)).check().is_ok());
+            assert!(with_template(digits(3, 4)).check().is_err());
+            assert!(with_template(digits(0, 0)).check().is_err());
+            let empty = DerivedTemplate::Classes {
+                length: 4,
+                classes: Vec::new(),
+            };
+            assert!(with_template(empty).check().is_err());
+        }
+
+        #[test]
+        fn test_template_from_password_config() {
+            let config = PasswordConfig::builder()
+                .length(Length::Single(12))
+                .symbols(false)
+                .exclude_chars("abc0".into())
+                .min_numbers(3)
+                .build();
+            let DerivedTemplate::Classes { length, classes } =
+                DerivedTemplate::from_password_config(&config).unwrap()
+            else {
+                panic!("expected a Classes template");
+            };
+            assert_eq!(length, 12);
+            let mins: Vec<usize> = classes.iter().map(|class| class.count.min).collect();
+            assert_eq!(mins, [0, 0, 3]);
+            assert_eq!(classes[0].chars.len(), 23);
+            assert_eq!(classes[2].chars.len(), 9);
+
+            let range = PasswordConfig::builder()
+                .length(Length::Range(8..=12))
+                .build();
+            assert!(DerivedTemplate::from_password_config(&range).is_err());
+            let maximum = PasswordConfig::builder().max_symbols(2).build();
+            assert!(DerivedTemplate::from_password_config(&maximum).is_err());
+            let repeats = PasswordConfig::builder().max_consecutive(2).build();
+            assert!(DerivedTemplate::from_password_config(&repeats).is_err());
+        }
+
+        #[test]
+        fn test_debug_hides_master() {
+            let config = DerivedConfig::new("hunter2".into(), "example.com".into(), "".into());
+            assert!(!format!("{:?}", config).contains("hunter2"));
+        }
+    }
+}
@@ -0,0 +1,475 @@
+//! This module defines the configuration for mask based password generation.
+//!
+//! A mask is a template describing every position of the output, using hashcat-style
+//! placeholders for character classes and literal characters for everything else.
+//!
+//! | Placeholder | Characters                                     |
+//! |-------------|------------------------------------------------|
+//! | `?l`        | lowercase letters                              |
+//! | `?u`        | uppercase letters                              |
+//! | `?d`        | digits                                         |
+//! | `?s`        | symbols (the default `PasswordConfig` symbols) |
+//! | `?a`        | all of the above                               |
+//! | `?h` / `?H` | lowercase / uppercase hexadecimal digits       |
+//! | `?1`-`?4`   | the matching custom charset                    |
+//! | `??`        | a literal `?`                                  |
+//! | `\x`        | the literal character `x`                      |
+//!
+//! For example `?u?l?l?l-?d?d?d?d` produces values like `Kxqe-4821`.
+//!
+//! With [`MaskSyntax::Template`] the mask is read as a template instead, where `A` stands for an
+//! uppercase letter, `a` for a lowercase letter, `9` for a digit and `\x` for the literal
+//! character `x`; every other character is emitted as-is. The template `Aaaa-9999` is
+//! equivalent to the mask `?u?l?l?l-?d?d?d?d`.
+//!
+//! Either way a mask needs at least one placeholder, since a mask of literals only always
+//! produces the same value.
+
+use alloc::{
+    collections::BTreeSet,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use crate::config::alphabet::{LOWERCASE, NUMBERS, SYMBOLS, UPPERCASE};
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::PassForgeError;
+
+/// A single position of a parsed mask.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MaskToken {
+    /// A character that is always emitted as-is.
+    Literal(char),
+    /// A position filled with a character drawn uniformly from the set.
+    Class(Vec<char>),
+}
+
+impl MaskToken {
+    /// Returns the number of distinct characters this position can take.
+    pub fn size(&self) -> usize {
+        match self {
+            MaskToken::Literal(_) => 1,
+            MaskToken::Class(chars) => chars.len(),
+        }
+    }
+}
+
+/// The syntax a mask is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MaskSyntax {
+    /// Hashcat-style placeholders such as `?l`, `?u` and `?d`.
+    #[default]
+    Hashcat,
+    /// Template letters, where `A` is an uppercase letter, `a` a lowercase letter and `9` a
+    /// digit.
+    Template,
+}
+
+/// Represents the configuration options for mask based password generation.
+#[derive(Debug, Clone)]
+pub struct MaskConfig {
+    /// The mask as it was written.
+    mask: String,
+    /// The syntax the mask is written in.
+    syntax: MaskSyntax,
+    /// The parsed positions of the mask.
+    tokens: Vec<MaskToken>,
+}
+
+impl MaskConfig {
+    /// The number of custom charsets that can be referenced as `?1` to `?4`.
+    pub const CUSTOM_CHARSETS: usize = 4;
+
+    /// Creates a new `MaskConfig` from a mask without custom charsets.
+    ///
+    /// # Arguments
+    ///
+    /// * `mask` - The mask describing the generated output.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the parsed `MaskConfig` if successful,
+    /// or a `PassForgeError` if the mask is invalid.
+    pub fn new(mask: &str) -> Result<Self, PassForgeError> {
+        Self::builder().mask(mask.to_string()).build()
+    }
+
+    /// Returns a new `MaskConfigBuilder` for creating a `MaskConfig`.
+    pub fn builder() -> MaskConfigBuilder {
+        MaskConfigBuilder::default()
+    }
+
+    /// Returns the mask as it was written.
+    pub fn mask(&self) -> &str {
+        &self.mask
+    }
+
+    /// Returns the syntax the mask is written in.
+    pub fn syntax(&self) -> MaskSyntax {
+        self.syntax
+    }
+
+    /// Returns the parsed positions of the mask.
+    pub fn tokens(&self) -> &[MaskToken] {
+        &self.tokens
+    }
+
+    /// Returns the number of distinct outputs the mask can produce,
+    /// or `None` if it does not fit in a `u128`.
+    pub fn keyspace(&self) -> Option<u128> {
+        self.tokens
+            .iter()
+            .try_fold(1u128, |acc, token| acc.checked_mul(token.size() as u128))
+    }
+
+    /// Returns the entropy of the mask in bits, i.e. the base 2 logarithm of the keyspace.
+    pub fn entropy_bits(&self) -> f64 {
+        self.tokens
+            .iter()
+            .map(|token| (token.size() as f64).log2())
+            .sum()
+    }
+
+    /// Parses a mask into its positions.
+    ///
+    /// # Arguments
+    ///
+    /// * `mask` - The mask to parse.
+    /// * `custom_charsets` - The parsed custom charsets referenced by `?1` to `?4`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the parsed positions if successful,
+    /// or a `PassForgeError` if the mask is invalid.
+    fn parse(
+        mask: &str,
+        custom_charsets: &[Option<Vec<char>>],
+    ) -> Result<Vec<MaskToken>, PassForgeError> {
+        let mut tokens = Vec::new();
+        let mut chars = mask.chars();
+        while let Some(c) = chars.next() {
+            let token = match c {
+                '?' => {
+                    let placeholder = chars.next().ok_or_else(|| {
+                        PassForgeError::InvalidConfig(
+                            "Mask ends with an incomplete placeholder".into(),
+                        )
+                    })?;
+                    if placeholder == '?' {
+                        MaskToken::Literal('?')
+                    } else {
+                        MaskToken::Class(Self::placeholder(placeholder, custom_charsets)?)
+                    }
+                }
+                '\\' => MaskToken::Literal(chars.next().ok_or_else(|| {
+                    PassForgeError::InvalidConfig("Mask ends with an incomplete escape".into())
+                })?),
+                c if c.is_control() => {
+                    return Err(PassForgeError::InvalidConfig(
+                        "Mask cannot contain control characters".into(),
+                    ))
+                }
+                c => MaskToken::Literal(c),
+            };
+            tokens.push(token);
+        }
+        if tokens.is_empty() {
+            return Err(PassForgeError::InvalidConfig("Mask cannot be empty".into()));
+        }
+        Ok(tokens)
+    }
+
+    /// Parses a template, where `A`, `a` and `9` stand for an uppercase letter, a lowercase
+    /// letter and a digit, into its positions.
+    fn parse_template(template: &str) -> Result<Vec<MaskToken>, PassForgeError> {
+        let mut tokens = Vec::new();
+        let mut chars = template.chars();
+        while let Some(c) = chars.next() {
+            let token = match c {
+                'A' => MaskToken::Class(UPPERCASE.chars().collect()),
+                'a' => MaskToken::Class(LOWERCASE.chars().collect()),
+                '9' => MaskToken::Class(NUMBERS.chars().collect()),
+                '\\' => MaskToken::Literal(chars.next().ok_or_else(|| {
+                    PassForgeError::InvalidConfig("Mask ends with an incomplete escape".into())
+                })?),
+                c if c.is_control() => {
+                    return Err(PassForgeError::InvalidConfig(
+                        "Mask cannot contain control characters".into(),
+                    ))
+                }
+                c => MaskToken::Literal(c),
+            };
+            tokens.push(token);
+        }
+        if tokens.is_empty() {
+            return Err(PassForgeError::InvalidConfig("Mask cannot be empty".into()));
+        }
+        Ok(tokens)
+    }
+
+    /// Resolves the characters of a single placeholder.
+    fn placeholder(
+        placeholder: char,
+        custom_charsets: &[Option<Vec<char>>],
+    ) -> Result<Vec<char>, PassForgeError> {
+        let chars = match placeholder {
+            'l' => LOWERCASE.chars().collect(),
+            'u' => UPPERCASE.chars().collect(),
+            'd' => NUMBERS.chars().collect(),
+            's' => SYMBOLS.chars().collect(),
+            'a' => [LOWERCASE, UPPERCASE, NUMBERS, SYMBOLS]
+                .concat()
+                .chars()
+                .collect(),
+            'h' => "0123456789abcdef".chars().collect(),
+            'H' => "0123456789ABCDEF".chars().collect(),
+            '1'..='4' => {
+                let index = placeholder as usize - '1' as usize;
+                custom_charsets[index].clone().ok_or_else(|| {
+                    PassForgeError::InvalidConfig(format!(
+                        "Mask uses ?{} but custom charset {} is not defined",
+                        placeholder, placeholder
+                    ))
+                })?
+            }
+            other => {
+                return Err(PassForgeError::InvalidConfig(format!(
+                    "Unknown mask placeholder ?{}",
+                    other
+                )))
+            }
+        };
+        Ok(chars)
+    }
+
+    /// Parses a custom charset definition, which may itself use the built-in placeholders,
+    /// into a deduplicated set of characters.
+    fn parse_charset(index: usize, charset: &str) -> Result<Vec<char>, PassForgeError> {
+        if charset.is_empty() {
+            return Err(PassForgeError::InvalidConfig(format!(
+                "Custom charset {} cannot be empty",
+                index + 1
+            )));
+        }
+        let mut seen = BTreeSet::new();
+        let chars = Self::parse(charset, &[None, None, None, None])?
+            .into_iter()
+            .flat_map(|token| match token {
+                MaskToken::Literal(c) => vec![c],
+                MaskToken::Class(chars) => chars,
+            })
+            .filter(|c| seen.insert(*c))
+            .collect();
+        Ok(chars)
+    }
+}
+
+/// A builder for creating `MaskConfig` instances.
+#[derive(Default)]
+pub struct MaskConfigBuilder {
+    mask: Option<String>,
+    syntax: MaskSyntax,
+    custom_charsets: Vec<(usize, String)>,
+}
+
+impl MaskConfigBuilder {
+    /// Sets the mask describing the generated output.
+    pub fn mask(mut self, mask: String) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Sets the syntax the mask is written in. Defaults to [`MaskSyntax::Hashcat`].
+    pub fn syntax(mut self, syntax: MaskSyntax) -> Self {
+        self.syntax = syntax;
+        self
+    }
+
+    /// Sets the custom charset referenced by `?1` to `?4`, where `index` is 1 to 4. Setting the
+    /// same index again replaces the earlier charset.
+    ///
+    /// The charset may use the built-in placeholders, e.g. `?l?d` for lowercase letters and digits.
+    pub fn custom_charset(mut self, index: usize, chars: String) -> Self {
+        self.custom_charsets.push((index, chars));
+        self
+    }
+
+    /// Builds a `MaskConfig` from the current builder state.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if no mask was set, if the mask or a custom charset is invalid,
+    /// if a custom charset index is not between 1 and 4, if the mask has no placeholder, if
+    /// the mask references an undefined custom charset, or if custom charsets are set for a
+    /// template.
+    pub fn build(self) -> Result<MaskConfig, PassForgeError> {
+        let mask = self
+            .mask
+            .ok_or_else(|| PassForgeError::InvalidConfig("A mask is required".into()))?;
+        let tokens = match self.syntax {
+            MaskSyntax::Hashcat => {
+                let mut custom_charsets = vec![None; MaskConfig::CUSTOM_CHARSETS];
+                for (index, charset) in &self.custom_charsets {
+                    let slot = index
+                        .checked_sub(1)
+                        .and_then(|index| custom_charsets.get_mut(index))
+                        .ok_or_else(|| {
+                            PassForgeError::InvalidConfig(format!(
+                                "Custom charset index {} is not between 1 and {}",
+                                index,
+                                MaskConfig::CUSTOM_CHARSETS
+                            ))
+                        })?;
+                    *slot = Some(MaskConfig::parse_charset(index - 1, charset)?);
+                }
+                MaskConfig::parse(&mask, &custom_charsets)?
+            }
+            MaskSyntax::Template => {
+                if !self.custom_charsets.is_empty() {
+                    return Err(PassForgeError::InvalidConfig(
+                        "Custom charsets cannot be used with a template".into(),
+                    ));
+                }
+                MaskConfig::parse_template(&mask)?
+            }
+        };
+        if tokens
+            .iter()
+            .all(|token| matches!(token, MaskToken::Literal(_)))
+        {
+            let example = match self.syntax {
+                MaskSyntax::Hashcat => "?l or ?d",
+                MaskSyntax::Template => "A, a or 9",
+            };
+            return Err(PassForgeError::InvalidConfig(format!(
+                "Mask needs at least one placeholder such as {}",
+                example
+            )));
+        }
+        Ok(MaskConfig {
+            mask,
+            syntax: self.syntax,
+            tokens,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod mask_config_tests {
+        use super::*;
+
+        #[test]
+        fn test_parse_placeholders_and_literals() {
+            let config = MaskConfig::new("?u?l-?d\\?d??").unwrap();
+            let sizes: Vec<usize> = config.tokens().iter().map(MaskToken::size).collect();
+            assert_eq!(sizes, [26, 26, 1, 10, 1, 1, 1]);
+            assert_eq!(config.tokens()[4], MaskToken::Literal('?'));
+            assert_eq!(config.tokens()[5], MaskToken::Literal('d'));
+            assert_eq!(config.tokens()[6], MaskToken::Literal('?'));
+        }
+
+        #[test]
+        fn test_keyspace() {
+            let config = MaskConfig::new("?u?l?l?l?d?d?s").unwrap();
+            let expected = 26u128.pow(4) * 100 * SYMBOLS.len() as u128;
+            assert_eq!(config.keyspace(), Some(expected));
+            assert!((config.entropy_bits() - (expected as f64).log2()).abs() < 1e-9);
+
+            let huge = MaskConfig::new(&"?a".repeat(100)).unwrap();
+            assert_eq!(huge.keyspace(), None);
+        }
+
+        #[test]
+        fn test_custom_charsets() {
+            let config = MaskConfig::builder()
+                .mask("?1?1?2".into())
+                .custom_charset(1, "?dabcaa".into())
+                .custom_charset(2, "xyz".into())
+                .build()
+                .unwrap();
+            assert_eq!(config.keyspace(), Some(13 * 13 * 3));
+        }
+
+        #[test]
+        fn test_invalid_masks() {
+            assert!(MaskConfig::new("").is_err());
+            assert!(MaskConfig::new("?d?").is_err());
+            assert!(MaskConfig::new("abc\\").is_err());
+            assert!(MaskConfig::new("?x").is_err());
+            assert!(MaskConfig::new("?1").is_err());
+            assert!(MaskConfig::builder().build().is_err());
+        }
+
+        #[test]
+        fn test_mask_without_placeholder() {
+            assert!(MaskConfig::new("Aaaa-9999-aaaa").is_err());
+            assert_eq!(
+                template("Aaaa-9999-aaaa").unwrap().keyspace(),
+                Some(26 * 26u128.pow(3) * 10u128.pow(4) * 26u128.pow(4))
+            );
+            assert!(MaskConfig::new("??\\?d").is_err());
+            assert!(MaskConfig::new("Aaaa-?d").is_ok());
+            assert!(template("-\\A\\a\\9-").is_err());
+        }
+
+        fn template(template: &str) -> Result<MaskConfig, PassForgeError> {
+            MaskConfig::builder()
+                .mask(template.into())
+                .syntax(MaskSyntax::Template)
+                .build()
+        }
+
+        #[test]
+        fn test_template_keyspace() {
+            let config = template("Aaaa-9999-aaaa").unwrap();
+            assert_eq!(config.syntax(), MaskSyntax::Template);
+            assert_eq!(config.keyspace(), Some(26u128.pow(8) * 10u128.pow(4)));
+            let sizes: Vec<usize> = config.tokens().iter().map(MaskToken::size).collect();
+            assert_eq!(
+                sizes,
+                [26, 26, 26, 26, 1, 10, 10, 10, 10, 1, 26, 26, 26, 26]
+            );
+        }
+
+        #[test]
+        fn test_template_entropy() {
+            let config = template("Aaaa-9999-aaaa").unwrap();
+            let expected = 8.0 * 26f64.log2() + 4.0 * 10f64.log2();
+            assert!((config.entropy_bits() - expected).abs() < 1e-9);
+        }
+
+        #[test]
+        fn test_template_literals_and_escapes() {
+            let config = template("\\A-a?9").unwrap();
+            assert_eq!(config.tokens()[0], MaskToken::Literal('A'));
+            assert_eq!(config.tokens()[1], MaskToken::Literal('-'));
+            assert_eq!(config.tokens()[3], MaskToken::Literal('?'));
+            assert_eq!(config.keyspace(), Some(26 * 10));
+            assert!(template("a\\").is_err());
+            assert!(MaskConfig::builder()
+                .mask("a?1".into())
+                .syntax(MaskSyntax::Template)
+                .custom_charset(1, "xyz".into())
+                .build()
+                .is_err());
+        }
+
+        #[test]
+        fn test_custom_charset_index_out_of_range() {
+            for index in [0, 5] {
+                let result = MaskConfig::builder()
+                    .mask("?1".into())
+                    .custom_charset(1, "abc".into())
+                    .custom_charset(index, "xyz".into())
+                    .build();
+                assert!(result.is_err());
+            }
+        }
+    }
+}
@@ -0,0 +1,242 @@
+//! This module defines the configuration for one-time password (OTP) secrets.
+//!
+//! The secrets are shared keys for HOTP (RFC 4226) and TOTP (RFC 6238), written in unpadded
+//! RFC 4648 base32 as authenticator apps expect. The configuration also holds the code
+//! parameters that end up in the provisioning URI: the number of digits, the time step and
+//! the HMAC algorithm.
+
+use alloc::format;
+
+use crate::PassForgeError;
+
+/// The HMAC hash function used to compute one-time passwords.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OtpAlgorithm {
+    /// HMAC-SHA-1, the RFC 4226 default and the only algorithm some authenticator apps support.
+    Sha1,
+    /// HMAC-SHA-256.
+    Sha256,
+    /// HMAC-SHA-512.
+    Sha512,
+}
+
+impl OtpAlgorithm {
+    /// Every algorithm, in the order the names are listed in.
+    pub const ALL: [OtpAlgorithm; 3] = [
+        OtpAlgorithm::Sha1,
+        OtpAlgorithm::Sha256,
+        OtpAlgorithm::Sha512,
+    ];
+
+    /// Looks up an algorithm by its name: `sha1`, `sha256` or `sha512`, ignoring case.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the name is not one of the above.
+    pub fn from_name(name: &str) -> Result<Self, PassForgeError> {
+        Self::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                PassForgeError::InvalidConfig(format!(
+                    "Unknown OTP algorithm \"{}\". Choices are: sha1, sha256, sha512",
+                    name
+                ))
+            })
+    }
+
+    /// Returns the name of the algorithm as written in `otpauth` URIs, such as `SHA1`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            OtpAlgorithm::Sha1 => "SHA1",
+            OtpAlgorithm::Sha256 => "SHA256",
+            OtpAlgorithm::Sha512 => "SHA512",
+        }
+    }
+}
+
+/// Represents the configuration options for OTP secrets and codes.
+#[derive(Debug, Clone)]
+pub struct OtpConfig {
+    /// The number of random bytes in a generated secret.
+    pub secret_bytes: usize,
+    /// The number of digits in a code.
+    pub digits: u32,
+    /// The TOTP time step in seconds.
+    pub period: u64,
+    /// The HMAC algorithm codes are computed with.
+    pub algorithm: OtpAlgorithm,
+}
+
+impl OtpConfig {
+    /// The default secret size, the 160 bits recommended by RFC 4226.
+    pub const DEFAULT_SECRET_BYTES: usize = 20;
+    /// The minimum secret size, the 128 bits required by RFC 4226.
+    pub const MIN_SECRET_BYTES: usize = 16;
+    /// The maximum secret size.
+    pub const MAX_SECRET_BYTES: usize = 128;
+    /// The default number of digits in a code.
+    pub const DEFAULT_DIGITS: u32 = 6;
+    /// The default TOTP time step in seconds.
+    pub const DEFAULT_PERIOD: u64 = 30;
+    /// The default algorithm.
+    pub const DEFAULT_ALGORITHM: OtpAlgorithm = OtpAlgorithm::Sha1;
+
+    /// Creates a new `OtpConfig` with a 20 byte secret and the given code parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `digits` - The number of digits in a code, from 6 to 8.
+    /// * `period` - The TOTP time step in seconds.
+    /// * `algorithm` - The HMAC algorithm codes are computed with.
+    pub fn new(digits: u32, period: u64, algorithm: OtpAlgorithm) -> Self {
+        Self {
+            secret_bytes: Self::DEFAULT_SECRET_BYTES,
+            digits,
+            period,
+            algorithm,
+        }
+    }
+
+    /// Returns a new `OtpConfigBuilder` for creating an `OtpConfig`.
+    pub fn builder() -> OtpConfigBuilder {
+        OtpConfigBuilder::default()
+    }
+
+    /// Returns the entropy of the generated secrets in bits, which is 8 bits per random byte.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the configuration is invalid.
+    pub fn entropy_bits(&self) -> Result<f64, PassForgeError> {
+        self.check()?;
+        self.check_secret_bytes()?;
+        Ok(self.secret_bytes as f64 * 8.0)
+    }
+
+    /// Checks the code parameters: 6 to 8 digits and a time step of at least one second.
+    pub(crate) fn check(&self) -> Result<(), PassForgeError> {
+        if !(6..=8).contains(&self.digits) {
+            return Err(PassForgeError::InvalidConfig(
+                "OTP codes must have between 6 and 8 digits".into(),
+            ));
+        }
+        if self.period == 0 {
+            return Err(PassForgeError::InvalidConfig(
+                "OTP period must be at least 1 second".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that the secret size is between `MIN_SECRET_BYTES` and `MAX_SECRET_BYTES`. Only
+    /// generated secrets are held to this, so codes can still be computed for existing
+    /// shorter secrets.
+    pub(crate) fn check_secret_bytes(&self) -> Result<(), PassForgeError> {
+        if self.secret_bytes < Self::MIN_SECRET_BYTES || self.secret_bytes > Self::MAX_SECRET_BYTES
+        {
+            return Err(PassForgeError::InvalidLength(format!(
+                "OTP secret must have between {} and {} bytes",
+                Self::MIN_SECRET_BYTES,
+                Self::MAX_SECRET_BYTES
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A builder for creating `OtpConfig` instances.
+#[derive(Default)]
+pub struct OtpConfigBuilder {
+    secret_bytes: Option<usize>,
+    digits: Option<u32>,
+    period: Option<u64>,
+    algorithm: Option<OtpAlgorithm>,
+}
+
+impl OtpConfigBuilder {
+    /// Sets the number of random bytes in a generated secret.
+    pub fn secret_bytes(mut self, secret_bytes: usize) -> Self {
+        self.secret_bytes = Some(secret_bytes);
+        self
+    }
+
+    /// Sets the number of digits in a code.
+    pub fn digits(mut self, digits: u32) -> Self {
+        self.digits = Some(digits);
+        self
+    }
+
+    /// Sets the TOTP time step in seconds.
+    pub fn period(mut self, period: u64) -> Self {
+        self.period = Some(period);
+        self
+    }
+
+    /// Sets the HMAC algorithm codes are computed with.
+    pub fn algorithm(mut self, algorithm: OtpAlgorithm) -> Self {
+        self.algorithm = Some(algorithm);
+        self
+    }
+
+    /// Builds an `OtpConfig` from the current builder state.
+    /// Unless set otherwise, secrets have 20 bytes and codes 6 digits every 30 seconds with
+    /// SHA-1, the parameters every authenticator app supports.
+    pub fn build(self) -> OtpConfig {
+        OtpConfig {
+            secret_bytes: self.secret_bytes.unwrap_or(OtpConfig::DEFAULT_SECRET_BYTES),
+            digits: self.digits.unwrap_or(OtpConfig::DEFAULT_DIGITS),
+            period: self.period.unwrap_or(OtpConfig::DEFAULT_PERIOD),
+            algorithm: self.algorithm.unwrap_or(OtpConfig::DEFAULT_ALGORITHM),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod otp_config_tests {
+        use super::*;
+
+        #[test]
+        fn test_algorithm_names() {
+            for algorithm in OtpAlgorithm::ALL {
+                assert_eq!(
+                    OtpAlgorithm::from_name(algorithm.name()).unwrap(),
+                    algorithm
+                );
+            }
+            assert_eq!(
+                OtpAlgorithm::from_name("sha256").unwrap(),
+                OtpAlgorithm::Sha256
+            );
+            assert!(OtpAlgorithm::from_name("md5").is_err());
+        }
+
+        #[test]
+        fn test_builder_defaults() {
+            let config = OtpConfig::builder().build();
+            assert_eq!(config.secret_bytes, 20);
+            assert_eq!(config.digits, 6);
+            assert_eq!(config.period, 30);
+            assert_eq!(config.algorithm, OtpAlgorithm::Sha1);
+            assert_eq!(config.entropy_bits().unwrap(), 160.0);
+        }
+
+        #[test]
+        fn test_invalid_config() {
+            assert!(OtpConfig::new(5, 30, OtpAlgorithm::Sha1).check().is_err());
+            assert!(OtpConfig::new(9, 30, OtpAlgorithm::Sha1).check().is_err());
+            assert!(OtpConfig::new(8, 0, OtpAlgorithm::Sha1).check().is_err());
+            assert!(OtpConfig::new(8, 60, OtpAlgorithm::Sha512).check().is_ok());
+
+            let short = OtpConfig::builder().secret_bytes(10).build();
+            assert!(short.check().is_ok());
+            assert!(matches!(
+                short.entropy_bits(),
+                Err(PassForgeError::InvalidLength(_))
+            ));
+        }
+    }
+}
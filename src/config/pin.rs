@@ -0,0 +1,423 @@
+//! This module defines the configuration for numeric PIN generation.
+//!
+//! PINs are drawn uniformly from all digit strings of the configured length, except those
+//! matching one of the rejected weak patterns. Because the weak PINs are enumerated exactly,
+//! the keyspace and entropy account for the rejection.
+
+use alloc::{
+    collections::BTreeSet,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::PassForgeError;
+
+/// Frequently used PINs, rejected by `WeakPinPattern::Common`.
+pub const COMMON_PINS: &[&str] = &[
+    "1234", "1111", "0000", "1212", "7777", "1004", "2000", "4444", "2222", "6969", "9999", "3333",
+    "5555", "6666", "1122", "1313", "8888", "4321", "2001", "1010", "2580", "0852", "1357", "2468",
+    "123456", "654321", "111111", "000000", "123123", "666666", "121212", "112233", "789456",
+    "159753", "147258", "123321", "696969", "101010", "12345678", "87654321", "11111111",
+    "00000000", "12341234", "11223344",
+];
+
+/// A class of easily guessed PINs that can be rejected during generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WeakPinPattern {
+    /// Every digit is the same, e.g. `0000`.
+    AllSame,
+    /// The digits form an ascending or descending run, e.g. `1234` or `9876`.
+    Sequential,
+    /// The PIN repeats a two digit block, e.g. `1212`, or is built from doubled digits, e.g. `1122`.
+    RepeatedPairs,
+    /// The PIN reads as a calendar date or year: `MMDD`, `DDMM` or `YYYY` (1900-2099) for four
+    /// digits, `DDMMYY`, `MMDDYY` or `YYMMDD` for six and `DDMMYYYY`, `MMDDYYYY` or `YYYYMMDD`
+    /// for eight.
+    Date,
+    /// The PIN is one of the `COMMON_PINS`.
+    Common,
+}
+
+impl WeakPinPattern {
+    /// Every weak pattern, rejected by default.
+    pub const ALL: [WeakPinPattern; 5] = [
+        WeakPinPattern::AllSame,
+        WeakPinPattern::Sequential,
+        WeakPinPattern::RepeatedPairs,
+        WeakPinPattern::Date,
+        WeakPinPattern::Common,
+    ];
+
+    /// Returns `true` if the PIN matches this pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - The PIN to check, consisting of ASCII digits only.
+    pub fn matches(&self, pin: &str) -> bool {
+        let digits = pin.as_bytes();
+        match self {
+            WeakPinPattern::AllSame => digits.windows(2).all(|pair| pair[0] == pair[1]),
+            WeakPinPattern::Sequential => {
+                digits.len() > 1
+                    && (digits.windows(2).all(|pair| pair[1] == pair[0] + 1)
+                        || digits.windows(2).all(|pair| pair[1] + 1 == pair[0]))
+            }
+            WeakPinPattern::RepeatedPairs => {
+                digits.len() >= 4
+                    && digits.len().is_multiple_of(2)
+                    && (digits.iter().skip(2).zip(digits).all(|(a, b)| a == b)
+                        || digits.chunks(2).all(|pair| pair[0] == pair[1]))
+            }
+            WeakPinPattern::Date => date_formats(digits.len())
+                .iter()
+                .any(|format| format.matches(pin)),
+            WeakPinPattern::Common => COMMON_PINS.contains(&pin),
+        }
+    }
+
+    /// Lists every PIN of the given length matching this pattern.
+    fn enumerate(&self, length: usize) -> Vec<String> {
+        match self {
+            WeakPinPattern::AllSame => (0..10u8)
+                .map(|d| char::from(b'0' + d).to_string().repeat(length))
+                .collect(),
+            WeakPinPattern::Sequential if length > 1 && length <= 10 => (0..=(10 - length) as u8)
+                .flat_map(|start| {
+                    let ascending: String = (start..start + length as u8)
+                        .map(|d| char::from(b'0' + d))
+                        .collect();
+                    let descending = ascending.chars().rev().collect();
+                    [ascending, descending]
+                })
+                .collect(),
+            WeakPinPattern::Sequential => Vec::new(),
+            WeakPinPattern::RepeatedPairs if length >= 4 && length.is_multiple_of(2) => {
+                let repeated = (0..100).map(|block| format!("{:02}", block).repeat(length / 2));
+                let doubled = (0..10u64.pow(length as u32 / 2)).map(|half| {
+                    format!("{:0width$}", half, width = length / 2)
+                        .chars()
+                        .flat_map(|c| [c, c])
+                        .collect()
+                });
+                repeated.chain(doubled).collect()
+            }
+            WeakPinPattern::RepeatedPairs => Vec::new(),
+            WeakPinPattern::Date => date_formats(length)
+                .iter()
+                .flat_map(DateFormat::enumerate)
+                .collect(),
+            WeakPinPattern::Common => COMMON_PINS
+                .iter()
+                .filter(|pin| pin.len() == length)
+                .map(|pin| pin.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// A single field of a date layout.
+#[derive(Debug, Clone, Copy)]
+enum DateField {
+    Day,
+    Month,
+    ShortYear,
+    Year,
+}
+
+/// A date layout, described by the order of its fields. Years are four digits wide,
+/// every other field two.
+#[derive(Debug, Clone, Copy)]
+struct DateFormat(&'static [DateField]);
+
+/// The date formats that apply to PINs of the given length.
+fn date_formats(length: usize) -> &'static [DateFormat] {
+    use DateField::*;
+    match length {
+        4 => &[
+            DateFormat(&[Month, Day]),
+            DateFormat(&[Day, Month]),
+            DateFormat(&[Year]),
+        ],
+        6 => &[
+            DateFormat(&[Day, Month, ShortYear]),
+            DateFormat(&[Month, Day, ShortYear]),
+            DateFormat(&[ShortYear, Month, Day]),
+        ],
+        8 => &[
+            DateFormat(&[Day, Month, Year]),
+            DateFormat(&[Month, Day, Year]),
+            DateFormat(&[Year, Month, Day]),
+        ],
+        _ => &[],
+    }
+}
+
+/// Returns the number of days in a month, allowing February 29th.
+fn days_in_month(month: u32) -> u32 {
+    match month {
+        2 => 29,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl DateFormat {
+    /// Returns `true` if the PIN is a valid date in this format.
+    fn matches(&self, pin: &str) -> bool {
+        let (mut day, mut month, mut offset) = (None, None, 0);
+        for field in self.0 {
+            let width = if matches!(field, DateField::Year) {
+                4
+            } else {
+                2
+            };
+            let Some(value) = pin
+                .get(offset..offset + width)
+                .and_then(|part| part.parse::<u32>().ok())
+            else {
+                return false;
+            };
+            offset += width;
+            match field {
+                DateField::Day => day = Some(value),
+                DateField::Month => month = Some(value),
+                DateField::ShortYear => {}
+                DateField::Year if (1900..=2099).contains(&value) => {}
+                DateField::Year => return false,
+            }
+        }
+        let month_valid = month.is_none_or(|month| (1..=12).contains(&month));
+        let day_valid = day.is_none_or(|day| day >= 1 && day <= month.map_or(31, days_in_month));
+        month_valid && day_valid
+    }
+
+    /// Lists every PIN that is a valid date in this format.
+    fn enumerate(&self) -> Vec<String> {
+        let has = |field: fn(&DateField) -> bool| self.0.iter().any(field);
+        let years: Vec<u32> = if has(|f| matches!(f, DateField::Year)) {
+            (1900..=2099).collect()
+        } else if has(|f| matches!(f, DateField::ShortYear)) {
+            (0..100).collect()
+        } else {
+            vec![0]
+        };
+        let months: Vec<u32> = if has(|f| matches!(f, DateField::Month)) {
+            (1..=12).collect()
+        } else {
+            vec![0]
+        };
+
+        let mut pins = Vec::new();
+        for &year in &years {
+            for &month in &months {
+                let days: Vec<u32> = if has(|f| matches!(f, DateField::Day)) {
+                    (1..=days_in_month(month)).collect()
+                } else {
+                    vec![0]
+                };
+                for day in days {
+                    let pin = self
+                        .0
+                        .iter()
+                        .map(|field| match field {
+                            DateField::Day => format!("{:02}", day),
+                            DateField::Month => format!("{:02}", month),
+                            DateField::ShortYear => format!("{:02}", year),
+                            DateField::Year => format!("{:04}", year),
+                        })
+                        .collect();
+                    pins.push(pin);
+                }
+            }
+        }
+        pins
+    }
+}
+
+/// Represents the configuration options for PIN generation.
+#[derive(Debug, Clone)]
+pub struct PinConfig {
+    /// The number of digits in the PIN.
+    pub length: usize,
+    /// The weak patterns that generated PINs must not match.
+    pub reject: Vec<WeakPinPattern>,
+}
+
+impl PinConfig {
+    /// The default number of digits in a PIN.
+    pub const DEFAULT_LENGTH: usize = 6;
+    /// The maximum number of digits in a PIN.
+    pub const MAX_LENGTH: usize = 12;
+
+    /// Creates a new `PinConfig` with the specified options.
+    ///
+    /// # Arguments
+    ///
+    /// * `length` - The number of digits in the PIN.
+    /// * `reject` - The weak patterns that generated PINs must not match.
+    pub fn new(length: usize, reject: Vec<WeakPinPattern>) -> Self {
+        Self { length, reject }
+    }
+
+    /// Returns a new `PinConfigBuilder` for creating a `PinConfig`.
+    pub fn builder() -> PinConfigBuilder {
+        PinConfigBuilder::default()
+    }
+
+    /// Returns the first rejected pattern the PIN matches, if any.
+    pub fn weakness(&self, pin: &str) -> Option<WeakPinPattern> {
+        self.reject
+            .iter()
+            .copied()
+            .find(|pattern| pattern.matches(pin))
+    }
+
+    /// Returns the number of PINs that can be generated, i.e. all PINs of the configured
+    /// length minus the rejected ones.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the length is not between 1 and `MAX_LENGTH`,
+    /// or if every PIN of that length is rejected.
+    pub fn keyspace(&self) -> Result<u64, PassForgeError> {
+        self.check_length()?;
+        let rejected: BTreeSet<String> = self
+            .reject
+            .iter()
+            .flat_map(|pattern| pattern.enumerate(self.length))
+            .collect();
+        let keyspace = 10u64.pow(self.length as u32) - rejected.len() as u64;
+        if keyspace == 0 {
+            return Err(PassForgeError::InvalidConfig(format!(
+                "Every PIN of length {} is rejected as weak",
+                self.length
+            )));
+        }
+        Ok(keyspace)
+    }
+
+    /// Returns the entropy of the generated PINs in bits.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the configuration cannot produce a PIN.
+    pub fn entropy_bits(&self) -> Result<f64, PassForgeError> {
+        Ok((self.keyspace()? as f64).log2())
+    }
+
+    /// Returns how many bits of entropy are lost by rejecting weak PINs.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the configuration cannot produce a PIN.
+    pub fn entropy_loss_bits(&self) -> Result<f64, PassForgeError> {
+        Ok(self.length as f64 * 10f64.log2() - self.entropy_bits()?)
+    }
+
+    /// Checks that the length is between 1 and `MAX_LENGTH`.
+    pub(crate) fn check_length(&self) -> Result<(), PassForgeError> {
+        if self.length < 1 || self.length > Self::MAX_LENGTH {
+            return Err(PassForgeError::InvalidLength(format!(
+                "PIN length must be between 1 and {}",
+                Self::MAX_LENGTH
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A builder for creating `PinConfig` instances.
+#[derive(Default)]
+pub struct PinConfigBuilder {
+    length: Option<usize>,
+    reject: Option<Vec<WeakPinPattern>>,
+}
+
+impl PinConfigBuilder {
+    /// Sets the number of digits in the PIN.
+    pub fn length(mut self, length: usize) -> Self {
+        self.length = Some(length);
+        self
+    }
+
+    /// Sets the weak patterns that generated PINs must not match.
+    pub fn reject(mut self, patterns: Vec<WeakPinPattern>) -> Self {
+        self.reject = Some(patterns);
+        self
+    }
+
+    /// Builds a `PinConfig` from the current builder state.
+    /// Unless set otherwise, every weak pattern is rejected.
+    pub fn build(self) -> PinConfig {
+        PinConfig {
+            length: self.length.unwrap_or(PinConfig::DEFAULT_LENGTH),
+            reject: self.reject.unwrap_or_else(|| WeakPinPattern::ALL.to_vec()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod pin_config_tests {
+        use super::*;
+
+        #[test]
+        fn test_pattern_matching() {
+            assert!(WeakPinPattern::AllSame.matches("7777"));
+            assert!(WeakPinPattern::Sequential.matches("3456"));
+            assert!(WeakPinPattern::Sequential.matches("987654"));
+            assert!(WeakPinPattern::RepeatedPairs.matches("4545"));
+            assert!(WeakPinPattern::RepeatedPairs.matches("112299"));
+            assert!(WeakPinPattern::Date.matches("1225"));
+            assert!(WeakPinPattern::Date.matches("1987"));
+            assert!(WeakPinPattern::Date.matches("19871225"));
+            assert!(!WeakPinPattern::Date.matches("1332"));
+            assert!(!WeakPinPattern::Date.matches("18991225"));
+            assert!(WeakPinPattern::Common.matches("2580"));
+            let config = PinConfig::builder().length(4).build();
+            assert_eq!(config.weakness("8305"), None);
+        }
+
+        #[test]
+        fn test_enumeration_agrees_with_matching() {
+            for length in [2, 3, 4, 5, 6] {
+                for pattern in WeakPinPattern::ALL {
+                    let enumerated: BTreeSet<String> =
+                        pattern.enumerate(length).into_iter().collect();
+                    let matched = (0..10u32.pow(length as u32))
+                        .map(|pin| format!("{:0width$}", pin, width = length))
+                        .filter(|pin| pattern.matches(pin))
+                        .count();
+                    assert_eq!(enumerated.len(), matched, "{:?} {}", pattern, length);
+                    assert!(enumerated.iter().all(|pin| pattern.matches(pin)));
+                }
+            }
+        }
+
+        #[test]
+        fn test_keyspace_and_entropy() {
+            let config = PinConfig::new(4, vec![WeakPinPattern::AllSame]);
+            assert_eq!(config.keyspace().unwrap(), 9990);
+            let loss = config.entropy_loss_bits().unwrap();
+            assert!(loss > 0.0 && loss < 0.01);
+
+            let unrestricted = PinConfig::new(6, Vec::new());
+            assert_eq!(unrestricted.keyspace().unwrap(), 1_000_000);
+        }
+
+        #[test]
+        fn test_invalid_configs() {
+            assert!(PinConfig::new(0, Vec::new()).keyspace().is_err());
+            assert!(PinConfig::new(13, Vec::new()).keyspace().is_err());
+            assert!(PinConfig::new(1, vec![WeakPinPattern::AllSame])
+                .keyspace()
+                .is_err());
+        }
+    }
+}
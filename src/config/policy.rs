@@ -0,0 +1,503 @@
+//! This module defines password policies, the rules a site or system imposes on passwords.
+//!
+//! A `PasswordPolicy` both drives generation, through the `PasswordConfig` it resolves to,
+//! and checks existing passwords, reporting every rule they break.
+
+use alloc::{string::String, vec::Vec};
+
+use thiserror::Error;
+
+use crate::config::{Alphabet, CharClass, ClassCount, Length, PasswordConfig};
+use crate::PassForgeError;
+
+/// A single rule of a `PasswordPolicy` that a password breaks.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PolicyViolation {
+    /// The password has fewer characters than the policy requires.
+    #[error("password has {length} characters but at least {min} are required")]
+    TooShort {
+        /// The length of the password.
+        length: usize,
+        /// The minimum length of the policy.
+        min: usize,
+    },
+    /// The password has more characters than the policy allows.
+    #[error("password has {length} characters but at most {max} are allowed")]
+    TooLong {
+        /// The length of the password.
+        length: usize,
+        /// The maximum length of the policy.
+        max: usize,
+    },
+    /// The password contains a character that is forbidden or not in any allowed class.
+    #[error("character {0:?} is not allowed")]
+    DisallowedChar(char),
+    /// The password has fewer characters of a class than the policy requires.
+    #[error("password has {found} {class} but at least {min} are required")]
+    TooFew {
+        /// The name of the class.
+        class: String,
+        /// The number of characters of the class in the password.
+        found: usize,
+        /// The minimum of the class.
+        min: usize,
+    },
+    /// The password has more characters of a class than the policy allows.
+    #[error("password has {found} {class} but at most {max} are allowed")]
+    TooMany {
+        /// The name of the class.
+        class: String,
+        /// The number of characters of the class in the password.
+        found: usize,
+        /// The maximum of the class.
+        max: usize,
+    },
+    /// The same character appears more often in a row than the policy allows.
+    #[error("character {c:?} appears {run} times in a row but at most {max} are allowed")]
+    TooManyConsecutive {
+        /// The repeated character.
+        c: char,
+        /// The length of the run.
+        run: usize,
+        /// The maximum run of the policy.
+        max: usize,
+    },
+    /// The password does not start with a letter.
+    #[error("password must start with a letter")]
+    MustStartWithLetter,
+}
+
+/// Describes the rules a password must follow.
+///
+/// Each built-in class is either forbidden (`None`) or allowed with bounds on how many of its
+/// characters must or may appear; a minimum above 0 makes the class required.
+#[derive(Debug, Clone)]
+pub struct PasswordPolicy {
+    /// The minimum number of characters.
+    pub min_length: usize,
+    /// The maximum number of characters, if limited.
+    pub max_length: Option<usize>,
+    /// The rule for lowercase letters, or `None` if they are forbidden.
+    pub lowercase: Option<ClassCount>,
+    /// The rule for capital letters, or `None` if they are forbidden.
+    pub capitals: Option<ClassCount>,
+    /// The rule for numbers, or `None` if they are forbidden.
+    pub numbers: Option<ClassCount>,
+    /// The rule for symbols, or `None` if they are forbidden.
+    pub symbols: Option<ClassCount>,
+    /// The allowed symbols, if they differ from the default symbol set.
+    pub symbol_set: Option<String>,
+    /// Additional allowed character classes with their own bounds.
+    pub custom_classes: Vec<CharClass>,
+    /// Characters that must never appear, even if a class contains them.
+    pub forbidden_chars: String,
+    /// The maximum number of times the same character may appear in a row, if limited.
+    pub max_consecutive: Option<usize>,
+    /// Whether the password must start with a letter.
+    pub start_with_letter: bool,
+}
+
+impl PasswordPolicy {
+    /// The default minimum length of a policy.
+    pub const DEFAULT_MIN_LENGTH: usize = 1;
+
+    /// Creates a new `PasswordPolicy` that allows every built-in class without further rules.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_length` - The minimum number of characters.
+    /// * `max_length` - The maximum number of characters, or `None` for no limit.
+    pub fn new(min_length: usize, max_length: Option<usize>) -> Self {
+        Self {
+            min_length,
+            max_length,
+            lowercase: Some(ClassCount::default()),
+            capitals: Some(ClassCount::default()),
+            numbers: Some(ClassCount::default()),
+            symbols: Some(ClassCount::default()),
+            symbol_set: None,
+            custom_classes: Vec::new(),
+            forbidden_chars: String::new(),
+            max_consecutive: None,
+            start_with_letter: false,
+        }
+    }
+
+    /// Returns a new `PasswordPolicyBuilder` for creating a `PasswordPolicy`.
+    pub fn builder() -> PasswordPolicyBuilder {
+        PasswordPolicyBuilder::default()
+    }
+
+    /// Resolves the `PasswordConfig` that generates passwords conforming to the policy.
+    ///
+    /// The generated length is the default password length, raised to the minimum length and
+    /// the number of required characters, and capped at the maximum length. The `length` field
+    /// of the returned config can be changed to any length the policy allows.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the `PasswordConfig` if successful,
+    /// or a `PassForgeError` if the policy is contradictory.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the maximum length is smaller than the minimum length.
+    pub fn to_config(&self) -> Result<PasswordConfig, PassForgeError> {
+        if self.max_length.is_some_and(|max| max < self.min_length) {
+            return Err(PassForgeError::InvalidLength(
+                "Maximum length of the policy cannot be smaller than the minimum length".into(),
+            ));
+        }
+        let required: usize = [self.lowercase, self.capitals, self.numbers, self.symbols]
+            .iter()
+            .flatten()
+            .map(|count| count.min)
+            .chain(self.custom_classes.iter().map(|class| class.count.min))
+            .sum();
+        let length = PasswordConfig::DEFAULT_LENGTH
+            .max(self.min_length)
+            .max(required)
+            .min(self.max_length.unwrap_or(usize::MAX));
+
+        let count = |rule: Option<ClassCount>| rule.unwrap_or_default();
+        Ok(PasswordConfig {
+            lowercase: self.lowercase.is_some(),
+            capitals: self.capitals.is_some(),
+            numbers: self.numbers.is_some(),
+            symbols: self.symbols.is_some(),
+            lowercase_count: count(self.lowercase),
+            capitals_count: count(self.capitals),
+            numbers_count: count(self.numbers),
+            symbols_count: count(self.symbols),
+            symbol_set: self.symbol_set.clone(),
+            custom_classes: self.custom_classes.clone(),
+            excluded_chars: self.forbidden_chars.clone(),
+            max_consecutive: self.max_consecutive,
+            start_with_letter: self.start_with_letter,
+            ..PasswordConfig::new(Length::Single(length), true, true, true)
+        })
+    }
+
+    /// Checks a password against every rule of the policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to check.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing every violated rule, which is empty if the password
+    /// conforms, or a `PassForgeError` if the policy itself is contradictory.
+    pub fn validate(&self, password: &str) -> Result<Vec<PolicyViolation>, PassForgeError> {
+        let alphabet = Alphabet::from_config(&self.to_config()?)?;
+        let chars: Vec<char> = password.chars().collect();
+        let mut violations = Vec::new();
+
+        if chars.len() < self.min_length {
+            violations.push(PolicyViolation::TooShort {
+                length: chars.len(),
+                min: self.min_length,
+            });
+        }
+        if let Some(max) = self.max_length.filter(|max| chars.len() > *max) {
+            violations.push(PolicyViolation::TooLong {
+                length: chars.len(),
+                max,
+            });
+        }
+        if self.start_with_letter && !chars.first().is_some_and(|c| c.is_alphabetic()) {
+            violations.push(PolicyViolation::MustStartWithLetter);
+        }
+
+        let mut disallowed = Vec::new();
+        for c in &chars {
+            if !alphabet.contains(*c) && !disallowed.contains(c) {
+                disallowed.push(*c);
+            }
+        }
+        violations.extend(disallowed.into_iter().map(PolicyViolation::DisallowedChar));
+
+        for class in alphabet.classes() {
+            let found = chars.iter().filter(|c| class.chars.contains(c)).count();
+            if found < class.count.min {
+                violations.push(PolicyViolation::TooFew {
+                    class: class.name.clone(),
+                    found,
+                    min: class.count.min,
+                });
+            }
+            if let Some(max) = class.count.max.filter(|max| found > *max) {
+                violations.push(PolicyViolation::TooMany {
+                    class: class.name.clone(),
+                    found,
+                    max,
+                });
+            }
+        }
+
+        if let Some(max) = self.max_consecutive {
+            for run in chars.chunk_by(|a, b| a == b) {
+                if run.len() > max {
+                    violations.push(PolicyViolation::TooManyConsecutive {
+                        c: run[0],
+                        run: run.len(),
+                        max,
+                    });
+                }
+            }
+        }
+        Ok(violations)
+    }
+}
+
+/// A builder for creating `PasswordPolicy` instances.
+#[derive(Default)]
+pub struct PasswordPolicyBuilder {
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    forbid_lowercase: bool,
+    forbid_capitals: bool,
+    forbid_numbers: bool,
+    forbid_symbols: bool,
+    lowercase: ClassCount,
+    capitals: ClassCount,
+    numbers: ClassCount,
+    symbols: ClassCount,
+    symbol_set: Option<String>,
+    custom_classes: Vec<CharClass>,
+    forbidden_chars: String,
+    max_consecutive: Option<usize>,
+    start_with_letter: bool,
+}
+
+impl PasswordPolicyBuilder {
+    /// Sets the minimum number of characters.
+    pub fn min_length(mut self, length: usize) -> Self {
+        self.min_length = Some(length);
+        self
+    }
+
+    /// Sets the maximum number of characters.
+    pub fn max_length(mut self, length: usize) -> Self {
+        self.max_length = Some(length);
+        self
+    }
+
+    /// Requires at least `min` lowercase letters.
+    pub fn require_lowercase(mut self, min: usize) -> Self {
+        self.lowercase.min = min;
+        self
+    }
+
+    /// Requires at least `min` capital letters.
+    pub fn require_capitals(mut self, min: usize) -> Self {
+        self.capitals.min = min;
+        self
+    }
+
+    /// Requires at least `min` numbers.
+    pub fn require_numbers(mut self, min: usize) -> Self {
+        self.numbers.min = min;
+        self
+    }
+
+    /// Requires at least `min` symbols.
+    pub fn require_symbols(mut self, min: usize) -> Self {
+        self.symbols.min = min;
+        self
+    }
+
+    /// Allows at most `max` lowercase letters.
+    pub fn max_lowercase(mut self, max: usize) -> Self {
+        self.lowercase.max = Some(max);
+        self
+    }
+
+    /// Allows at most `max` capital letters.
+    pub fn max_capitals(mut self, max: usize) -> Self {
+        self.capitals.max = Some(max);
+        self
+    }
+
+    /// Allows at most `max` numbers.
+    pub fn max_numbers(mut self, max: usize) -> Self {
+        self.numbers.max = Some(max);
+        self
+    }
+
+    /// Allows at most `max` symbols.
+    pub fn max_symbols(mut self, max: usize) -> Self {
+        self.symbols.max = Some(max);
+        self
+    }
+
+    /// Forbids lowercase letters.
+    pub fn forbid_lowercase(mut self) -> Self {
+        self.forbid_lowercase = true;
+        self
+    }
+
+    /// Forbids capital letters.
+    pub fn forbid_capitals(mut self) -> Self {
+        self.forbid_capitals = true;
+        self
+    }
+
+    /// Forbids numbers.
+    pub fn forbid_numbers(mut self) -> Self {
+        self.forbid_numbers = true;
+        self
+    }
+
+    /// Forbids symbols.
+    pub fn forbid_symbols(mut self) -> Self {
+        self.forbid_symbols = true;
+        self
+    }
+
+    /// Sets the allowed symbols, replacing the default symbol set.
+    pub fn symbol_set(mut self, chars: String) -> Self {
+        self.symbol_set = Some(chars);
+        self
+    }
+
+    /// Adds an allowed character class with its own bounds.
+    pub fn custom_class(mut self, class: CharClass) -> Self {
+        self.custom_classes.push(class);
+        self
+    }
+
+    /// Sets the characters that must never appear.
+    pub fn forbid_chars(mut self, chars: String) -> Self {
+        self.forbidden_chars = chars;
+        self
+    }
+
+    /// Sets the maximum number of times the same character may appear in a row.
+    pub fn max_consecutive(mut self, max: usize) -> Self {
+        self.max_consecutive = Some(max);
+        self
+    }
+
+    /// Sets whether the password must start with a letter.
+    pub fn start_with_letter(mut self, enabled: bool) -> Self {
+        self.start_with_letter = enabled;
+        self
+    }
+
+    /// Builds a `PasswordPolicy` from the current builder state.
+    pub fn build(self) -> PasswordPolicy {
+        let rule = |forbidden: bool, count: ClassCount| (!forbidden).then_some(count);
+        PasswordPolicy {
+            min_length: self
+                .min_length
+                .unwrap_or(PasswordPolicy::DEFAULT_MIN_LENGTH),
+            max_length: self.max_length,
+            lowercase: rule(self.forbid_lowercase, self.lowercase),
+            capitals: rule(self.forbid_capitals, self.capitals),
+            numbers: rule(self.forbid_numbers, self.numbers),
+            symbols: rule(self.forbid_symbols, self.symbols),
+            symbol_set: self.symbol_set,
+            custom_classes: self.custom_classes,
+            forbidden_chars: self.forbidden_chars,
+            max_consecutive: self.max_consecutive,
+            start_with_letter: self.start_with_letter,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::{Generator, PasswordGenerator};
+
+    mod password_policy_tests {
+        use super::*;
+
+        fn site_policy() -> PasswordPolicy {
+            PasswordPolicy::builder()
+                .min_length(10)
+                .max_length(16)
+                .require_capitals(1)
+                .require_numbers(2)
+                .symbol_set("!?#".into())
+                .forbid_chars("#".into())
+                .max_consecutive(2)
+                .start_with_letter(true)
+                .build()
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_generated_passwords_conform() {
+            let policy = site_policy();
+            let config = policy.to_config().unwrap();
+            for _ in 0..50 {
+                let password = PasswordGenerator::generate(&config).unwrap();
+                let password = password.expose_secret();
+                assert_eq!(password.chars().count(), 16);
+                assert_eq!(policy.validate(password).unwrap(), []);
+            }
+        }
+
+        #[test]
+        fn test_every_violation_is_reported() {
+            let violations = site_policy().validate("1aaa#b").unwrap();
+            assert_eq!(
+                violations,
+                [
+                    PolicyViolation::TooShort { length: 6, min: 10 },
+                    PolicyViolation::MustStartWithLetter,
+                    PolicyViolation::DisallowedChar('#'),
+                    PolicyViolation::TooFew {
+                        class: "capital letters".into(),
+                        found: 0,
+                        min: 1,
+                    },
+                    PolicyViolation::TooFew {
+                        class: "numbers".into(),
+                        found: 1,
+                        min: 2,
+                    },
+                    PolicyViolation::TooManyConsecutive {
+                        c: 'a',
+                        run: 3,
+                        max: 2,
+                    },
+                ]
+            );
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_forbidden_classes() {
+            let policy = PasswordPolicy::builder()
+                .forbid_symbols()
+                .forbid_lowercase()
+                .build();
+            let config = policy.to_config().unwrap();
+            let password = PasswordGenerator::generate(&config).unwrap();
+            let password = password.expose_secret();
+            assert!(password
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()));
+            assert_eq!(
+                policy.validate("AB-c").unwrap(),
+                [
+                    PolicyViolation::DisallowedChar('-'),
+                    PolicyViolation::DisallowedChar('c'),
+                ]
+            );
+        }
+
+        #[test]
+        fn test_length_follows_policy() {
+            let short = PasswordPolicy::new(4, Some(8)).to_config().unwrap();
+            assert!(matches!(short.length, Length::Single(8)));
+            let long = PasswordPolicy::new(24, None).to_config().unwrap();
+            assert!(matches!(long.length, Length::Single(24)));
+            assert!(PasswordPolicy::new(10, Some(8)).to_config().is_err());
+        }
+    }
+}
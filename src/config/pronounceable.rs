@@ -0,0 +1,274 @@
+//! This module defines the configuration for pronounceable password generation.
+//!
+//! Pronounceable passwords are built from alternating consonant and vowel units, optionally
+//! with a number of letters capitalized and a number of digits mixed in. Because the units are
+//! not chosen uniformly from all strings, the entropy is computed from the actual generation
+//! process rather than from the alphabet size.
+
+use alloc::{format, vec, vec::Vec};
+
+use crate::config::Length;
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::PassForgeError;
+
+/// Consonant units that can appear between vowels.
+pub const CONSONANTS: &[&str] = &[
+    "b", "c", "d", "f", "g", "h", "j", "k", "l", "m", "n", "p", "r", "s", "t", "v", "w", "x", "y",
+    "z", "ch", "sh", "th", "ph", "st", "tr", "br", "cr", "dr", "fr", "gr", "pr", "bl", "cl", "fl",
+    "gl", "pl", "sl",
+];
+
+/// Vowel units that can appear between consonants.
+pub const VOWELS: &[&str] = &[
+    "a", "e", "i", "o", "u", "ai", "ea", "ee", "ie", "oa", "oo", "ou",
+];
+
+/// Represents the configuration options for pronounceable password generation.
+#[derive(Debug, Clone)]
+pub struct PronounceableConfig {
+    /// The length specification for the password, including digits.
+    pub length: Length,
+    /// The number of letters to capitalize.
+    pub capitals: usize,
+    /// The number of digits to mix into the password.
+    pub numbers: usize,
+}
+
+impl PronounceableConfig {
+    /// The default length for generated pronounceable passwords.
+    pub const DEFAULT_LENGTH: usize = 12;
+    /// The default number of capitalized letters.
+    pub const DEFAULT_CAPITALS: usize = 1;
+    /// The default number of digits.
+    pub const DEFAULT_NUMBERS: usize = 1;
+
+    /// Creates a new `PronounceableConfig` with the specified options.
+    ///
+    /// # Arguments
+    ///
+    /// * `length` - The length specification for the password, including digits.
+    /// * `capitals` - The number of letters to capitalize.
+    /// * `numbers` - The number of digits to mix into the password.
+    pub fn new(length: Length, capitals: usize, numbers: usize) -> Self {
+        Self {
+            length,
+            capitals,
+            numbers,
+        }
+    }
+
+    /// Returns a new `PronounceableConfigBuilder` for creating a `PronounceableConfig`.
+    pub fn builder() -> PronounceableConfigBuilder {
+        PronounceableConfigBuilder::default()
+    }
+
+    /// Checks that a password of the given length can hold the configured letters and digits.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the length is less than 1, if the digits leave no room for
+    /// letters, or if more letters should be capitalized than there are letters.
+    pub fn check_length(&self, length: usize) -> Result<(), PassForgeError> {
+        if length < 1 {
+            return Err(PassForgeError::InvalidLength(
+                "Length of password cannot be less than 1".into(),
+            ));
+        }
+        if self.numbers >= length {
+            return Err(PassForgeError::InvalidLength(format!(
+                "Length {} leaves no room for letters next to {} digits",
+                length, self.numbers
+            )));
+        }
+        if self.capitals > length - self.numbers {
+            return Err(PassForgeError::InvalidLength(format!(
+                "Length {} has fewer than {} letters to capitalize",
+                length, self.capitals
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns the Shannon entropy of the generated passwords in bits.
+    ///
+    /// This is the average information content of a password, taking into account that
+    /// units are picked per step rather than uniformly from all strings of the same length.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the configuration cannot produce a password.
+    pub fn entropy_bits(&self) -> Result<f64, PassForgeError> {
+        let lengths = self.lengths()?;
+        let count = (lengths.end() - lengths.start() + 1) as f64;
+        let total: f64 = lengths
+            .map(|length| self.entropy_for_length(length, false))
+            .sum();
+        Ok(count.log2() + total / count)
+    }
+
+    /// Returns the min-entropy of the generated passwords in bits.
+    ///
+    /// This is the information content of the most likely password, which bounds how quickly
+    /// an attacker guessing the likeliest passwords first can succeed.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the configuration cannot produce a password.
+    pub fn min_entropy_bits(&self) -> Result<f64, PassForgeError> {
+        let lengths = self.lengths()?;
+        let count = (lengths.end() - lengths.start() + 1) as f64;
+        let lowest = lengths
+            .map(|length| self.entropy_for_length(length, true))
+            .fold(f64::INFINITY, f64::min);
+        Ok(count.log2() + lowest)
+    }
+
+    /// Validates every possible length and returns them.
+    fn lengths(&self) -> Result<core::ops::RangeInclusive<usize>, PassForgeError> {
+        let lengths = match &self.length {
+            Length::Single(length) => *length..=*length,
+            Length::Range(range) => range.clone(),
+        };
+        if lengths.is_empty() {
+            return Err(PassForgeError::InvalidLength(
+                "Length range cannot be empty".into(),
+            ));
+        }
+        self.check_length(*lengths.start())?;
+        self.check_length(*lengths.end())?;
+        Ok(lengths)
+    }
+
+    /// Computes the entropy of a password of a fixed length: the letters, the positions of
+    /// the capitals and the positions and values of the digits.
+    fn entropy_for_length(&self, length: usize, worst_case: bool) -> f64 {
+        let letters = length - self.numbers;
+        let letter_bits = Self::letter_entropy(letters, worst_case);
+        letter_bits
+            + log2_binomial(letters, self.capitals)
+            + log2_binomial(length, self.numbers)
+            + self.numbers as f64 * 10f64.log2()
+    }
+
+    /// Computes the entropy of the letter sequence, including the choice of the first unit type.
+    ///
+    /// At every step a unit is chosen uniformly from those that still fit, so the entropy is
+    /// computed over the tree of choices, either averaged or along the least random path.
+    fn letter_entropy(letters: usize, worst_case: bool) -> f64 {
+        // entropy[remaining][0] starts with a consonant, entropy[remaining][1] with a vowel.
+        let mut entropy = vec![[0.0f64; 2]; letters + 1];
+        for remaining in 1..=letters {
+            for (kind, units) in [CONSONANTS, VOWELS].into_iter().enumerate() {
+                let next: Vec<f64> = units
+                    .iter()
+                    .filter(|unit| unit.len() <= remaining)
+                    .map(|unit| entropy[remaining - unit.len()][1 - kind])
+                    .collect();
+                let rest = if worst_case {
+                    next.iter().copied().fold(f64::INFINITY, f64::min)
+                } else {
+                    next.iter().sum::<f64>() / next.len() as f64
+                };
+                entropy[remaining][kind] = (next.len() as f64).log2() + rest;
+            }
+        }
+        let [consonant_first, vowel_first] = entropy[letters];
+        let rest = if worst_case {
+            consonant_first.min(vowel_first)
+        } else {
+            (consonant_first + vowel_first) / 2.0
+        };
+        1.0 + rest
+    }
+}
+
+/// Computes `log2(n choose k)`.
+pub(crate) fn log2_binomial(n: usize, k: usize) -> f64 {
+    (0..k)
+        .map(|i| ((n - i) as f64).log2() - ((i + 1) as f64).log2())
+        .sum()
+}
+
+/// A builder for creating `PronounceableConfig` instances.
+#[derive(Default)]
+pub struct PronounceableConfigBuilder {
+    length: Option<Length>,
+    capitals: Option<usize>,
+    numbers: Option<usize>,
+}
+
+impl PronounceableConfigBuilder {
+    /// Sets the length for the password.
+    pub fn length(mut self, length: Length) -> Self {
+        self.length = Some(length);
+        self
+    }
+
+    /// Sets the number of letters to capitalize.
+    pub fn capitals(mut self, count: usize) -> Self {
+        self.capitals = Some(count);
+        self
+    }
+
+    /// Sets the number of digits to mix into the password.
+    pub fn numbers(mut self, count: usize) -> Self {
+        self.numbers = Some(count);
+        self
+    }
+
+    /// Builds a `PronounceableConfig` from the current builder state.
+    pub fn build(self) -> PronounceableConfig {
+        PronounceableConfig {
+            length: self
+                .length
+                .unwrap_or(Length::Single(PronounceableConfig::DEFAULT_LENGTH)),
+            capitals: self
+                .capitals
+                .unwrap_or(PronounceableConfig::DEFAULT_CAPITALS),
+            numbers: self.numbers.unwrap_or(PronounceableConfig::DEFAULT_NUMBERS),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod pronounceable_config_tests {
+        use super::*;
+
+        #[test]
+        fn test_single_letter_entropy() {
+            // One letter: pick a unit type, then one of the single letter units of that type.
+            let singles = |units: &[&str]| units.iter().filter(|u| u.len() == 1).count() as f64;
+            let config = PronounceableConfig::new(Length::Single(1), 0, 0);
+            let expected = 1.0 + (singles(CONSONANTS).log2() + singles(VOWELS).log2()) / 2.0;
+            assert!((config.entropy_bits().unwrap() - expected).abs() < 1e-9);
+            let worst = 1.0 + singles(VOWELS).log2();
+            assert!((config.min_entropy_bits().unwrap() - worst).abs() < 1e-9);
+        }
+
+        #[test]
+        fn test_entropy_is_below_uniform() {
+            let config = PronounceableConfig::builder().build();
+            let entropy = config.entropy_bits().unwrap();
+            let min_entropy = config.min_entropy_bits().unwrap();
+            assert!(min_entropy < entropy);
+            assert!(entropy < 12.0 * 62f64.log2());
+        }
+
+        #[test]
+        fn test_invalid_configs() {
+            assert!(PronounceableConfig::new(Length::Single(0), 0, 0)
+                .entropy_bits()
+                .is_err());
+            assert!(PronounceableConfig::new(Length::Single(4), 0, 4)
+                .entropy_bits()
+                .is_err());
+            assert!(PronounceableConfig::new(Length::Range(3..=8), 3, 1)
+                .entropy_bits()
+                .is_err());
+        }
+    }
+}
@@ -0,0 +1,278 @@
+//! This module defines the configuration for sets of one-time recovery codes.
+//!
+//! A set holds a number of codes such as `7kq4-m2xz`, each made of groups of characters
+//! joined by a separator. The characters come from an alphabet without look-alikes, and no
+//! code appears twice in a set. The codes can be hashed with a random salt for storage on
+//! the server, where they are checked with `verify_recovery_code`.
+
+use alloc::{format, string::String, vec::Vec};
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::PassForgeError;
+
+/// Represents the configuration options for recovery code generation.
+#[derive(Debug, Clone)]
+pub struct RecoveryCodesConfig {
+    /// The number of codes in a set.
+    pub count: usize,
+    /// The number of groups in a code.
+    pub groups: usize,
+    /// The number of characters in a group.
+    pub group_length: usize,
+    /// The characters codes are drawn from.
+    pub alphabet: String,
+    /// The text placed between the groups of a code.
+    pub separator: String,
+    /// Whether to also return a salted SHA-256 hash of each code.
+    pub hashed: bool,
+}
+
+impl RecoveryCodesConfig {
+    /// The default number of codes in a set.
+    pub const DEFAULT_COUNT: usize = 10;
+    /// The maximum number of codes in a set.
+    pub const MAX_COUNT: usize = 1000;
+    /// The default number of groups in a code.
+    pub const DEFAULT_GROUPS: usize = 2;
+    /// The default number of characters in a group.
+    pub const DEFAULT_GROUP_LENGTH: usize = 4;
+    /// The default alphabet: digits and lowercase letters without `0`, `1`, `i`, `l` and `o`,
+    /// which are easily mistaken for one another.
+    pub const DEFAULT_ALPHABET: &'static str = "23456789abcdefghjkmnpqrstuvwxyz";
+    /// The default separator between groups.
+    pub const DEFAULT_SEPARATOR: &'static str = "-";
+
+    /// Creates a new `RecoveryCodesConfig` with the default alphabet and separator, without
+    /// hashes.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - The number of codes in a set.
+    /// * `groups` - The number of groups in a code.
+    /// * `group_length` - The number of characters in a group.
+    pub fn new(count: usize, groups: usize, group_length: usize) -> Self {
+        Self {
+            count,
+            groups,
+            group_length,
+            alphabet: Self::DEFAULT_ALPHABET.into(),
+            separator: Self::DEFAULT_SEPARATOR.into(),
+            hashed: false,
+        }
+    }
+
+    /// Returns a new `RecoveryCodesConfigBuilder` for creating a `RecoveryCodesConfig`.
+    pub fn builder() -> RecoveryCodesConfigBuilder {
+        RecoveryCodesConfigBuilder::default()
+    }
+
+    /// Returns the entropy of a single code in bits.
+    ///
+    /// The codes of a set are distinct, so together they carry slightly less than `count`
+    /// times this.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the configuration is invalid.
+    pub fn entropy_bits(&self) -> Result<f64, PassForgeError> {
+        self.check()?;
+        Ok(self.code_length() as f64 * (self.alphabet.chars().count() as f64).log2())
+    }
+
+    /// Returns the number of distinct codes, or `None` if it does not fit in a `u128`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the configuration is invalid.
+    pub fn keyspace(&self) -> Result<Option<u128>, PassForgeError> {
+        self.check()?;
+        Ok(self.checked_keyspace())
+    }
+
+    /// Returns the number of alphabet characters in a code, without separators.
+    pub(crate) fn code_length(&self) -> usize {
+        self.groups * self.group_length
+    }
+
+    fn checked_keyspace(&self) -> Option<u128> {
+        let len = u32::try_from(self.code_length()).ok()?;
+        (self.alphabet.chars().count() as u128).checked_pow(len)
+    }
+
+    /// Checks that the set has between 1 and `MAX_COUNT` codes, that codes have at least one
+    /// group of at least one character, that the alphabet has at least two distinct
+    /// characters that do not appear in the separator, and that there are enough distinct
+    /// codes to fill a set.
+    pub(crate) fn check(&self) -> Result<(), PassForgeError> {
+        if self.count < 1 || self.count > Self::MAX_COUNT {
+            return Err(PassForgeError::InvalidGenAmount(format!(
+                "A set must have between 1 and {} recovery codes",
+                Self::MAX_COUNT
+            )));
+        }
+        if self.groups < 1 || self.group_length < 1 {
+            return Err(PassForgeError::InvalidLength(
+                "Recovery codes need at least one group of at least one character".into(),
+            ));
+        }
+        let mut chars: Vec<char> = self.alphabet.chars().collect();
+        chars.sort_unstable();
+        if chars.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(PassForgeError::InvalidConfig(
+                "Recovery code alphabet cannot contain a character twice".into(),
+            ));
+        }
+        if chars.len() < 2 {
+            return Err(PassForgeError::InvalidConfig(
+                "Recovery code alphabet needs at least two characters".into(),
+            ));
+        }
+        if let Some(c) = chars
+            .iter()
+            .find(|c| c.is_whitespace() || self.separator.contains(**c))
+        {
+            return Err(PassForgeError::InvalidConfig(format!(
+                "Recovery code alphabet cannot contain {:?}, which is whitespace or part of the separator",
+                c
+            )));
+        }
+        if self
+            .checked_keyspace()
+            .is_some_and(|keyspace| keyspace < self.count as u128)
+        {
+            return Err(PassForgeError::InvalidConfig(format!(
+                "There are fewer than {} distinct recovery codes of this length",
+                self.count
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A builder for creating `RecoveryCodesConfig` instances.
+#[derive(Default)]
+pub struct RecoveryCodesConfigBuilder {
+    count: Option<usize>,
+    groups: Option<usize>,
+    group_length: Option<usize>,
+    alphabet: Option<String>,
+    separator: Option<String>,
+    hashed: Option<bool>,
+}
+
+impl RecoveryCodesConfigBuilder {
+    /// Sets the number of codes in a set.
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    /// Sets the number of groups in a code.
+    pub fn groups(mut self, groups: usize) -> Self {
+        self.groups = Some(groups);
+        self
+    }
+
+    /// Sets the number of characters in a group.
+    pub fn group_length(mut self, group_length: usize) -> Self {
+        self.group_length = Some(group_length);
+        self
+    }
+
+    /// Sets the characters codes are drawn from.
+    pub fn alphabet(mut self, alphabet: String) -> Self {
+        self.alphabet = Some(alphabet);
+        self
+    }
+
+    /// Sets the text placed between the groups of a code.
+    pub fn separator(mut self, separator: String) -> Self {
+        self.separator = Some(separator);
+        self
+    }
+
+    /// Sets whether a salted hash of each code is returned.
+    pub fn hashed(mut self, hashed: bool) -> Self {
+        self.hashed = Some(hashed);
+        self
+    }
+
+    /// Builds a `RecoveryCodesConfig` from the current builder state.
+    /// Unless set otherwise, a set has 10 codes of two groups of four characters from
+    /// `DEFAULT_ALPHABET`, joined by `-`, without hashes.
+    pub fn build(self) -> RecoveryCodesConfig {
+        RecoveryCodesConfig {
+            count: self.count.unwrap_or(RecoveryCodesConfig::DEFAULT_COUNT),
+            groups: self.groups.unwrap_or(RecoveryCodesConfig::DEFAULT_GROUPS),
+            group_length: self
+                .group_length
+                .unwrap_or(RecoveryCodesConfig::DEFAULT_GROUP_LENGTH),
+            alphabet: self
+                .alphabet
+                .unwrap_or_else(|| RecoveryCodesConfig::DEFAULT_ALPHABET.into()),
+            separator: self
+                .separator
+                .unwrap_or_else(|| RecoveryCodesConfig::DEFAULT_SEPARATOR.into()),
+            hashed: self.hashed.unwrap_or(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod recovery_codes_config_tests {
+        use super::*;
+
+        #[test]
+        fn test_builder_defaults() {
+            let config = RecoveryCodesConfig::builder().build();
+            assert_eq!(config.count, 10);
+            assert_eq!(config.groups, 2);
+            assert_eq!(config.group_length, 4);
+            assert_eq!(config.alphabet.len(), 31);
+            assert_eq!(config.separator, "-");
+            assert!(!config.hashed);
+        }
+
+        #[test]
+        fn test_entropy_and_keyspace() {
+            let config = RecoveryCodesConfig::new(10, 2, 4);
+            assert_eq!(config.keyspace().unwrap(), Some(31u128.pow(8)));
+            assert!((config.entropy_bits().unwrap() - 8.0 * 31f64.log2()).abs() < 1e-9);
+
+            let huge = RecoveryCodesConfig::new(10, 10, 10);
+            assert_eq!(huge.keyspace().unwrap(), None);
+        }
+
+        #[test]
+        fn test_invalid_config() {
+            assert!(RecoveryCodesConfig::new(0, 2, 4).check().is_err());
+            assert!(RecoveryCodesConfig::new(1001, 2, 4).check().is_err());
+            assert!(RecoveryCodesConfig::new(10, 0, 4).check().is_err());
+            assert!(RecoveryCodesConfig::new(10, 2, 0).check().is_err());
+
+            let builder = || RecoveryCodesConfig::builder();
+            assert!(builder().alphabet("a".into()).build().check().is_err());
+            assert!(builder().alphabet("abca".into()).build().check().is_err());
+            assert!(builder().alphabet("ab-".into()).build().check().is_err());
+            assert!(builder().alphabet("ab ".into()).build().check().is_err());
+            // Two groups of one binary digit give only four distinct codes.
+            let small = builder()
+                .alphabet("01".into())
+                .group_length(1)
+                .count(5)
+                .build();
+            assert!(small.check().is_err());
+            assert!(builder()
+                .alphabet("01".into())
+                .group_length(1)
+                .count(4)
+                .build()
+                .check()
+                .is_ok());
+        }
+    }
+}
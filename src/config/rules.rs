@@ -0,0 +1,412 @@
+//! This module parses the `passwordrules` attribute syntax into a `PasswordPolicy`.
+//!
+//! Websites describe their password requirements in this format, for example
+//! `minlength: 20; required: lower; required: upper; allowed: [-_]`. The supported
+//! properties are:
+//!
+//! | Property          | Value                                                         |
+//! |-------------------|---------------------------------------------------------------|
+//! | `minlength`       | the minimum number of characters                              |
+//! | `maxlength`       | the maximum number of characters                              |
+//! | `max-consecutive` | the maximum number of identical characters in a row           |
+//! | `required`        | classes of which at least one character must appear           |
+//! | `allowed`         | classes whose characters may appear                           |
+//!
+//! Classes are `upper`, `lower`, `digit`, `special`, `ascii-printable`, `unicode` or a custom
+//! set of characters in brackets such as `[-_.]`. Several classes separated by commas form a
+//! single requirement that any of them satisfies. Repeating a requirement does not raise it, and
+//! a requirement that includes every character of another one, as `upper, digit` does for
+//! `upper`, is already met by that one. Other required classes must not share characters.
+//! Without `required` or `allowed` properties, every printable ASCII character is allowed.
+//! Unknown properties are ignored, as browsers do.
+
+use alloc::{format, string::String, vec::Vec};
+use core::iter::Peekable;
+use core::str::Chars;
+
+use crate::config::alphabet::{LOWERCASE, NUMBERS, UPPERCASE};
+use crate::config::{CharClass, ClassCount, PasswordPolicy};
+use crate::PassForgeError;
+
+/// The characters of the `special` class: printable ASCII that is not a letter or digit,
+/// including the space.
+pub const SPECIAL: &str = " !\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~";
+
+/// A set of characters named after the classes it was written as.
+struct RuleClass {
+    name: String,
+    chars: Vec<char>,
+}
+
+impl PasswordPolicy {
+    /// Parses a `passwordrules` string into a `PasswordPolicy`.
+    ///
+    /// Each distinct `required` property becomes a class of which at least one character must
+    /// appear, unless it includes every character of another requirement, in which case its
+    /// characters are only allowed. The `allowed` characters not covered by a requirement
+    /// become an optional class.
+    /// Only ASCII passwords are generated, so `unicode` is treated like `ascii-printable`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rules` - The rules, e.g. `minlength: 12; required: lower; required: digit`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the `PasswordPolicy` if successful,
+    /// or a `PassForgeError` if the rules are invalid.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the rules are malformed, use an unknown class, or have
+    /// required classes that share characters without one including the other.
+    pub fn from_rules(rules: &str) -> Result<Self, PassForgeError> {
+        let mut min_length = None;
+        let mut max_length = None;
+        let mut max_consecutive = None;
+        let mut required: Vec<RuleClass> = Vec::new();
+        let mut allowed: Option<Vec<char>> = None;
+
+        let mut chars = rules.chars().peekable();
+        loop {
+            skip_whitespace(&mut chars);
+            if chars.peek().is_none() {
+                break;
+            }
+            let name: String = core::iter::from_fn(|| chars.next_if(|c| *c != ':' && *c != ';'))
+                .collect::<String>()
+                .trim()
+                .to_lowercase();
+            if chars.next() != Some(':') {
+                return Err(invalid(format!("Property \"{}\" is missing a value", name)));
+            }
+            skip_whitespace(&mut chars);
+            match name.as_str() {
+                "minlength" => {
+                    let value = parse_number(&mut chars, &name)?;
+                    min_length = Some(min_length.map_or(value, |min: usize| min.max(value)));
+                }
+                "maxlength" => {
+                    let value = parse_number(&mut chars, &name)?;
+                    max_length = Some(max_length.map_or(value, |max: usize| max.min(value)));
+                }
+                "max-consecutive" => {
+                    let value = parse_number(&mut chars, &name)?;
+                    max_consecutive =
+                        Some(max_consecutive.map_or(value, |max: usize| max.min(value)));
+                }
+                "required" => {
+                    let class = parse_classes(&mut chars)?;
+                    if !required.iter().any(|other| other.chars == class.chars) {
+                        required.push(class);
+                    }
+                }
+                "allowed" => {
+                    let class = parse_classes(&mut chars)?;
+                    let allowed = allowed.get_or_insert_with(Vec::new);
+                    for c in class.chars {
+                        if !allowed.contains(&c) {
+                            allowed.push(c);
+                        }
+                    }
+                }
+                _ => {
+                    // Skip the value of an unknown property, including any bracketed sets.
+                    while let Some(c) = chars.next_if(|c| *c != ';') {
+                        if c == '[' {
+                            parse_custom_class(&mut chars)?;
+                        }
+                    }
+                }
+            }
+            skip_whitespace(&mut chars);
+            match chars.next() {
+                None | Some(';') => {}
+                Some(c) => {
+                    return Err(invalid(format!(
+                        "Unexpected character {:?} after property \"{}\"",
+                        c, name
+                    )))
+                }
+            }
+        }
+
+        // A requirement that contains every character of another requirement is met by the
+        // character drawn for the smaller one, so it only contributes allowed characters.
+        let is_implied: Vec<bool> = required
+            .iter()
+            .map(|class| {
+                required.iter().any(|other| {
+                    other.chars != class.chars
+                        && other.chars.iter().all(|c| class.chars.contains(c))
+                })
+            })
+            .collect();
+        let (implied, required): (Vec<_>, Vec<_>) = required
+            .into_iter()
+            .zip(is_implied)
+            .partition(|(_, is_implied)| *is_implied);
+        let required: Vec<RuleClass> = required.into_iter().map(|(class, _)| class).collect();
+        for (i, class) in required.iter().enumerate() {
+            for other in &required[i + 1..] {
+                if class.chars.iter().any(|c| other.chars.contains(c)) {
+                    return Err(invalid(format!(
+                        "Required classes {} and {} overlap",
+                        class.name, other.name
+                    )));
+                }
+            }
+        }
+
+        let mut custom_classes: Vec<CharClass> = required
+            .into_iter()
+            .map(|class| CharClass {
+                name: format!("characters from {}", class.name),
+                chars: class.chars,
+                count: ClassCount::at_least(1),
+            })
+            .collect();
+        let mut allowed = match allowed {
+            Some(allowed) => allowed,
+            None if custom_classes.is_empty() => ascii_printable(),
+            None => Vec::new(),
+        };
+        for c in implied.into_iter().flat_map(|(class, _)| class.chars) {
+            if !allowed.contains(&c) {
+                allowed.push(c);
+            }
+        }
+        let others: Vec<char> = allowed
+            .into_iter()
+            .filter(|c| !custom_classes.iter().any(|class| class.chars.contains(c)))
+            .collect();
+        if !others.is_empty() {
+            custom_classes.push(CharClass {
+                name: "other allowed characters".into(),
+                chars: others,
+                count: ClassCount::default(),
+            });
+        }
+
+        Ok(Self {
+            min_length: min_length.unwrap_or(Self::DEFAULT_MIN_LENGTH),
+            max_length,
+            lowercase: None,
+            capitals: None,
+            numbers: None,
+            symbols: None,
+            custom_classes,
+            max_consecutive,
+            ..Self::new(Self::DEFAULT_MIN_LENGTH, None)
+        })
+    }
+}
+
+/// Creates the error returned for malformed rules.
+fn invalid(message: String) -> PassForgeError {
+    PassForgeError::InvalidConfig(format!("Invalid password rules: {}", message))
+}
+
+/// Returns every printable ASCII character.
+fn ascii_printable() -> Vec<char> {
+    [LOWERCASE, UPPERCASE, NUMBERS, SPECIAL]
+        .concat()
+        .chars()
+        .collect()
+}
+
+/// Advances past any whitespace.
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+/// Parses the number value of the named property.
+fn parse_number(chars: &mut Peekable<Chars>, name: &str) -> Result<usize, PassForgeError> {
+    let digits: String = core::iter::from_fn(|| chars.next_if(|c| c.is_ascii_digit())).collect();
+    digits
+        .parse()
+        .map_err(|_| invalid(format!("Property \"{}\" needs a number", name)))
+}
+
+/// Parses a comma separated list of classes into the union of their characters.
+fn parse_classes(chars: &mut Peekable<Chars>) -> Result<RuleClass, PassForgeError> {
+    let mut names = Vec::new();
+    let mut set: Vec<char> = Vec::new();
+    loop {
+        skip_whitespace(chars);
+        let (name, class_chars) = if chars.next_if_eq(&'[').is_some() {
+            let custom = parse_custom_class(chars)?;
+            (format!("[{}]", custom.iter().collect::<String>()), custom)
+        } else {
+            let name: String =
+                core::iter::from_fn(|| chars.next_if(|c| c.is_ascii_alphabetic() || *c == '-'))
+                    .collect::<String>()
+                    .to_lowercase();
+            let class_chars = match name.as_str() {
+                "upper" => UPPERCASE.chars().collect(),
+                "lower" => LOWERCASE.chars().collect(),
+                "digit" => NUMBERS.chars().collect(),
+                "special" => SPECIAL.chars().collect(),
+                "ascii-printable" | "unicode" => ascii_printable(),
+                "" => return Err(invalid("Expected a character class".into())),
+                other => return Err(invalid(format!("Unknown character class \"{}\"", other))),
+            };
+            (name, class_chars)
+        };
+        names.push(name);
+        for c in class_chars {
+            if !set.contains(&c) {
+                set.push(c);
+            }
+        }
+        skip_whitespace(chars);
+        if chars.next_if_eq(&',').is_none() {
+            break;
+        }
+    }
+    set.sort_unstable();
+    Ok(RuleClass {
+        name: names.join(", "),
+        chars: set,
+    })
+}
+
+/// Parses the characters of a custom class after its opening bracket, up to and including
+/// the closing bracket. A `]` directly after the opening bracket is taken literally.
+fn parse_custom_class(chars: &mut Peekable<Chars>) -> Result<Vec<char>, PassForgeError> {
+    let mut set = Vec::new();
+    if chars.next_if_eq(&']').is_some() {
+        set.push(']');
+    }
+    loop {
+        match chars.next() {
+            None => return Err(invalid("Custom character class is missing a \"]\"".into())),
+            Some(']') => break,
+            Some(c) if c.is_ascii_graphic() || c == ' ' => {
+                if !set.contains(&c) {
+                    set.push(c);
+                }
+            }
+            Some(c) => return Err(invalid(format!("Character {:?} is not printable ASCII", c))),
+        }
+    }
+    if set.is_empty() {
+        return Err(invalid("Custom character class cannot be empty".into()));
+    }
+    Ok(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::{Generator, PasswordGenerator};
+
+    mod password_rules_tests {
+        use super::*;
+
+        #[test]
+        fn test_parse_rules() {
+            let policy = PasswordPolicy::from_rules(
+                "minlength: 20; maxlength: 30; required: lower; required: upper, digit; \
+                 allowed: [-_]; max-consecutive: 2",
+            )
+            .unwrap();
+            assert_eq!(policy.min_length, 20);
+            assert_eq!(policy.max_length, Some(30));
+            assert_eq!(policy.max_consecutive, Some(2));
+            let names: Vec<&str> = policy
+                .custom_classes
+                .iter()
+                .map(|class| class.name.as_str())
+                .collect();
+            assert_eq!(
+                names,
+                [
+                    "characters from lower",
+                    "characters from upper, digit",
+                    "other allowed characters"
+                ]
+            );
+            assert_eq!(policy.custom_classes[1].chars.len(), 36);
+            assert_eq!(policy.custom_classes[2].chars, ['-', '_']);
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_generated_passwords_follow_rules() {
+            let policy = PasswordPolicy::from_rules(
+                "required: lower; required: digit; allowed: [-]; \
+                 max-consecutive: 1; minlength: 8; maxlength: 10",
+            )
+            .unwrap();
+            let config = policy.to_config().unwrap();
+            for _ in 0..50 {
+                let password = PasswordGenerator::generate(&config).unwrap();
+                let password = password.expose_secret();
+                assert_eq!(policy.validate(password).unwrap(), []);
+                assert!(password.chars().any(|c| c.is_ascii_digit()));
+                assert!(password
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'));
+            }
+        }
+
+        #[test]
+        fn test_defaults_and_unknown_properties() {
+            let policy = PasswordPolicy::from_rules("MinLength: 12; future-rule: [;] x;").unwrap();
+            assert_eq!(policy.min_length, 12);
+            assert_eq!(policy.custom_classes.len(), 1);
+            assert_eq!(policy.custom_classes[0].chars.len(), 95);
+            assert!(policy.validate("correct horse!").unwrap().is_empty());
+        }
+
+        #[test]
+        fn test_invalid_rules() {
+            assert!(PasswordPolicy::from_rules("minlength 12").is_err());
+            assert!(PasswordPolicy::from_rules("minlength: twelve").is_err());
+            assert!(PasswordPolicy::from_rules("required: uppercase").is_err());
+            assert!(PasswordPolicy::from_rules("allowed: [abc").is_err());
+            assert!(
+                PasswordPolicy::from_rules("required: upper, digit; required: digit, [-]").is_err()
+            );
+        }
+
+        #[test]
+        fn test_repeated_required_class() {
+            let policy = PasswordPolicy::from_rules(
+                "required: digit; required: digit; required: [9876543210]",
+            )
+            .unwrap();
+            assert_eq!(policy.custom_classes.len(), 1);
+            assert_eq!(policy.custom_classes[0].name, "characters from digit");
+            assert_eq!(policy.custom_classes[0].count, ClassCount::at_least(1));
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_required_superset_class() {
+            let policy =
+                PasswordPolicy::from_rules("required: upper; required: upper, digit; minlength: 8")
+                    .unwrap();
+            let names: Vec<&str> = policy
+                .custom_classes
+                .iter()
+                .map(|class| class.name.as_str())
+                .collect();
+            assert_eq!(names, ["characters from upper", "other allowed characters"]);
+            assert_eq!(policy.custom_classes[0].count, ClassCount::at_least(1));
+            assert_eq!(policy.custom_classes[1].chars.len(), 10);
+            let config = policy.to_config().unwrap();
+            for _ in 0..20 {
+                let password = PasswordGenerator::generate(&config).unwrap();
+                let password = password.expose_secret();
+                assert_eq!(policy.validate(password).unwrap(), []);
+                assert!(password.chars().any(|c| c.is_ascii_uppercase()));
+                assert!(password
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()));
+            }
+        }
+    }
+}
@@ -0,0 +1,536 @@
+//! This module defines the configuration for API token and raw key generation.
+//!
+//! A token is a number of random bytes written in one of the `TokenEncoding`s, optionally
+//! preceded by an identifying prefix such as `pf_live_` and followed by a checksum segment.
+//! The prefix and checksum carry no entropy, but let secret scanners recognise a leaked
+//! token and rule out random strings that merely look like one.
+
+use alloc::{format, string::String, vec, vec::Vec};
+
+use zeroize::Zeroize;
+
+use crate::PassForgeError;
+
+/// The text encoding of the random bytes in a token. Padding is never written, since the
+/// number of bytes is fixed by the configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenEncoding {
+    /// Lowercase hexadecimal, two characters per byte.
+    Hex,
+    /// The RFC 4648 base32 alphabet, `A-Z2-7`.
+    Base32,
+    /// Crockford's base32 alphabet, which leaves out `I`, `L`, `O` and `U`. When validating,
+    /// lowercase letters are accepted and `I`, `L` and `O` are read as `1`, `1` and `0`.
+    Base32Crockford,
+    /// The RFC 4648 base64 alphabet, `A-Za-z0-9+/`.
+    Base64,
+    /// The RFC 4648 URL and filename safe base64 alphabet, `A-Za-z0-9-_`.
+    Base64Url,
+    /// The Bitcoin base58 alphabet, which leaves out `0`, `O`, `I` and `l`. The length of the
+    /// encoded bytes varies slightly, since leading zero bytes are written as `1`.
+    Base58,
+}
+
+impl TokenEncoding {
+    /// Every encoding, in the order the names are listed in.
+    pub const ALL: [TokenEncoding; 6] = [
+        TokenEncoding::Hex,
+        TokenEncoding::Base32,
+        TokenEncoding::Base32Crockford,
+        TokenEncoding::Base64,
+        TokenEncoding::Base64Url,
+        TokenEncoding::Base58,
+    ];
+
+    /// Looks up an encoding by its name: `hex`, `base32`, `crockford`, `base64`, `base64url`
+    /// or `base58`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the name is not one of the above.
+    pub fn from_name(name: &str) -> Result<Self, PassForgeError> {
+        let name = name.to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|encoding| encoding.name() == name)
+            .ok_or_else(|| {
+                PassForgeError::InvalidConfig(format!(
+                    "Unknown token encoding \"{}\". Choices are: {}",
+                    name,
+                    Self::ALL.map(|encoding| encoding.name()).join(", ")
+                ))
+            })
+    }
+
+    /// Returns the name of the encoding, as accepted by `from_name`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TokenEncoding::Hex => "hex",
+            TokenEncoding::Base32 => "base32",
+            TokenEncoding::Base32Crockford => "crockford",
+            TokenEncoding::Base64 => "base64",
+            TokenEncoding::Base64Url => "base64url",
+            TokenEncoding::Base58 => "base58",
+        }
+    }
+
+    /// Returns the characters of the encoding, in order of their value.
+    pub fn alphabet(&self) -> &'static [u8] {
+        match self {
+            TokenEncoding::Hex => b"0123456789abcdef",
+            TokenEncoding::Base32 => b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567",
+            TokenEncoding::Base32Crockford => b"0123456789ABCDEFGHJKMNPQRSTVWXYZ",
+            TokenEncoding::Base64 => {
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/"
+            }
+            TokenEncoding::Base64Url => {
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_"
+            }
+            TokenEncoding::Base58 => b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz",
+        }
+    }
+
+    /// Encodes the bytes as text.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The bytes to encode.
+    ///
+    /// # Returns
+    ///
+    /// Returns the encoded text, allocated at its final size so that no partial copies of the
+    /// bytes are left behind.
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        match self.bits_per_char() {
+            Some(bits) => self.encode_bits(bytes, bits),
+            None => self.encode_base58(bytes),
+        }
+    }
+
+    /// Decodes text written in this encoding.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to decode.
+    ///
+    /// # Returns
+    ///
+    /// Returns the decoded bytes, or `None` if the text contains a character outside the
+    /// alphabet or is not the encoding of any bytes, such as base64 with unused trailing bits.
+    pub fn decode(&self, text: &str) -> Option<Vec<u8>> {
+        match self.bits_per_char() {
+            Some(bits) => self.decode_bits(text, bits),
+            None => self.decode_base58(text),
+        }
+    }
+
+    /// Returns the number of characters a checksum segment takes, i.e. the fewest characters
+    /// that can hold every 32-bit value.
+    pub fn checksum_len(&self) -> usize {
+        let base = self.alphabet().len() as u64;
+        let mut len = 0;
+        let mut capacity = 1u64;
+        while capacity <= u32::MAX as u64 {
+            capacity *= base;
+            len += 1;
+        }
+        len
+    }
+
+    /// Writes a checksum as a fixed-width number in this alphabet, most significant digit
+    /// first.
+    pub(crate) fn encode_checksum(&self, checksum: u32) -> String {
+        let alphabet = self.alphabet();
+        let base = alphabet.len() as u32;
+        let mut digits = vec![alphabet[0]; self.checksum_len()];
+        let mut rest = checksum;
+        for digit in digits.iter_mut().rev() {
+            *digit = alphabet[(rest % base) as usize];
+            rest /= base;
+        }
+        digits.into_iter().map(char::from).collect()
+    }
+
+    /// Rewrites characters that the encoding reads as another to the canonical character.
+    /// Only Crockford's base32 has such aliases.
+    pub(crate) fn normalize(&self, text: &str) -> String {
+        match self {
+            TokenEncoding::Base32Crockford => {
+                let mut normalized = String::with_capacity(text.len());
+                normalized.extend(text.chars().map(|c| match c.to_ascii_uppercase() {
+                    'I' | 'L' => '1',
+                    'O' => '0',
+                    c => c,
+                }));
+                normalized
+            }
+            _ => text.into(),
+        }
+    }
+
+    /// Returns the number of bits each character carries, or `None` for base58, whose base
+    /// is not a power of two.
+    fn bits_per_char(&self) -> Option<usize> {
+        match self {
+            TokenEncoding::Hex => Some(4),
+            TokenEncoding::Base32 | TokenEncoding::Base32Crockford => Some(5),
+            TokenEncoding::Base64 | TokenEncoding::Base64Url => Some(6),
+            TokenEncoding::Base58 => None,
+        }
+    }
+
+    /// Returns the value of a character, or `None` if it is not in the alphabet.
+    fn value(&self, c: u8) -> Option<u32> {
+        self.alphabet()
+            .iter()
+            .position(|known| *known == c)
+            .map(|value| value as u32)
+    }
+
+    /// Writes the bytes as groups of `bits` bits, most significant first, padding the last
+    /// group with zero bits.
+    fn encode_bits(&self, bytes: &[u8], bits: usize) -> String {
+        let alphabet = self.alphabet();
+        let mask = (1u32 << bits) - 1;
+        let mut text = String::with_capacity((bytes.len() * 8).div_ceil(bits));
+        let mut buffer = 0u32;
+        let mut held = 0;
+        for byte in bytes {
+            buffer = (buffer << 8) | *byte as u32;
+            held += 8;
+            while held >= bits {
+                held -= bits;
+                text.push(char::from(alphabet[((buffer >> held) & mask) as usize]));
+            }
+            buffer &= (1 << held) - 1;
+        }
+        if held > 0 {
+            text.push(char::from(
+                alphabet[((buffer << (bits - held)) & mask) as usize],
+            ));
+        }
+        buffer.zeroize();
+        text
+    }
+
+    /// Reverses `encode_bits`, rejecting text whose padding bits are not zero or that has a
+    /// character more than the bytes need.
+    fn decode_bits(&self, text: &str, bits: usize) -> Option<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(text.len() * bits / 8);
+        let mut buffer = 0u32;
+        let mut held = 0;
+        for c in text.bytes() {
+            buffer = (buffer << bits) | self.value(c)?;
+            held += bits;
+            if held >= 8 {
+                held -= 8;
+                bytes.push((buffer >> held) as u8);
+                buffer &= (1 << held) - 1;
+            }
+        }
+        (held < bits && buffer == 0).then_some(bytes)
+    }
+
+    /// Writes the bytes as one big-endian number in base 58, with a `1` for every leading zero
+    /// byte.
+    fn encode_base58(&self, bytes: &[u8]) -> String {
+        let alphabet = self.alphabet();
+        let zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+        // Each byte needs log(256) / log(58) < 1.37 digits, least significant digit first.
+        let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 137 / 100 + 1);
+        for byte in &bytes[zeros..] {
+            let mut carry = *byte as u32;
+            for digit in digits.iter_mut() {
+                carry += (*digit as u32) << 8;
+                *digit = (carry % 58) as u8;
+                carry /= 58;
+            }
+            while carry > 0 {
+                digits.push((carry % 58) as u8);
+                carry /= 58;
+            }
+        }
+        let mut text = String::with_capacity(zeros + digits.len());
+        text.extend(core::iter::repeat_n('1', zeros));
+        text.extend(
+            digits
+                .iter()
+                .rev()
+                .map(|digit| char::from(alphabet[*digit as usize])),
+        );
+        digits.zeroize();
+        text
+    }
+
+    /// Reverses `encode_base58`.
+    fn decode_base58(&self, text: &str) -> Option<Vec<u8>> {
+        let zeros = text.bytes().take_while(|c| *c == b'1').count();
+        // The value's bytes, least significant first.
+        let mut value: Vec<u8> = Vec::with_capacity(text.len());
+        for c in text.bytes().skip(zeros) {
+            let mut carry = self.value(c)?;
+            for byte in value.iter_mut() {
+                carry += *byte as u32 * 58;
+                *byte = carry as u8;
+                carry >>= 8;
+            }
+            while carry > 0 {
+                value.push(carry as u8);
+                carry >>= 8;
+            }
+        }
+        let mut bytes = vec![0; zeros];
+        bytes.extend(value.iter().rev());
+        value.zeroize();
+        Some(bytes)
+    }
+}
+
+/// Represents the configuration options for token generation.
+#[derive(Debug, Clone)]
+pub struct TokenConfig {
+    /// The number of random bytes in the token.
+    pub bytes: usize,
+    /// The encoding the random bytes are written in.
+    pub encoding: TokenEncoding,
+    /// Text placed before the encoded bytes to identify the token, such as `pf_live_`.
+    /// Empty for no prefix.
+    pub prefix: String,
+    /// Whether to append a CRC32 checksum of the prefix and encoded bytes, written in the
+    /// same encoding with the width given by `TokenEncoding::checksum_len`.
+    pub checksum: bool,
+}
+
+impl TokenConfig {
+    /// The default number of random bytes, giving 256 bits of entropy.
+    pub const DEFAULT_BYTES: usize = 32;
+    /// The maximum number of random bytes in a token.
+    pub const MAX_BYTES: usize = 1024;
+    /// The default encoding.
+    pub const DEFAULT_ENCODING: TokenEncoding = TokenEncoding::Base64Url;
+
+    /// Creates a new `TokenConfig` without a prefix or checksum.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The number of random bytes in the token.
+    /// * `encoding` - The encoding the random bytes are written in.
+    pub fn new(bytes: usize, encoding: TokenEncoding) -> Self {
+        Self {
+            bytes,
+            encoding,
+            prefix: String::new(),
+            checksum: false,
+        }
+    }
+
+    /// Returns a new `TokenConfigBuilder` for creating a `TokenConfig`.
+    pub fn builder() -> TokenConfigBuilder {
+        TokenConfigBuilder::default()
+    }
+
+    /// Returns the entropy of the generated tokens in bits, which is 8 bits per random byte.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the configuration is invalid.
+    pub fn entropy_bits(&self) -> Result<f64, PassForgeError> {
+        self.check()?;
+        Ok(self.bytes as f64 * 8.0)
+    }
+
+    /// Returns the number of distinct tokens, or `None` if it does not fit in a `u128`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the configuration is invalid.
+    pub fn keyspace(&self) -> Result<Option<u128>, PassForgeError> {
+        self.check()?;
+        Ok(256u128.checked_pow(self.bytes as u32))
+    }
+
+    /// Checks that the number of bytes is between 1 and `MAX_BYTES`, and that the prefix only
+    /// holds ASCII letters, digits, `_` and `-`.
+    pub(crate) fn check(&self) -> Result<(), PassForgeError> {
+        if self.bytes < 1 || self.bytes > Self::MAX_BYTES {
+            return Err(PassForgeError::InvalidLength(format!(
+                "Token must have between 1 and {} random bytes",
+                Self::MAX_BYTES
+            )));
+        }
+        if let Some(c) = self
+            .prefix
+            .chars()
+            .find(|c| !c.is_ascii_alphanumeric() && *c != '_' && *c != '-')
+        {
+            return Err(PassForgeError::InvalidConfig(format!(
+                "Token prefix cannot contain {:?}, only ASCII letters, digits, '_' and '-'",
+                c
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A builder for creating `TokenConfig` instances.
+#[derive(Default)]
+pub struct TokenConfigBuilder {
+    bytes: Option<usize>,
+    encoding: Option<TokenEncoding>,
+    prefix: Option<String>,
+    checksum: Option<bool>,
+}
+
+impl TokenConfigBuilder {
+    /// Sets the number of random bytes in the token.
+    pub fn bytes(mut self, bytes: usize) -> Self {
+        self.bytes = Some(bytes);
+        self
+    }
+
+    /// Sets the encoding the random bytes are written in.
+    pub fn encoding(mut self, encoding: TokenEncoding) -> Self {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    /// Sets the text placed before the encoded bytes.
+    pub fn prefix(mut self, prefix: String) -> Self {
+        self.prefix = Some(prefix);
+        self
+    }
+
+    /// Sets whether a checksum segment is appended.
+    pub fn checksum(mut self, checksum: bool) -> Self {
+        self.checksum = Some(checksum);
+        self
+    }
+
+    /// Builds a `TokenConfig` from the current builder state.
+    /// Unless set otherwise, tokens have 32 bytes in base64url, without prefix or checksum.
+    pub fn build(self) -> TokenConfig {
+        TokenConfig {
+            bytes: self.bytes.unwrap_or(TokenConfig::DEFAULT_BYTES),
+            encoding: self.encoding.unwrap_or(TokenConfig::DEFAULT_ENCODING),
+            prefix: self.prefix.unwrap_or_default(),
+            checksum: self.checksum.unwrap_or(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod token_config_tests {
+        use super::*;
+
+        #[test]
+        fn test_rfc_4648_vectors() {
+            let cases = [
+                ("", "", "", ""),
+                ("f", "66", "MY", "Zg"),
+                ("fo", "666f", "MZXQ", "Zm8"),
+                ("foo", "666f6f", "MZXW6", "Zm9v"),
+                ("foob", "666f6f62", "MZXW6YQ", "Zm9vYg"),
+                ("fooba", "666f6f6261", "MZXW6YTB", "Zm9vYmE"),
+                ("foobar", "666f6f626172", "MZXW6YTBOI", "Zm9vYmFy"),
+            ];
+            for (input, hex, base32, base64) in cases {
+                assert_eq!(TokenEncoding::Hex.encode(input.as_bytes()), hex);
+                assert_eq!(TokenEncoding::Base32.encode(input.as_bytes()), base32);
+                assert_eq!(TokenEncoding::Base64.encode(input.as_bytes()), base64);
+            }
+            assert_eq!(
+                TokenEncoding::Base32Crockford.encode(b"foobar"),
+                "CSQPYRK1E8"
+            );
+            assert_eq!(TokenEncoding::Base64.encode(&[0xfb, 0xff]), "+/8");
+            assert_eq!(TokenEncoding::Base64Url.encode(&[0xfb, 0xff]), "-_8");
+        }
+
+        #[test]
+        fn test_base58_vectors() {
+            assert_eq!(
+                TokenEncoding::Base58.encode(b"Hello World!"),
+                "2NEpo7TZRRrLZSi2U"
+            );
+            assert_eq!(TokenEncoding::Base58.encode(&[0, 0, 1]), "112");
+            assert_eq!(TokenEncoding::Base58.encode(&[0, 0]), "11");
+            assert_eq!(
+                TokenEncoding::Base58.decode("112").unwrap(),
+                vec![0u8, 0, 1]
+            );
+        }
+
+        #[test]
+        fn test_round_trip() {
+            let bytes: Vec<u8> = (0..=255u8).rev().collect();
+            for encoding in TokenEncoding::ALL {
+                for length in [0, 1, 2, 3, 4, 5, 16, 33, 256] {
+                    let text = encoding.encode(&bytes[..length]);
+                    assert_eq!(
+                        encoding.decode(&text).unwrap(),
+                        &bytes[..length],
+                        "{}",
+                        encoding.name()
+                    );
+                }
+            }
+        }
+
+        #[test]
+        fn test_non_canonical_text_is_rejected() {
+            assert_eq!(TokenEncoding::Hex.decode("abc"), None);
+            assert_eq!(TokenEncoding::Hex.decode("ABCD"), None);
+            assert_eq!(TokenEncoding::Base64.decode("Zh"), None);
+            assert_eq!(TokenEncoding::Base64.decode("Z"), None);
+            assert_eq!(TokenEncoding::Base32.decode("MZXW6YQ="), None);
+            assert_eq!(TokenEncoding::Base58.decode("0OIl"), None);
+        }
+
+        #[test]
+        fn test_checksum_width() {
+            let widths = TokenEncoding::ALL.map(|encoding| encoding.checksum_len());
+            assert_eq!(widths, [8, 7, 7, 6, 6, 6]);
+            for encoding in TokenEncoding::ALL {
+                assert_eq!(
+                    encoding.encode_checksum(u32::MAX).len(),
+                    encoding.checksum_len()
+                );
+            }
+            assert_eq!(TokenEncoding::Hex.encode_checksum(0xcbf43926), "cbf43926");
+            assert_eq!(TokenEncoding::Base58.encode_checksum(0), "111111");
+        }
+
+        #[test]
+        fn test_encoding_names() {
+            for encoding in TokenEncoding::ALL {
+                assert_eq!(TokenEncoding::from_name(encoding.name()).unwrap(), encoding);
+            }
+            assert_eq!(
+                TokenEncoding::from_name("Crockford").unwrap(),
+                TokenEncoding::Base32Crockford
+            );
+            assert!(TokenEncoding::from_name("base62").is_err());
+        }
+
+        #[test]
+        fn test_invalid_configs() {
+            assert!(TokenConfig::new(0, TokenEncoding::Hex).check().is_err());
+            assert!(TokenConfig::new(1025, TokenEncoding::Hex).check().is_err());
+            let config = TokenConfig::builder().prefix("pf live".into()).build();
+            assert!(config.check().is_err());
+            let config = TokenConfig::builder().prefix("pf_live-".into()).build();
+            assert!(config.check().is_ok());
+        }
+
+        #[test]
+        fn test_keyspace_and_entropy() {
+            let config = TokenConfig::new(4, TokenEncoding::Hex);
+            assert_eq!(config.keyspace().unwrap(), Some(1 << 32));
+            assert_eq!(config.entropy_bits().unwrap(), 32.0);
+            assert_eq!(TokenConfig::builder().build().keyspace().unwrap(), None);
+        }
+    }
+}
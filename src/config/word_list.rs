@@ -0,0 +1,162 @@
+//! This module provides a parsed word list that can be loaded once and shared.
+//!
+//! Loading a `WordList` reads and parses its source, which dominates the cost of generating
+//! passphrases when done for every call. A `LoadedWordList` holds the parsed words behind an
+//! `Arc`, so it can be placed into any number of `PassphraseConfig`s and cloned cheaply.
+
+use alloc::{
+    collections::BTreeSet,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use core::fmt;
+
+use crate::config::WordList;
+use crate::generator::PassphraseGenerator;
+use crate::PassForgeError;
+
+mod report;
+
+pub use report::{AmbiguousPair, DuplicateWord, SkippedLine, WordListReport};
+
+/// A parsed and deduplicated word list that is cheap to clone and share between threads.
+#[derive(Clone)]
+pub struct LoadedWordList {
+    words: Arc<[String]>,
+}
+
+impl LoadedWordList {
+    /// Loads and parses a word list. The bundled lists are parsed once per process and
+    /// shared by every later load.
+    ///
+    /// # Arguments
+    ///
+    /// * `word_list` - A reference to the `WordList` specifying the source of words.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the `LoadedWordList` if successful,
+    /// or a `PassForgeError` if the list could not be read or has no words.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if a custom file cannot be read, or if the list has no words.
+    pub fn load(word_list: &WordList) -> Result<Self, PassForgeError> {
+        PassphraseGenerator::get_word_list(word_list)
+    }
+
+    /// Creates a word list from the given words, skipping blank and repeated words.
+    ///
+    /// # Arguments
+    ///
+    /// * `words` - The words to choose from.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the `LoadedWordList` if successful,
+    /// or a `PassForgeError` if there are no words.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if no words remain after skipping blank ones.
+    pub fn from_words<I, S>(words: I) -> Result<Self, PassForgeError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut unique: Vec<String> = Vec::new();
+        let mut seen = BTreeSet::new();
+        for word in words {
+            let word: String = word.into();
+            let word = word.trim();
+            if !word.is_empty() && seen.insert(word.to_string()) {
+                unique.push(word.to_string());
+            }
+        }
+        if unique.is_empty() {
+            return Err(PassForgeError::WordListError(
+                "Word list is empty or invalid".into(),
+            ));
+        }
+        Ok(Self {
+            words: unique.into(),
+        })
+    }
+
+    /// Parses the lines of a word list file. Each line holds either a word, or a dice roll
+    /// followed by a word as in diceware lists. Other lines are skipped.
+    pub(crate) fn parse<I, S>(lines: I) -> Result<Self, PassForgeError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::from_words(lines.into_iter().filter_map(|line| {
+            let parts: Vec<&str> = line.as_ref().split_whitespace().collect();
+            match parts[..] {
+                [word] | [_, word] => Some(word.to_string()),
+                _ => None, // Invalid format, skip this line
+            }
+        }))
+    }
+
+    /// Returns the words in the list.
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    /// Returns the number of words in the list.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns `true` if the list has no words, which cannot happen for a loaded list.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+impl fmt::Debug for LoadedWordList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoadedWordList")
+            .field("len", &self.words.len())
+            .finish()
+    }
+}
+
+impl From<LoadedWordList> for WordList {
+    fn from(words: LoadedWordList) -> Self {
+        WordList::Loaded(words)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod loaded_word_list_tests {
+        use super::*;
+
+        #[test]
+        fn test_from_words_deduplicates() {
+            let list =
+                LoadedWordList::from_words(["apple", " pear ", "apple", "", "plum"]).unwrap();
+            assert_eq!(list.words(), ["apple", "pear", "plum"]);
+            assert!(LoadedWordList::from_words(["", " "]).is_err());
+        }
+
+        #[test]
+        fn test_parse_diceware_lines() {
+            let list = LoadedWordList::parse(["11111\tabacus", "11112 abdomen", "a b c"]).unwrap();
+            assert_eq!(list.words(), ["abacus", "abdomen"]);
+        }
+
+        #[test]
+        fn test_default_list_is_parsed_once() {
+            let first = LoadedWordList::load(&WordList::Default).unwrap();
+            let second = LoadedWordList::load(&WordList::Default).unwrap();
+            assert_eq!(first.len(), 7776);
+            assert!(Arc::ptr_eq(&first.words, &second.words));
+        }
+    }
+}
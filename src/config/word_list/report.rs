@@ -0,0 +1,332 @@
+//! Analysis of word lists before they are used for passphrases.
+//!
+//! A custom list is accepted as long as it has a single word, so problems such as malformed
+//! lines or repeated words only show up as weaker passphrases. A `WordListReport` points them
+//! out, together with words that are prefixes or suffixes of other words, which make
+//! passphrases without separators ambiguous.
+
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use crate::config::WordList;
+use crate::generator::PassphraseGenerator;
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::PassForgeError;
+
+/// The number of problems of each kind listed by the `Display` implementation.
+const MAX_LISTED: usize = 10;
+
+/// A line that does not hold a word, or a dice roll followed by a word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedLine {
+    /// The line number, starting at 1.
+    pub line: usize,
+    /// The content of the line, with bytes that are not valid UTF-8 replaced by `U+FFFD`.
+    pub content: String,
+}
+
+/// A word that appears on more than one line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateWord {
+    /// The repeated word.
+    pub word: String,
+    /// The line numbers the word appears on, starting at 1.
+    pub lines: Vec<usize>,
+}
+
+/// A pair of words where the first is a prefix or suffix of the second.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmbiguousPair {
+    /// The shorter word.
+    pub word: String,
+    /// The longer word that starts or ends with `word`.
+    pub other: String,
+}
+
+/// The findings of checking a word list.
+#[derive(Debug, Clone)]
+pub struct WordListReport {
+    /// The number of distinct words that would be used.
+    pub words: usize,
+    /// Lines that are skipped when the list is loaded.
+    pub skipped_lines: Vec<SkippedLine>,
+    /// Words that appear more than once. Only the first occurrence is used.
+    pub duplicates: Vec<DuplicateWord>,
+    /// Words that are a prefix of another word.
+    pub prefixes: Vec<AmbiguousPair>,
+    /// Words that are a suffix of another word.
+    pub suffixes: Vec<AmbiguousPair>,
+    /// The number of distinct words of each length in characters.
+    pub length_distribution: BTreeMap<usize, usize>,
+    /// The entropy each randomly chosen word adds, in bits.
+    pub entropy_per_word: f64,
+}
+
+impl WordListReport {
+    /// Reads a word list and reports on its contents.
+    ///
+    /// # Arguments
+    ///
+    /// * `word_list` - A reference to the `WordList` specifying the source of words.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the `WordListReport` if successful,
+    /// or a `PassForgeError` if the list could not be read.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if a custom file cannot be read.
+    pub fn check(word_list: &WordList) -> Result<Self, PassForgeError> {
+        let lines = PassphraseGenerator::load_file(word_list)?;
+        Ok(Self::from_results(lines.into_iter().map(|line| {
+            line.map_err(|err| String::from_utf8_lossy(err.as_bytes()).into_owned())
+        })))
+    }
+
+    /// Reports on the lines of a word list, parsed the same way as when it is loaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `lines` - The lines of the word list.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `WordListReport` of the lines.
+    pub fn from_lines<I, S>(lines: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::from_results(lines.into_iter().map(Ok))
+    }
+
+    /// Reports on the lines of a word list, where lines that are not valid UTF-8 are given as
+    /// errors holding their lossily decoded content and are reported as skipped.
+    fn from_results<I, S>(lines: I) -> Self
+    where
+        I: IntoIterator<Item = Result<S, String>>,
+        S: AsRef<str>,
+    {
+        let mut skipped_lines = Vec::new();
+        let mut words: Vec<String> = Vec::new();
+        let mut occurrences: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (index, line) in lines.into_iter().enumerate() {
+            let line = match &line {
+                Ok(line) => line.as_ref(),
+                Err(content) => {
+                    skipped_lines.push(SkippedLine {
+                        line: index + 1,
+                        content: content.clone(),
+                    });
+                    continue;
+                }
+            };
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let word = match parts[..] {
+                [] => continue,
+                [word] | [_, word] => word,
+                _ => {
+                    skipped_lines.push(SkippedLine {
+                        line: index + 1,
+                        content: line.to_string(),
+                    });
+                    continue;
+                }
+            };
+            let lines = occurrences.entry(word.to_string()).or_default();
+            if lines.is_empty() {
+                words.push(word.to_string());
+            }
+            lines.push(index + 1);
+        }
+
+        let duplicates = words
+            .iter()
+            .filter(|word| occurrences[*word].len() > 1)
+            .map(|word| DuplicateWord {
+                word: word.clone(),
+                lines: occurrences[word].clone(),
+            })
+            .collect();
+        let prefixes = prefix_pairs(words.clone());
+        let suffixes = prefix_pairs(words.iter().map(|word| reversed(word)).collect())
+            .into_iter()
+            .map(|pair| AmbiguousPair {
+                word: reversed(&pair.word),
+                other: reversed(&pair.other),
+            })
+            .collect();
+        let mut length_distribution = BTreeMap::new();
+        for word in &words {
+            *length_distribution.entry(word.chars().count()).or_insert(0) += 1;
+        }
+        let entropy_per_word = if words.is_empty() {
+            0.0
+        } else {
+            (words.len() as f64).log2()
+        };
+
+        Self {
+            words: words.len(),
+            skipped_lines,
+            duplicates,
+            prefixes,
+            suffixes,
+            length_distribution,
+            entropy_per_word,
+        }
+    }
+
+    /// Returns `true` if the list has words, and every line is used exactly once.
+    pub fn is_clean(&self) -> bool {
+        self.words > 0 && self.skipped_lines.is_empty() && self.duplicates.is_empty()
+    }
+
+    /// Returns `true` if no word is a prefix or suffix of another, so passphrases without
+    /// separators can be split back into their words.
+    pub fn is_unambiguous(&self) -> bool {
+        self.prefixes.is_empty() && self.suffixes.is_empty()
+    }
+}
+
+impl fmt::Display for WordListReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Words: {}", self.words)?;
+        writeln!(f, "Entropy per word: {:.2} bits", self.entropy_per_word)?;
+        writeln!(f, "Word lengths:")?;
+        for (length, count) in &self.length_distribution {
+            writeln!(f, "  {:>3} characters: {}", length, count)?;
+        }
+
+        writeln!(f, "Skipped lines: {}", self.skipped_lines.len())?;
+        for skipped in self.skipped_lines.iter().take(MAX_LISTED) {
+            writeln!(f, "  line {}: {:?}", skipped.line, skipped.content)?;
+        }
+        write_more(f, self.skipped_lines.len())?;
+
+        writeln!(f, "Duplicate words: {}", self.duplicates.len())?;
+        for duplicate in self.duplicates.iter().take(MAX_LISTED) {
+            let lines: Vec<String> = duplicate.lines.iter().map(usize::to_string).collect();
+            writeln!(f, "  {:?} on lines {}", duplicate.word, lines.join(", "))?;
+        }
+        write_more(f, self.duplicates.len())?;
+
+        for (kind, pairs) in [("prefix", &self.prefixes), ("suffix", &self.suffixes)] {
+            writeln!(
+                f,
+                "Words that are a {} of another word: {}",
+                kind,
+                pairs.len()
+            )?;
+            for pair in pairs.iter().take(MAX_LISTED) {
+                writeln!(f, "  {:?} is a {} of {:?}", pair.word, kind, pair.other)?;
+            }
+            write_more(f, pairs.len())?;
+        }
+        Ok(())
+    }
+}
+
+/// Notes how many problems were left out of a listing.
+fn write_more(f: &mut fmt::Formatter<'_>, count: usize) -> fmt::Result {
+    if count > MAX_LISTED {
+        writeln!(f, "  ... and {} more", count - MAX_LISTED)?;
+    }
+    Ok(())
+}
+
+/// Returns the characters of the word in reverse order.
+fn reversed(word: &str) -> String {
+    word.chars().rev().collect()
+}
+
+/// Finds every pair of distinct words where the first is a prefix of the second.
+fn prefix_pairs(mut words: Vec<String>) -> Vec<AmbiguousPair> {
+    // After sorting, the words starting with a word directly follow it.
+    words.sort_unstable();
+    let mut pairs = Vec::new();
+    for (i, word) in words.iter().enumerate() {
+        for other in words[i + 1..]
+            .iter()
+            .take_while(|other| other.starts_with(word.as_str()))
+        {
+            pairs.push(AmbiguousPair {
+                word: word.clone(),
+                other: other.clone(),
+            });
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod word_list_report_tests {
+        use alloc::vec;
+
+        use super::*;
+
+        #[test]
+        fn test_report_findings() {
+            let report = WordListReport::from_lines([
+                "11111 act",
+                "11112 action",
+                "11113 react",
+                "",
+                "bad line here",
+                "act",
+                "zebra",
+            ]);
+            assert_eq!(report.words, 4);
+            assert_eq!(
+                report.skipped_lines,
+                [SkippedLine {
+                    line: 5,
+                    content: "bad line here".into()
+                }]
+            );
+            assert_eq!(
+                report.duplicates,
+                [DuplicateWord {
+                    word: "act".into(),
+                    lines: vec![1, 6]
+                }]
+            );
+            assert_eq!(
+                report.prefixes,
+                [AmbiguousPair {
+                    word: "act".into(),
+                    other: "action".into()
+                }]
+            );
+            assert_eq!(
+                report.suffixes,
+                [AmbiguousPair {
+                    word: "act".into(),
+                    other: "react".into()
+                }]
+            );
+            assert_eq!(report.length_distribution[&5], 2);
+            assert_eq!(report.entropy_per_word, 2.0);
+            assert!(!report.is_clean());
+            assert!(!report.is_unambiguous());
+        }
+
+        #[test]
+        fn test_default_list_is_clean() {
+            let report = WordListReport::check(&WordList::Default).unwrap();
+            assert_eq!(report.words, 7776);
+            assert!(report.is_clean());
+            assert!((report.entropy_per_word - 12.925).abs() < 1e-3);
+            assert!(report.to_string().contains("Skipped lines: 0"));
+        }
+    }
+}
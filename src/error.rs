@@ -3,14 +3,18 @@
 //! It provides a comprehensive set of errors that can occur during password
 //! and passphrase generation, configuration, and strength evaluation.
 
+use alloc::string::String;
+use core::num::ParseIntError;
+#[cfg(feature = "std")]
 use std::io;
-use std::num::ParseIntError;
+
 use thiserror::Error;
 
 /// Represents all possible errors that can occur in the PassForge library.
 #[derive(Error, Debug)]
 pub enum PassForgeError {
     /// Represents errors that occur during I/O operations.
+    #[cfg(feature = "std")]
     #[error("IO error: {0}")]
     Io(#[from] io::Error),
 
@@ -34,6 +38,14 @@ pub enum PassForgeError {
     #[error("Word list error: {0}")]
     WordListError(String),
 
+    /// Represents errors related to physical dice rolls entered for diceware passphrases.
+    #[error("Invalid dice roll: {0}")]
+    InvalidDiceRoll(String),
+
+    /// Represents a token that does not match its configuration or checksum.
+    #[error("Invalid token: {0}")]
+    InvalidToken(String),
+
     /// Represents errors that occur during strength evaluation.
     #[error("Strength evaluation error: {0}")]
     StrengthEvaluationError(String),
@@ -49,4 +61,4 @@ pub enum PassForgeError {
     /// Represents unknown or unexpected errors.
     #[error("Unknown error occurred")]
     Unknown,
-}
\ No newline at end of file
+}
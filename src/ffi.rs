@@ -0,0 +1,485 @@
+//! This module exposes password and passphrase generation and strength evaluation through a
+//! C ABI, for callers written in C, Go or any language with a C foreign function interface.
+//!
+//! Configurations are opaque handles created by the `*_config_new` functions and released with
+//! the matching `*_config_free` function. Fallible functions return an `ErrorCode`, and a
+//! description of the last error on the calling thread is available from
+//! `passforge_last_error_message`. Strings returned through out parameters belong to the
+//! caller and must be released with `passforge_string_free`, which also wipes them.
+//!
+//! The C declarations are in `include/passforge.h`. Building with the `ffi` feature
+//! regenerates them into `OUT_DIR`, and a test checks that the committed header matches.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::ptr;
+
+use zeroize::Zeroize;
+
+use crate::{
+    Generator, Length, LoadedWordList, PassForgeError, PassphraseConfig, PassphraseGenerator,
+    PasswordConfig, PasswordGenerator, SecretString, StrengthEvaluator, WordList, ZxcvbnAnalysis,
+};
+
+/// The result of a fallible function. Every value other than `Ok` is an error.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = 1,
+    /// A string argument was not valid UTF-8.
+    InvalidUtf8 = 2,
+    /// Reading a file failed.
+    Io = 3,
+    /// The password length is invalid.
+    InvalidLength = 4,
+    /// The passphrase word count is invalid.
+    InvalidWordCount = 5,
+    /// The number of items to generate is invalid.
+    InvalidGenAmount = 6,
+    /// The configuration is invalid.
+    InvalidConfig = 7,
+    /// The word list could not be loaded or has no words.
+    WordList = 8,
+    /// A dice roll is invalid.
+    InvalidDiceRoll = 9,
+    /// Strength evaluation failed.
+    StrengthEvaluation = 10,
+    /// A number could not be parsed.
+    Parse = 11,
+    /// Random number generation failed.
+    Random = 12,
+    /// An unexpected error occurred.
+    Unknown = 13,
+    /// The library panicked. This is a bug, and the call had no effect.
+    Panic = 14,
+    /// A token does not match its configuration or checksum.
+    InvalidToken = 15,
+}
+
+impl From<&PassForgeError> for ErrorCode {
+    fn from(error: &PassForgeError) -> Self {
+        match error {
+            PassForgeError::Io(_) => ErrorCode::Io,
+            PassForgeError::InvalidLength(_) => ErrorCode::InvalidLength,
+            PassForgeError::InvalidWordCount(_) => ErrorCode::InvalidWordCount,
+            PassForgeError::InvalidGenAmount(_) => ErrorCode::InvalidGenAmount,
+            PassForgeError::InvalidConfig(_) => ErrorCode::InvalidConfig,
+            PassForgeError::WordListError(_) => ErrorCode::WordList,
+            PassForgeError::InvalidDiceRoll(_) => ErrorCode::InvalidDiceRoll,
+            PassForgeError::InvalidToken(_) => ErrorCode::InvalidToken,
+            PassForgeError::StrengthEvaluationError(_) => ErrorCode::StrengthEvaluation,
+            PassForgeError::ParseError(_) => ErrorCode::Parse,
+            PassForgeError::RandomError => ErrorCode::Random,
+            PassForgeError::Unknown => ErrorCode::Unknown,
+        }
+    }
+}
+
+/// An error code together with the message stored for `passforge_last_error_message`.
+struct Failure {
+    code: ErrorCode,
+    message: String,
+}
+
+impl Failure {
+    fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<PassForgeError> for Failure {
+    fn from(error: PassForgeError) -> Self {
+        Self::new(ErrorCode::from(&error), error.to_string())
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Stores the message returned by `passforge_last_error_message` on this thread.
+fn set_last_error(message: Option<String>) {
+    let message = message.map(|message| {
+        CString::new(message.replace('\0', " ")).expect("interior NUL bytes were replaced")
+    });
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+}
+
+/// Runs the body of an exported function, turning errors and panics into error codes.
+/// Unwinding into the caller is undefined behavior, and a panic leaves no handle half updated
+/// as every function changes at most one field.
+fn run<F>(body: F) -> ErrorCode
+where
+    F: FnOnce() -> Result<(), Failure>,
+{
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => {
+            set_last_error(None);
+            ErrorCode::Ok
+        }
+        Ok(Err(failure)) => {
+            set_last_error(Some(failure.message));
+            failure.code
+        }
+        Err(_) => {
+            set_last_error(Some("PassForge panicked".into()));
+            ErrorCode::Panic
+        }
+    }
+}
+
+/// Runs the body of an exported constructor, returning null on errors and panics.
+fn run_new<T, F>(body: F) -> *mut T
+where
+    F: FnOnce() -> Result<T, Failure>,
+{
+    let mut handle = ptr::null_mut();
+    run(|| {
+        handle = Box::into_raw(Box::new(body()?));
+        Ok(())
+    });
+    handle
+}
+
+/// Borrows a C string argument as UTF-8.
+///
+/// # Safety
+///
+/// `value` must be null or point to a NUL-terminated string that outlives the borrow.
+unsafe fn borrow_str<'a>(value: *const c_char, name: &str) -> Result<&'a str, Failure> {
+    if value.is_null() {
+        return Err(Failure::new(
+            ErrorCode::NullPointer,
+            format!("{} cannot be null", name),
+        ));
+    }
+    CStr::from_ptr(value)
+        .to_str()
+        .map_err(|_| Failure::new(ErrorCode::InvalidUtf8, format!("{} is not UTF-8", name)))
+}
+
+/// Borrows a handle argument.
+///
+/// # Safety
+///
+/// `handle` must be null or a valid pointer that outlives the borrow.
+unsafe fn borrow_handle<'a, T>(handle: *mut T, name: &str) -> Result<&'a mut T, Failure> {
+    handle
+        .as_mut()
+        .ok_or_else(|| Failure::new(ErrorCode::NullPointer, format!("{} cannot be null", name)))
+}
+
+/// Hands a copy of a generated string to the caller through an out parameter. The copy is
+/// allocated with room for the NUL terminator, so appending it leaves no stray copy behind.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writing a pointer.
+unsafe fn write_string(out: *mut *mut c_char, value: &str) -> Result<(), Failure> {
+    if out.is_null() {
+        return Err(Failure::new(ErrorCode::NullPointer, "out cannot be null"));
+    }
+    let mut bytes = Vec::with_capacity(value.len() + 1);
+    bytes.extend_from_slice(value.as_bytes());
+    let value = CString::new(bytes).map_err(|error| {
+        error.into_vec().zeroize();
+        Failure::new(
+            ErrorCode::InvalidConfig,
+            "The generated text contains a NUL byte",
+        )
+    })?;
+    *out = value.into_raw();
+    Ok(())
+}
+
+/// Creates a password configuration with a fixed length and lowercase letters, optionally
+/// with capitals, numbers and symbols.
+///
+/// # Returns
+///
+/// Returns a handle to release with `passforge_password_config_free`.
+#[no_mangle]
+pub extern "C" fn passforge_password_config_new(
+    length: usize,
+    capitals: bool,
+    numbers: bool,
+    symbols: bool,
+) -> *mut PasswordConfig {
+    run_new(|| {
+        Ok(PasswordConfig::new(
+            Length::Single(length),
+            capitals,
+            numbers,
+            symbols,
+        ))
+    })
+}
+
+/// Makes the configuration pick a random length between `min` and `max`, inclusive.
+///
+/// # Errors
+///
+/// Returns `InvalidLength` if `max` is smaller than `min`.
+///
+/// # Safety
+///
+/// `config` must be a handle from `passforge_password_config_new` that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn passforge_password_config_set_length_range(
+    config: *mut PasswordConfig,
+    min: usize,
+    max: usize,
+) -> ErrorCode {
+    run(|| {
+        let config = borrow_handle(config, "config")?;
+        config.length = match max {
+            max if max == min => Length::Single(min),
+            max if max > min => Length::Range(min..=max),
+            _ => {
+                return Err(PassForgeError::InvalidLength(
+                    "Maximum length must be greater than or equal to minimum length".into(),
+                )
+                .into())
+            }
+        };
+        Ok(())
+    })
+}
+
+/// Sets the characters that never appear in generated passwords.
+///
+/// # Safety
+///
+/// `config` must be a handle from `passforge_password_config_new` that has not been freed,
+/// and `chars` a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn passforge_password_config_set_excluded_chars(
+    config: *mut PasswordConfig,
+    chars: *const c_char,
+) -> ErrorCode {
+    run(|| {
+        let config = borrow_handle(config, "config")?;
+        config.excluded_chars = borrow_str(chars, "chars")?.to_string();
+        Ok(())
+    })
+}
+
+/// Releases a password configuration. Does nothing if `config` is null.
+///
+/// # Safety
+///
+/// `config` must be null or a handle from `passforge_password_config_new` that has not been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn passforge_password_config_free(config: *mut PasswordConfig) {
+    if !config.is_null() {
+        drop(Box::from_raw(config));
+    }
+}
+
+/// Generates a password and stores it in `out`.
+///
+/// # Errors
+///
+/// Returns an error code if the configuration is invalid, and leaves `out` unchanged.
+///
+/// # Safety
+///
+/// `config` must be a handle from `passforge_password_config_new` that has not been freed,
+/// and `out` valid for writing a pointer. The password must be released with
+/// `passforge_string_free`.
+#[no_mangle]
+pub unsafe extern "C" fn passforge_password_generate(
+    config: *const PasswordConfig,
+    out: *mut *mut c_char,
+) -> ErrorCode {
+    run(|| {
+        let config = borrow_handle(config.cast_mut(), "config")?;
+        write_string(out, PasswordGenerator::generate(config)?.expose_secret())
+    })
+}
+
+/// Creates a passphrase configuration using the default word list.
+///
+/// # Returns
+///
+/// Returns a handle to release with `passforge_passphrase_config_free`, or null if
+/// `separator` is null or not UTF-8.
+///
+/// # Safety
+///
+/// `separator` must be a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn passforge_passphrase_config_new(
+    words: usize,
+    separator: *const c_char,
+) -> *mut PassphraseConfig {
+    run_new(|| {
+        let separator = borrow_str(separator, "separator")?;
+        Ok(PassphraseConfig::new(
+            words,
+            separator.to_string(),
+            WordList::Default,
+        ))
+    })
+}
+
+/// Loads a word list file for the configuration, with one word or a dice roll followed by a
+/// word per line. The file is read once, by this call.
+///
+/// # Errors
+///
+/// Returns `Io` if the file cannot be read, or `WordList` if it has no words.
+///
+/// # Safety
+///
+/// `config` must be a handle from `passforge_passphrase_config_new` that has not been freed,
+/// and `path` a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn passforge_passphrase_config_set_word_list_file(
+    config: *mut PassphraseConfig,
+    path: *const c_char,
+) -> ErrorCode {
+    run(|| {
+        let config = borrow_handle(config, "config")?;
+        let path = PathBuf::from(borrow_str(path, "path")?);
+        config.word_list = LoadedWordList::load(&WordList::Custom(path))?.into();
+        Ok(())
+    })
+}
+
+/// Selects a bundled word list by name, such as `default`.
+///
+/// # Errors
+///
+/// Returns `WordList` if the name is unknown.
+///
+/// # Safety
+///
+/// `config` must be a handle from `passforge_passphrase_config_new` that has not been freed,
+/// and `name` a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn passforge_passphrase_config_set_word_list_name(
+    config: *mut PassphraseConfig,
+    name: *const c_char,
+) -> ErrorCode {
+    run(|| {
+        let config = borrow_handle(config, "config")?;
+        config.word_list = WordList::from_name(borrow_str(name, "name")?)?;
+        Ok(())
+    })
+}
+
+/// Releases a passphrase configuration. Does nothing if `config` is null.
+///
+/// # Safety
+///
+/// `config` must be null or a handle from `passforge_passphrase_config_new` that has not been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn passforge_passphrase_config_free(config: *mut PassphraseConfig) {
+    if !config.is_null() {
+        drop(Box::from_raw(config));
+    }
+}
+
+/// Generates a passphrase and stores it in `out`.
+///
+/// # Errors
+///
+/// Returns an error code if the configuration is invalid, and leaves `out` unchanged.
+///
+/// # Safety
+///
+/// `config` must be a handle from `passforge_passphrase_config_new` that has not been freed,
+/// and `out` valid for writing a pointer. The passphrase must be released with
+/// `passforge_string_free`.
+#[no_mangle]
+pub unsafe extern "C" fn passforge_passphrase_generate(
+    config: *const PassphraseConfig,
+    out: *mut *mut c_char,
+) -> ErrorCode {
+    run(|| {
+        let config = borrow_handle(config.cast_mut(), "config")?;
+        write_string(out, PassphraseGenerator::generate(config)?.expose_secret())
+    })
+}
+
+/// Evaluates the strength of a password with zxcvbn and stores a description of the score
+/// and crack time in `out`.
+///
+/// # Errors
+///
+/// Returns `InvalidLength` if the password is empty.
+///
+/// # Safety
+///
+/// `password` must be a NUL-terminated string, and `out` valid for writing a pointer. The
+/// description must be released with `passforge_string_free`.
+#[no_mangle]
+pub unsafe extern "C" fn passforge_evaluate_strength(
+    password: *const c_char,
+    out: *mut *mut c_char,
+) -> ErrorCode {
+    run(|| {
+        let password = SecretString::from(borrow_str(password, "password")?);
+        write_string(out, &ZxcvbnAnalysis::evaluate(&password)?)
+    })
+}
+
+/// Checks whether a password reaches a zxcvbn score of at least 3, storing the answer in
+/// `passes`.
+///
+/// # Errors
+///
+/// Returns `InvalidLength` if the password is empty.
+///
+/// # Safety
+///
+/// `password` must be a NUL-terminated string, and `passes` valid for writing a `bool`.
+#[no_mangle]
+pub unsafe extern "C" fn passforge_passes_threshold(
+    password: *const c_char,
+    passes: *mut bool,
+) -> ErrorCode {
+    run(|| {
+        let password = SecretString::from(borrow_str(password, "password")?);
+        let result = ZxcvbnAnalysis::passes_threshold(&password)?;
+        *borrow_handle(passes, "passes")? = result;
+        Ok(())
+    })
+}
+
+/// Wipes and releases a string returned by this library. Does nothing if `value` is null.
+///
+/// # Safety
+///
+/// `value` must be null or a string returned through an out parameter of this library that
+/// has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn passforge_string_free(value: *mut c_char) {
+    if value.is_null() {
+        return;
+    }
+    CString::from_raw(value).into_bytes_with_nul().zeroize();
+}
+
+/// Returns a description of the last error on the calling thread, or null if the last call
+/// succeeded. The message stays valid until the next call into this library on the thread,
+/// and must not be freed.
+#[no_mangle]
+pub extern "C" fn passforge_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
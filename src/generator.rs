@@ -3,18 +3,158 @@
 //! It provides a common `Generator` trait that can be implemented by different types of
 //! generators, allowing for a flexible and extensible password generation system.
 
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::Write;
+
+#[cfg(feature = "std")]
+use rand::rngs::ThreadRng;
+use rand::{CryptoRng, RngCore};
+
 use crate::PassForgeError;
+#[cfg(feature = "std")]
+use crate::SecretString;
+
+/// A function that generates one item at a time from state prepared for a configuration,
+/// such as a loaded word list or a character table.
+pub type Prepared<'a, R, T> = Box<dyn FnMut(&mut R) -> Result<T, PassForgeError> + 'a>;
 
 /// The `Generator` trait defines the interface for password and passphrase generation.
 ///
 /// This trait allows for a common interface across different types of generators,
 /// enabling easy swapping and extension of generation algorithms.
+///
+/// Implementors only need to provide `generate_with_rng`; the remaining methods delegate to it,
+/// using the thread-local cryptographically secure RNG when no RNG is supplied. Generators with
+/// costly setup can also override `prepare`, which every method producing more than one item
+/// goes through. Without the `std` feature, only the methods taking an RNG are available.
 pub trait Generator {
     /// The configuration type used by this generator.
     type Config;
     /// The output type produced by this generator.
     type Output;
 
+    /// Generates a single item (password or passphrase) using the supplied random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the configuration specifying generation parameters.
+    /// * `rng` - The cryptographically secure random number generator to draw from.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the generated item if successful, or a `PassForgeError` if an error occurred.
+    fn generate_with_rng<R: RngCore + CryptoRng + ?Sized>(
+        config: &Self::Config,
+        rng: &mut R,
+    ) -> Result<Self::Output, PassForgeError>;
+
+    /// Validates the configuration and prepares the state that can be reused between items.
+    /// By default nothing is prepared, and every item is generated by `generate_with_rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the configuration specifying generation parameters.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing a function that generates one item per call if successful,
+    /// or a `PassForgeError` if the configuration is invalid.
+    fn prepare<'a, R: RngCore + CryptoRng + ?Sized + 'a>(
+        config: &'a Self::Config,
+    ) -> Result<Prepared<'a, R, Self::Output>, PassForgeError>
+    where
+        Self: 'a,
+    {
+        Ok(Box::new(move |rng| Self::generate_with_rng(config, rng)))
+    }
+
+    /// Generates multiple items (passwords or passphrases) using the supplied random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the configuration specifying generation parameters.
+    /// * `amount` - The number of items to generate.
+    /// * `rng` - The cryptographically secure random number generator to draw from.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing a vector of generated items if successful, or a `PassForgeError` if an error occurred.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the specified amount is less than or equal to 1.
+    fn generate_multiple_with_rng<R: RngCore + CryptoRng + ?Sized>(
+        config: &Self::Config,
+        amount: usize,
+        rng: &mut R,
+    ) -> Result<Vec<Self::Output>, PassForgeError> {
+        if amount <= 1 {
+            return Err(PassForgeError::InvalidGenAmount(
+                "Amount cannot be smaller than 1".into(),
+            ));
+        }
+        let mut generate = Self::prepare(config)?;
+        (0..amount).map(|_| generate(rng)).collect()
+    }
+
+    /// Returns an endless iterator of items generated with the supplied random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the configuration specifying generation parameters.
+    /// * `rng` - The cryptographically secure random number generator to draw from.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the iterator if successful, or a `PassForgeError` if the
+    /// configuration is invalid. Each item is itself a `Result`.
+    fn iter_with_rng<'a, R: RngCore + CryptoRng + 'a>(
+        config: &'a Self::Config,
+        rng: R,
+    ) -> Result<GeneratorIter<'a, R, Self::Output>, PassForgeError>
+    where
+        Self: 'a,
+    {
+        Ok(GeneratorIter {
+            generate: Self::prepare(config)?,
+            rng,
+        })
+    }
+
+    /// Generates items using the supplied random number generator and writes them to the
+    /// writer, one per line, without keeping them in memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the configuration specifying generation parameters.
+    /// * `writer` - The writer to write the items to.
+    /// * `count` - The number of items to write.
+    /// * `rng` - The cryptographically secure random number generator to draw from.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` if every item was written, or a `PassForgeError` if generation or
+    /// writing failed.
+    #[cfg(feature = "std")]
+    fn generate_into_with_rng<W: Write + ?Sized, R: RngCore + CryptoRng + ?Sized>(
+        config: &Self::Config,
+        writer: &mut W,
+        count: usize,
+        rng: &mut R,
+    ) -> Result<(), PassForgeError>
+    where
+        Self::Output: Into<SecretString>,
+    {
+        let mut generate = Self::prepare(config)?;
+        for _ in 0..count {
+            let item: SecretString = generate(rng)?.into();
+            writeln!(writer, "{}", item.expose_secret())?;
+        }
+        Ok(())
+    }
+
     /// Generates a single item (password or passphrase) based on the provided configuration.
     ///
     /// # Arguments
@@ -24,7 +164,10 @@ pub trait Generator {
     /// # Returns
     ///
     /// Returns a `Result` containing the generated item if successful, or a `PassForgeError` if an error occurred.
-    fn generate(config: &Self::Config) -> Result<Self::Output, PassForgeError>;
+    #[cfg(feature = "std")]
+    fn generate(config: &Self::Config) -> Result<Self::Output, PassForgeError> {
+        Self::generate_with_rng(config, &mut rand::thread_rng())
+    }
 
     /// Generates multiple items (passwords or passphrases) based on the provided configuration.
     ///
@@ -36,14 +179,95 @@ pub trait Generator {
     /// # Returns
     ///
     /// Returns a `Result` containing a vector of generated items if successful, or a `PassForgeError` if an error occurred.
+    #[cfg(feature = "std")]
     fn generate_multiple(
         config: &Self::Config,
         amount: usize,
-    ) -> Result<Vec<Self::Output>, PassForgeError>;
+    ) -> Result<Vec<Self::Output>, PassForgeError> {
+        Self::generate_multiple_with_rng(config, amount, &mut rand::thread_rng())
+    }
+
+    /// Returns an endless iterator of items generated from the provided configuration, for
+    /// example to generate until a condition holds.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the configuration specifying generation parameters.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the iterator if successful, or a `PassForgeError` if the
+    /// configuration is invalid. Each item is itself a `Result`.
+    #[cfg(feature = "std")]
+    fn iter<'a>(
+        config: &'a Self::Config,
+    ) -> Result<GeneratorIter<'a, ThreadRng, Self::Output>, PassForgeError>
+    where
+        Self: 'a,
+    {
+        Self::iter_with_rng(config, rand::thread_rng())
+    }
+
+    /// Generates items from the provided configuration and writes them to the writer, one per
+    /// line, without keeping them in memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the configuration specifying generation parameters.
+    /// * `writer` - The writer to write the items to.
+    /// * `count` - The number of items to write.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` if every item was written, or a `PassForgeError` if generation or
+    /// writing failed.
+    #[cfg(feature = "std")]
+    fn generate_into<W: Write + ?Sized>(
+        config: &Self::Config,
+        writer: &mut W,
+        count: usize,
+    ) -> Result<(), PassForgeError>
+    where
+        Self::Output: Into<SecretString>,
+    {
+        Self::generate_into_with_rng(config, writer, count, &mut rand::thread_rng())
+    }
+}
+
+/// An endless iterator over generated items, returned by `Generator::iter`.
+pub struct GeneratorIter<'a, R, T> {
+    generate: Prepared<'a, R, T>,
+    rng: R,
+}
+
+impl<R, T> Iterator for GeneratorIter<'_, R, T> {
+    type Item = Result<T, PassForgeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some((self.generate)(&mut self.rng))
+    }
 }
 
+pub mod derived;
+pub mod mask;
+pub mod otp;
+#[cfg(feature = "parallel")]
+pub mod parallel;
 pub mod passphrase;
 pub mod password;
+pub mod pin;
+pub mod pronounceable;
+pub mod recovery;
+pub mod token;
 
+pub use derived::DerivedGenerator;
+pub use mask::MaskGenerator;
+pub use otp::{hotp_code, otpauth_uri, totp_code, OtpGenerator};
+#[cfg(feature = "parallel")]
+pub use parallel::ParallelGenerator;
 pub use passphrase::PassphraseGenerator;
 pub use password::PasswordGenerator;
+pub use pin::PinGenerator;
+pub use pronounceable::PronounceableGenerator;
+pub use recovery::{verify_recovery_code, RecoveryCodes, RecoveryCodesGenerator};
+pub use token::{validate_token, TokenGenerator};
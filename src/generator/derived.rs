@@ -0,0 +1,463 @@
+//! This module implements deterministic site password derivation.
+//!
+//! It provides a `DerivedGenerator` struct that implements the `Generator` trait, deriving
+//! the same password from the same master secret, site, login and counter every time, in
+//! the spirit of LessPass and Spectre.
+
+use alloc::{format, string::String, vec::Vec};
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::{CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use zeroize::Zeroize;
+
+use crate::config::{
+    CharClass, ClassCount, DerivedConfig, DerivedTemplate, LoadedWordList, WordList,
+};
+use crate::generator::Generator;
+use crate::{PassForgeError, SecretString};
+
+/// Domain separation tag at the start of the Argon2id salt. A new tag is introduced
+/// whenever the derivation changes, so old passwords stay reproducible.
+const SALT_TAG: &[u8] = b"passforge.derive.v1";
+
+/// The character classes of a derived password, in the order they are drawn from. They are
+/// part of the derivation, so they do not follow changes to the password generator's classes.
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const NUMBERS: &str = "0123456789";
+const SYMBOLS: &str = "!@#$%^&*()-_=+[]{}|;:,.<>?";
+
+/// Struct for deriving passwords and passphrases from a master secret.
+///
+/// The derivation, version 1, is:
+///
+/// 1. The salt is the ASCII tag `passforge.derive.v1`, followed by the UTF-8 site and the
+///    UTF-8 login, each preceded by its length in bytes as a big-endian `u32`, and finally
+///    the counter as a big-endian `u32`.
+/// 2. Argon2id (version 0x13) turns the UTF-8 master secret and the salt into 32 bytes,
+///    with the memory, passes and parallelism of the configuration.
+/// 3. The 32 bytes are the key of a ChaCha20 keystream with a zero nonce and a block counter
+///    starting at 0, read as consecutive little-endian `u32` words.
+/// 4. A number below `n` is drawn by taking the next word `x`, discarding it while
+///    `x >= 2^32 - (2^32 mod n)`, and returning `x mod n`.
+/// 5. A password draws the minimum number of characters of each of its classes in turn,
+///    then the remaining characters from all of them concatenated, and is shuffled by swapping
+///    position `i` with a drawn position below `i + 1`, for `i` from the last position down
+///    to 1. The `Password` template uses the classes `a-z`, then `A-Z`, `0-9` and the symbols
+///    ``!@#$%^&*()-_=+[]{}|;:,.<>?`` when enabled, in that order, with a minimum of 1 each.
+///    A `Classes` template uses its classes as given.
+/// 6. A passphrase draws each word from the 7,776 words of the bundled EFF large word list,
+///    in file order, and joins them with the separator.
+///
+/// Every step works on integers, so the output is the same on every platform. The test
+/// vectors in this module pin it; a change that breaks them must come with a new salt tag.
+pub struct DerivedGenerator;
+
+impl DerivedGenerator {
+    /// Builds the Argon2id salt from the site, login and counter.
+    fn salt(config: &DerivedConfig) -> Vec<u8> {
+        let mut salt =
+            Vec::with_capacity(SALT_TAG.len() + config.site.len() + config.login.len() + 12);
+        salt.extend_from_slice(SALT_TAG);
+        for field in [&config.site, &config.login] {
+            salt.extend_from_slice(&(field.len() as u32).to_be_bytes());
+            salt.extend_from_slice(field.as_bytes());
+        }
+        salt.extend_from_slice(&config.counter.to_be_bytes());
+        salt
+    }
+
+    /// Runs Argon2id over the master secret, giving the seed for rendering.
+    fn derive_seed(config: &DerivedConfig) -> Result<[u8; 32], PassForgeError> {
+        let params = Params::new(
+            config.memory_kib,
+            config.iterations,
+            config.parallelism,
+            Some(32),
+        )
+        .map_err(|e| {
+            PassForgeError::InvalidConfig(format!("Invalid Argon2id parameters: {}", e))
+        })?;
+        let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
+        let mut seed = [0u8; 32];
+        argon2
+            .hash_password_into(
+                config.master.expose_secret().as_bytes(),
+                &Self::salt(config),
+                &mut seed,
+            )
+            .map_err(|e| PassForgeError::InvalidConfig(format!("Argon2id failed: {}", e)))?;
+        Ok(seed)
+    }
+
+    /// Draws a number below `bound` from the keystream by rejection sampling.
+    fn draw<R: RngCore + ?Sized>(rng: &mut R, bound: usize) -> usize {
+        let bound = bound as u64;
+        let limit = (1u64 << 32) - (1u64 << 32) % bound;
+        loop {
+            let x = u64::from(rng.next_u32());
+            if x < limit {
+                return (x % bound) as usize;
+            }
+        }
+    }
+
+    /// Returns the classes of a `Password` template, each requiring one character.
+    fn password_classes(capitals: bool, numbers: bool, symbols: bool) -> Vec<CharClass> {
+        [
+            ("lowercase letters", true, LOWERCASE),
+            ("capital letters", capitals, UPPERCASE),
+            ("numbers", numbers, NUMBERS),
+            ("symbols", symbols, SYMBOLS),
+        ]
+        .into_iter()
+        .filter(|(_, enabled, _)| *enabled)
+        .map(|(name, _, chars)| CharClass::new(name, chars, ClassCount::at_least(1)))
+        .collect()
+    }
+
+    /// Renders a password with at least the minimum number of characters of each class.
+    fn render_password<R: RngCore + ?Sized>(
+        rng: &mut R,
+        length: usize,
+        classes: &[CharClass],
+    ) -> SecretString {
+        let all: Vec<char> = classes
+            .iter()
+            .flat_map(|class| class.chars.iter().copied())
+            .collect();
+
+        let mut chars = Vec::with_capacity(length);
+        for class in classes {
+            for _ in 0..class.count.min {
+                chars.push(class.chars[Self::draw(rng, class.chars.len())]);
+            }
+        }
+        while chars.len() < length {
+            chars.push(all[Self::draw(rng, all.len())]);
+        }
+        for i in (1..length).rev() {
+            chars.swap(i, Self::draw(rng, i + 1));
+        }
+        SecretString::from_chars(chars)
+    }
+
+    /// Renders a passphrase from the words of the bundled word list.
+    fn render_passphrase<R: RngCore + ?Sized>(
+        rng: &mut R,
+        words: usize,
+        separator: &str,
+    ) -> Result<SecretString, PassForgeError> {
+        let list = LoadedWordList::load(&WordList::Default)?;
+        let picked: Vec<&str> = (0..words)
+            .map(|_| list.words()[Self::draw(rng, list.len())].as_str())
+            .collect();
+        let length =
+            picked.iter().map(|word| word.len()).sum::<usize>() + separator.len() * (words - 1);
+        let mut passphrase = String::with_capacity(length);
+        for (index, word) in picked.into_iter().enumerate() {
+            if index > 0 {
+                passphrase.push_str(separator);
+            }
+            passphrase.push_str(word);
+        }
+        Ok(SecretString::new(passphrase))
+    }
+}
+
+impl Generator for DerivedGenerator {
+    type Config = DerivedConfig;
+    type Output = SecretString;
+
+    /// Derives the password or passphrase for the provided configuration.
+    ///
+    /// The result only depends on the configuration, so the supplied random number generator
+    /// is not used, and generating several items gives the same one each time.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the `DerivedConfig` specifying derivation parameters.
+    /// * `_rng` - Unused.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the derived password or passphrase as a `SecretString`
+    /// if successful, or a `PassForgeError` if an error occurred during derivation.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the master secret or site is empty, the Argon2id parameters
+    /// are out of range, the password length does not fit its classes, or the number of words
+    /// is out of range.
+    fn generate_with_rng<R: RngCore + CryptoRng + ?Sized>(
+        config: &Self::Config,
+        _rng: &mut R,
+    ) -> Result<Self::Output, PassForgeError> {
+        config.check()?;
+        let mut seed = Self::derive_seed(config)?;
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        seed.zeroize();
+        match &config.template {
+            DerivedTemplate::Password {
+                length,
+                capitals,
+                numbers,
+                symbols,
+            } => Ok(Self::render_password(
+                &mut rng,
+                *length,
+                &Self::password_classes(*capitals, *numbers, *symbols),
+            )),
+            DerivedTemplate::Classes { length, classes } => {
+                Ok(Self::render_password(&mut rng, *length, classes))
+            }
+            DerivedTemplate::Passphrase { words, separator } => {
+                Self::render_passphrase(&mut rng, *words, separator)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod derived_generator_tests {
+        use alloc::vec;
+        use rand::{rngs::StdRng, SeedableRng};
+        use rand_chacha::rand_core::impls;
+
+        use crate::config::alphabet::AMBIGUOUS;
+        use crate::config::{PasswordConfig, TokenEncoding};
+        use crate::Length;
+
+        use super::*;
+
+        /// Hands out fixed keystream words, to check the sampling steps on their own.
+        struct Words(Vec<u32>);
+
+        impl RngCore for Words {
+            fn next_u32(&mut self) -> u32 {
+                self.0.remove(0)
+            }
+
+            fn next_u64(&mut self) -> u64 {
+                impls::next_u64_via_u32(self)
+            }
+
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                impls::fill_bytes_via_next(self, dest)
+            }
+
+            fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+                self.fill_bytes(dest);
+                Ok(())
+            }
+        }
+
+        /// A configuration with cheap Argon2id parameters, so the vectors run quickly.
+        fn cheap(
+            site: &str,
+            login: &str,
+            counter: u32,
+            template: DerivedTemplate,
+        ) -> DerivedConfig {
+            DerivedConfig::builder()
+                .master("correct horse battery staple".into())
+                .site(site.into())
+                .login(login.into())
+                .counter(counter)
+                .template(template)
+                .memory_kib(256)
+                .iterations(1)
+                .build()
+        }
+
+        fn password(
+            length: usize,
+            capitals: bool,
+            numbers: bool,
+            symbols: bool,
+        ) -> DerivedTemplate {
+            DerivedTemplate::Password {
+                length,
+                capitals,
+                numbers,
+                symbols,
+            }
+        }
+
+        fn derive(config: &DerivedConfig) -> String {
+            DerivedGenerator::generate_with_rng(config, &mut StdRng::seed_from_u64(0))
+                .unwrap()
+                .expose_secret()
+                .into()
+        }
+
+        #[test]
+        fn test_salt_layout() {
+            let salt =
+                DerivedGenerator::salt(&cheap("ab", "c", 258, password(8, true, true, true)));
+            assert_eq!(
+                salt,
+                b"passforge.derive.v1\0\0\0\x02ab\0\0\0\x01c\0\0\x01\x02".to_vec()
+            );
+        }
+
+        #[test]
+        fn test_seed_vector() {
+            let config = cheap("example.com", "alice", 1, password(18, true, true, true));
+            let seed = DerivedGenerator::derive_seed(&config).unwrap();
+            assert_eq!(
+                TokenEncoding::Hex.encode(&seed),
+                "499e423d663db8c8585be46f6499703223d71e470258b2a99ad3274c2b37997e"
+            );
+        }
+
+        #[test]
+        fn test_keystream_vector() {
+            // The ChaCha20 keystream for an all-zero key and nonce, from RFC 7539 section A.1.
+            let mut rng = ChaCha20Rng::from_seed([0; 32]);
+            let words: Vec<u32> = (0..4).map(|_| rng.next_u32()).collect();
+            assert_eq!(words, [0xade0b876, 0x903df1a0, 0xe56a5d40, 0x28bd8653]);
+        }
+
+        #[test]
+        fn test_draw_rejects_the_biased_range() {
+            // 2^32 mod 10 = 6, so the six largest words are discarded.
+            let mut words = Words(vec![u32::MAX, u32::MAX - 5, u32::MAX - 6, 17]);
+            assert_eq!(DerivedGenerator::draw(&mut words, 10), 9);
+            assert_eq!(DerivedGenerator::draw(&mut words, 10), 7);
+            assert_eq!(DerivedGenerator::draw(&mut Words(vec![5]), 1), 0);
+        }
+
+        #[test]
+        fn test_render_steps() {
+            // Lowercase and numbers: one of each, then two from both, then three swaps.
+            let mut words = Words(vec![0, 9, 26, 35, 0, 0, 0]);
+            let classes = DerivedGenerator::password_classes(false, true, false);
+            let password = DerivedGenerator::render_password(&mut words, 4, &classes);
+            assert_eq!(password.expose_secret(), "909a");
+
+            assert_eq!(
+                LoadedWordList::load(&WordList::Default).unwrap().len(),
+                7776
+            );
+            let mut words = Words(vec![0, 7775]);
+            let passphrase = DerivedGenerator::render_passphrase(&mut words, 2, " ").unwrap();
+            assert_eq!(passphrase.expose_secret(), "abacus zoom");
+        }
+
+        #[test]
+        fn test_password_vectors() {
+            let template = || password(18, true, true, true);
+            let alice = |template| cheap("example.com", "alice", 1, template);
+            assert_eq!(
+                derive(&cheap("example.com", "alice", 1, template())),
+                "RI.QU9uVSDPJ*c(Y1M"
+            );
+            assert_eq!(
+                derive(&cheap("example.com", "alice", 2, template())),
+                "j>YjU7U|.E7t9d>F6A"
+            );
+            assert_eq!(
+                derive(&cheap("example.org", "alice", 1, template())),
+                "DWUdky7c+0!$sS:uJp"
+            );
+            assert_eq!(
+                derive(&cheap("example.com", "bob", 1, template())),
+                "X03H|LY>)5*>4f+lY8"
+            );
+            assert_eq!(
+                derive(&alice(password(12, true, true, false))),
+                "dE6UubFfY1kw"
+            );
+            assert_eq!(derive(&alice(password(4, false, false, false))), "uuij");
+        }
+
+        #[test]
+        fn test_password_has_every_class() {
+            for counter in 1..=20 {
+                let config = cheap(
+                    "example.com",
+                    "alice",
+                    counter,
+                    password(4, true, true, true),
+                );
+                let password = derive(&config);
+                assert!(password.chars().any(|c| LOWERCASE.contains(c)));
+                assert!(password.chars().any(|c| UPPERCASE.contains(c)));
+                assert!(password.chars().any(|c| NUMBERS.contains(c)));
+                assert!(password.chars().any(|c| SYMBOLS.contains(c)));
+            }
+        }
+
+        #[test]
+        fn test_classes_vectors() {
+            let alice = |template| cheap("example.com", "alice", 1, template);
+            let every_class = PasswordConfig::builder()
+                .min_lowercase(1)
+                .min_capitals(1)
+                .min_numbers(1)
+                .min_symbols(1)
+                .build();
+            let template = DerivedTemplate::from_password_config(&every_class).unwrap();
+            assert_eq!(derive(&alice(template)), "RI.QU9uVSDPJ*c(Y1M");
+
+            let restricted = PasswordConfig::builder()
+                .length(Length::Single(16))
+                .exclude_chars("aeiouAEIOU".into())
+                .unambiguous(true)
+                .min_numbers(4)
+                .min_symbols(2)
+                .build();
+            let template = DerivedTemplate::from_password_config(&restricted).unwrap();
+            assert_eq!(derive(&alice(template.clone())), "_^>jTd63]c4Yh7$Y");
+            for counter in 1..=20 {
+                let password = derive(&cheap("example.com", "alice", counter, template.clone()));
+                assert_eq!(password.len(), 16);
+                assert!(!password.chars().any(|c| "aeiouAEIOU".contains(c)));
+                assert!(!password.chars().any(|c| AMBIGUOUS.contains(c)));
+                assert!(password.chars().filter(char::is_ascii_digit).count() >= 4);
+                assert!(password.chars().filter(|c| SYMBOLS.contains(*c)).count() >= 2);
+            }
+        }
+
+        #[test]
+        fn test_passphrase_vector() {
+            let template = DerivedTemplate::Passphrase {
+                words: 5,
+                separator: " ".into(),
+            };
+            assert_eq!(
+                derive(&cheap("example.com", "alice", 1, template)),
+                "hastiness encourage caddie caption outskirts"
+            );
+        }
+
+        #[test]
+        fn test_default_parameters_vector() {
+            let config = DerivedConfig::builder()
+                .master("correct horse battery staple".into())
+                .site("example.com".into())
+                .login("alice".into())
+                .template(password(18, true, true, true))
+                .memory_kib(64 * 1024)
+                .iterations(3)
+                .parallelism(1)
+                .build();
+            assert_eq!(derive(&config), "xBzaFaH;B_w}kS>20.");
+        }
+
+        #[test]
+        fn test_invalid_parameters() {
+            let mut config = cheap("example.com", "alice", 1, password(18, true, true, true));
+            config.memory_kib = 1;
+            assert!(matches!(
+                DerivedGenerator::generate_with_rng(&config, &mut StdRng::seed_from_u64(0)),
+                Err(PassForgeError::InvalidConfig(_))
+            ));
+        }
+    }
+}
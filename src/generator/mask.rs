@@ -0,0 +1,87 @@
+//! This module implements mask based password generation functionality.
+//!
+//! It provides a `MaskGenerator` struct that implements the `Generator` trait,
+//! producing passwords that follow a fixed template such as `?u?l?l?l-?d?d?d?d`.
+
+use alloc::vec::Vec;
+
+use rand::{CryptoRng, Rng, RngCore};
+
+use crate::config::{MaskConfig, MaskToken};
+use crate::generator::Generator;
+use crate::{PassForgeError, SecretString};
+
+/// Struct for generating passwords from a mask.
+pub struct MaskGenerator;
+
+impl Generator for MaskGenerator {
+    type Config = MaskConfig;
+    type Output = SecretString;
+
+    /// Generates a single password following the mask, drawing randomness from the supplied
+    /// random number generator.
+    ///
+    /// Every placeholder is filled with a character drawn uniformly from its set, while
+    /// literals are copied as-is.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the `MaskConfig` specifying the mask.
+    /// * `rng` - The cryptographically secure random number generator to draw from.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the generated password as a `SecretString` if successful,
+    /// or a `PassForgeError` if an error occurred during generation.
+    fn generate_with_rng<R: RngCore + CryptoRng + ?Sized>(
+        config: &Self::Config,
+        rng: &mut R,
+    ) -> Result<Self::Output, PassForgeError> {
+        let chars: Vec<char> = config
+            .tokens()
+            .iter()
+            .map(|token| match token {
+                MaskToken::Literal(c) => *c,
+                MaskToken::Class(chars) => chars[rng.gen_range(0..chars.len())],
+            })
+            .collect();
+        Ok(SecretString::from_chars(chars))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    mod mask_generator_tests {
+        use super::*;
+
+        #[test]
+        fn test_mask_generation() {
+            let config = MaskConfig::new("?u?l?l?l-?d?d?d?d").unwrap();
+            let password = MaskGenerator::generate(&config).unwrap();
+            let chars: Vec<char> = password.expose_secret().chars().collect();
+            assert_eq!(chars.len(), 9);
+            assert!(chars[0].is_ascii_uppercase());
+            assert!(chars[1..4].iter().all(char::is_ascii_lowercase));
+            assert_eq!(chars[4], '-');
+            assert!(chars[5..].iter().all(char::is_ascii_digit));
+        }
+
+        #[test]
+        fn test_generate_multiple_masks() {
+            let config = MaskConfig::builder()
+                .mask("pf_?1?1?1?1".into())
+                .custom_charset(1, "?H".into())
+                .build()
+                .unwrap();
+            let passwords = MaskGenerator::generate_multiple(&config, 5).unwrap();
+            assert_eq!(passwords.len(), 5);
+            for password in passwords {
+                let password = password.expose_secret();
+                assert!(password.starts_with("pf_"));
+                assert!(password[3..].chars().all(|c| c.is_ascii_hexdigit()));
+            }
+        }
+    }
+}
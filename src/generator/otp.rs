@@ -0,0 +1,340 @@
+//! This module implements one-time password (OTP) secret generation and code computation.
+//!
+//! It provides an `OtpGenerator` struct that implements the `Generator` trait, producing base32
+//! secrets for HOTP (RFC 4226) and TOTP (RFC 6238), along with functions that compute the
+//! codes for a secret and build the `otpauth://` URI authenticator apps are provisioned with.
+
+use alloc::{format, string::String, vec, vec::Vec};
+
+use hmac::{Hmac, Mac};
+use rand::{CryptoRng, RngCore};
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use zeroize::Zeroize;
+
+use crate::config::{OtpAlgorithm, OtpConfig, TokenEncoding};
+use crate::generator::Generator;
+use crate::{PassForgeError, SecretString};
+
+/// Struct for generating OTP secrets based on specified configurations.
+pub struct OtpGenerator;
+
+impl Generator for OtpGenerator {
+    type Config = OtpConfig;
+    type Output = SecretString;
+
+    /// Generates a single OTP secret based on the provided configuration, drawing the random
+    /// bytes from the supplied random number generator.
+    ///
+    /// The secret is written in unpadded RFC 4648 base32, and the raw bytes are wiped once
+    /// encoded.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the `OtpConfig` specifying generation parameters.
+    /// * `rng` - The cryptographically secure random number generator to draw from.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the generated secret as a `SecretString` if successful,
+    /// or a `PassForgeError` if an error occurred during generation.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the secret size is not between `OtpConfig::MIN_SECRET_BYTES`
+    /// and `OtpConfig::MAX_SECRET_BYTES`, or if the code parameters are invalid.
+    fn generate_with_rng<R: RngCore + CryptoRng + ?Sized>(
+        config: &Self::Config,
+        rng: &mut R,
+    ) -> Result<Self::Output, PassForgeError> {
+        config.check()?;
+        config.check_secret_bytes()?;
+        let mut bytes = vec![0u8; config.secret_bytes];
+        rng.try_fill_bytes(&mut bytes)
+            .map_err(|_| PassForgeError::RandomError)?;
+        let secret = TokenEncoding::Base32.encode(&bytes);
+        bytes.zeroize();
+        Ok(SecretString::new(secret))
+    }
+}
+
+/// Computes the HOTP code for a base32 secret and counter, as described in RFC 4226.
+///
+/// # Arguments
+///
+/// * `config` - A reference to the `OtpConfig` giving the digits and algorithm.
+/// * `secret` - The shared secret in base32. Case, spaces, dashes and `=` padding are ignored.
+/// * `counter` - The moving factor shared with the verifier.
+///
+/// # Returns
+///
+/// Returns the code, padded with leading zeros to the configured number of digits.
+///
+/// # Errors
+///
+/// Will return an error if the secret is not base32 or the configuration is invalid.
+pub fn hotp_code(config: &OtpConfig, secret: &str, counter: u64) -> Result<String, PassForgeError> {
+    config.check()?;
+    let mut key = decode_secret(secret)?;
+    let mut mac = match config.algorithm {
+        OtpAlgorithm::Sha1 => hmac::<Hmac<Sha1>>(&key, counter),
+        OtpAlgorithm::Sha256 => hmac::<Hmac<Sha256>>(&key, counter),
+        OtpAlgorithm::Sha512 => hmac::<Hmac<Sha512>>(&key, counter),
+    };
+    key.zeroize();
+
+    // Dynamic truncation: the low nibble of the last byte picks four bytes of the MAC.
+    let offset = (mac[mac.len() - 1] & 0x0f) as usize;
+    let value = u32::from_be_bytes([
+        mac[offset] & 0x7f,
+        mac[offset + 1],
+        mac[offset + 2],
+        mac[offset + 3],
+    ]);
+    mac.zeroize();
+    Ok(format!(
+        "{:0width$}",
+        value % 10u32.pow(config.digits),
+        width = config.digits as usize
+    ))
+}
+
+/// Computes the TOTP code for a base32 secret at a point in time, as described in RFC 6238.
+///
+/// # Arguments
+///
+/// * `config` - A reference to the `OtpConfig` giving the digits, period and algorithm.
+/// * `secret` - The shared secret in base32. Case, spaces, dashes and `=` padding are ignored.
+/// * `timestamp` - The time in seconds since the Unix epoch.
+///
+/// # Returns
+///
+/// Returns the code for the time step holding `timestamp`.
+///
+/// # Errors
+///
+/// Will return an error if the secret is not base32 or the configuration is invalid.
+pub fn totp_code(
+    config: &OtpConfig,
+    secret: &str,
+    timestamp: u64,
+) -> Result<String, PassForgeError> {
+    config.check()?;
+    hotp_code(config, secret, timestamp / config.period)
+}
+
+/// Builds the `otpauth://totp/` provisioning URI for a secret, as understood by authenticator
+/// apps and usually shown as a QR code.
+///
+/// # Arguments
+///
+/// * `config` - A reference to the `OtpConfig` giving the digits, period and algorithm.
+/// * `secret` - The shared secret in base32.
+/// * `issuer` - The service the account belongs to, or an empty string for none.
+/// * `account` - The account name, such as a user name or email address.
+///
+/// # Returns
+///
+/// Returns the URI as a `SecretString`, since it contains the secret.
+///
+/// # Errors
+///
+/// Will return an error if the secret is not base32, the account is empty, or the
+/// configuration is invalid.
+pub fn otpauth_uri(
+    config: &OtpConfig,
+    secret: &str,
+    issuer: &str,
+    account: &str,
+) -> Result<SecretString, PassForgeError> {
+    config.check()?;
+    if account.is_empty() {
+        return Err(PassForgeError::InvalidConfig(
+            "OTP account name cannot be empty".into(),
+        ));
+    }
+    let mut label = String::new();
+    let mut params = String::new();
+    if !issuer.is_empty() {
+        label = format!("{}:", percent_encode(issuer));
+        params = format!("&issuer={}", percent_encode(issuer));
+    }
+    label.push_str(&percent_encode(account));
+    params.push_str(&format!(
+        "&algorithm={}&digits={}&period={}",
+        config.algorithm.name(),
+        config.digits,
+        config.period
+    ));
+
+    let mut key = decode_secret(secret)?;
+    let mut secret = TokenEncoding::Base32.encode(&key);
+    key.zeroize();
+    // Allocated at its final size, so growing it leaves no partial copies of the secret.
+    let mut uri = String::with_capacity(
+        "otpauth://totp/?secret=".len() + label.len() + secret.len() + params.len(),
+    );
+    uri.push_str("otpauth://totp/");
+    uri.push_str(&label);
+    uri.push_str("?secret=");
+    uri.push_str(&secret);
+    uri.push_str(&params);
+    secret.zeroize();
+    Ok(SecretString::new(uri))
+}
+
+/// Computes the HMAC of the big-endian counter with the given key.
+fn hmac<M: Mac + hmac::digest::KeyInit>(key: &[u8], counter: u64) -> Vec<u8> {
+    let mut mac = <M as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(&counter.to_be_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Decodes a base32 secret, ignoring case, spaces, dashes and `=` padding.
+fn decode_secret(secret: &str) -> Result<Vec<u8>, PassForgeError> {
+    let mut text = String::with_capacity(secret.len());
+    text.extend(
+        secret
+            .trim_end_matches(|c: char| c == '=' || c.is_whitespace())
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '-')
+            .map(|c| c.to_ascii_uppercase()),
+    );
+    let key = TokenEncoding::Base32.decode(&text);
+    text.zeroize();
+    match key {
+        Some(key) if !key.is_empty() => Ok(key),
+        _ => Err(PassForgeError::InvalidConfig(
+            "OTP secret is not valid base32".into(),
+        )),
+    }
+}
+
+/// Percent-encodes text for an `otpauth` URI, leaving only the RFC 3986 unreserved
+/// characters as they are.
+fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod otp_generator_tests {
+        use super::*;
+
+        /// The RFC 6238 seeds: the ASCII digits repeated to the output size of each hash.
+        fn rfc_secret(algorithm: OtpAlgorithm) -> String {
+            let len = match algorithm {
+                OtpAlgorithm::Sha1 => 20,
+                OtpAlgorithm::Sha256 => 32,
+                OtpAlgorithm::Sha512 => 64,
+            };
+            let seed: Vec<u8> = b"1234567890".iter().copied().cycle().take(len).collect();
+            TokenEncoding::Base32.encode(&seed)
+        }
+
+        #[test]
+        fn test_rfc_4226_vectors() {
+            let config = OtpConfig::builder().build();
+            let secret = rfc_secret(OtpAlgorithm::Sha1);
+            let expected = [
+                "755224", "287082", "359152", "969429", "338314", "254676", "287922", "162583",
+                "399871", "520489",
+            ];
+            for (counter, code) in expected.iter().enumerate() {
+                assert_eq!(hotp_code(&config, &secret, counter as u64).unwrap(), *code);
+            }
+        }
+
+        #[test]
+        fn test_rfc_6238_vectors() {
+            let cases: [(u64, [&str; 3]); 6] = [
+                (59, ["94287082", "46119246", "90693936"]),
+                (1111111109, ["07081804", "68084774", "25091201"]),
+                (1111111111, ["14050471", "67062674", "99943326"]),
+                (1234567890, ["89005924", "91819424", "93441116"]),
+                (2000000000, ["69279037", "90698825", "38618901"]),
+                (20000000000, ["65353130", "77737706", "47863826"]),
+            ];
+            for (timestamp, codes) in cases {
+                for (algorithm, code) in OtpAlgorithm::ALL.into_iter().zip(codes) {
+                    let config = OtpConfig::new(8, 30, algorithm);
+                    let secret = rfc_secret(algorithm);
+                    assert_eq!(totp_code(&config, &secret, timestamp).unwrap(), code);
+                }
+            }
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_secret_generation() {
+            let config = OtpConfig::builder().build();
+            let secret = OtpGenerator::generate(&config).unwrap();
+            let secret = secret.expose_secret();
+            assert_eq!(secret.len(), 32);
+            assert!(secret
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || ('2'..='7').contains(&c)));
+            assert!(totp_code(&config, secret, 0).is_ok());
+
+            let short = OtpConfig::builder().secret_bytes(10).build();
+            assert!(OtpGenerator::generate(&short).is_err());
+        }
+
+        #[test]
+        fn test_secret_formatting_is_ignored() {
+            let config = OtpConfig::builder().build();
+            let secret = rfc_secret(OtpAlgorithm::Sha1);
+            let spaced = secret
+                .to_lowercase()
+                .as_bytes()
+                .chunks(4)
+                .map(|chunk| core::str::from_utf8(chunk).unwrap())
+                .collect::<Vec<_>>()
+                .join(" ");
+            assert_eq!(
+                hotp_code(&config, &spaced, 1).unwrap(),
+                hotp_code(&config, &secret, 1).unwrap()
+            );
+            assert!(hotp_code(&config, "not base32!", 1).is_err());
+            assert!(hotp_code(&config, "", 1).is_err());
+        }
+
+        #[test]
+        fn test_otpauth_uri() {
+            let config = OtpConfig::new(8, 60, OtpAlgorithm::Sha256);
+            let uri = otpauth_uri(
+                &config,
+                "jbsw y3dp ehpk 3pxp",
+                "ACME Co",
+                "alice@example.com",
+            )
+            .unwrap();
+            assert_eq!(
+                uri.expose_secret(),
+                "otpauth://totp/ACME%20Co:alice%40example.com?secret=JBSWY3DPEHPK3PXP\
+                 &issuer=ACME%20Co&algorithm=SHA256&digits=8&period=60"
+            );
+
+            let uri = otpauth_uri(&config, "JBSWY3DPEHPK3PXP", "", "svc-backup").unwrap();
+            assert!(uri
+                .expose_secret()
+                .starts_with("otpauth://totp/svc-backup?secret="));
+            assert!(otpauth_uri(&config, "JBSWY3DPEHPK3PXP", "ACME", "").is_err());
+        }
+
+        #[test]
+        fn test_percent_encode() {
+            assert_eq!(percent_encode("a:b/é~"), "a%3Ab%2F%C3%A9~");
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! This module implements parallel generation of many items, behind the `parallel` feature.
+//!
+//! The items are split into fixed-size chunks, and every chunk draws from its own random number
+//! generator, seeded in order from the caller's generator. Which thread generates a chunk does
+//! not affect its items, so a seeded caller gets the same output for any number of threads.
+
+use rand::rngs::StdRng;
+use rand::{CryptoRng, RngCore, SeedableRng};
+use rayon::prelude::*;
+
+use crate::generator::{Generator, PassphraseGenerator, PasswordGenerator};
+use crate::PassForgeError;
+
+/// The number of items generated from each chunk's random number generator.
+const CHUNK_SIZE: usize = 1024;
+
+/// Extends a `Generator` with bulk generation spread over the threads of the current
+/// rayon thread pool.
+pub trait ParallelGenerator: Generator {
+    /// Generates multiple items in parallel, seeding a random number generator per chunk of
+    /// items from the supplied one. The result only depends on the supplied generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the configuration specifying generation parameters.
+    /// * `amount` - The number of items to generate.
+    /// * `rng` - The cryptographically secure random number generator to seed the chunks from.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing a vector of generated items if successful, or a `PassForgeError` if an error occurred.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the specified amount is less than or equal to 1,
+    /// or if the configuration is invalid.
+    fn generate_multiple_par_with_rng<R: RngCore + CryptoRng + ?Sized>(
+        config: &Self::Config,
+        amount: usize,
+        rng: &mut R,
+    ) -> Result<Vec<Self::Output>, PassForgeError>
+    where
+        Self::Config: Sync,
+        Self::Output: Send,
+    {
+        if amount <= 1 {
+            return Err(PassForgeError::InvalidGenAmount(
+                "Amount cannot be smaller than 1".into(),
+            ));
+        }
+        let seeds: Vec<<StdRng as SeedableRng>::Seed> = (0..amount.div_ceil(CHUNK_SIZE))
+            .map(|_| {
+                let mut seed = <StdRng as SeedableRng>::Seed::default();
+                rng.fill_bytes(&mut seed);
+                seed
+            })
+            .collect();
+        let chunks = seeds
+            .into_par_iter()
+            .enumerate()
+            .map(|(chunk, seed)| {
+                let mut rng = StdRng::from_seed(seed);
+                let size = CHUNK_SIZE.min(amount - chunk * CHUNK_SIZE);
+                let mut generate = Self::prepare(config)?;
+                (0..size).map(|_| generate(&mut rng)).collect()
+            })
+            .collect::<Result<Vec<Vec<Self::Output>>, PassForgeError>>()?;
+        Ok(chunks.into_iter().flatten().collect())
+    }
+
+    /// Generates multiple items in parallel, seeding the chunks from the thread-local
+    /// cryptographically secure RNG.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the configuration specifying generation parameters.
+    /// * `amount` - The number of items to generate.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing a vector of generated items if successful, or a `PassForgeError` if an error occurred.
+    fn generate_multiple_par(
+        config: &Self::Config,
+        amount: usize,
+    ) -> Result<Vec<Self::Output>, PassForgeError>
+    where
+        Self::Config: Sync,
+        Self::Output: Send,
+    {
+        Self::generate_multiple_par_with_rng(config, amount, &mut rand::thread_rng())
+    }
+}
+
+impl ParallelGenerator for PasswordGenerator {}
+
+impl ParallelGenerator for PassphraseGenerator {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod parallel_generator_tests {
+        use crate::{Length, PassphraseConfig, PasswordConfig, WordList};
+
+        use super::*;
+
+        #[test]
+        fn test_parallel_generation_is_deterministic() {
+            let config = PasswordConfig::new(Length::Single(12), true, true, true);
+            let amount = 2 * CHUNK_SIZE + 7;
+            let single = rayon::ThreadPoolBuilder::new()
+                .num_threads(1)
+                .build()
+                .unwrap()
+                .install(|| {
+                    PasswordGenerator::generate_multiple_par_with_rng(
+                        &config,
+                        amount,
+                        &mut StdRng::seed_from_u64(5),
+                    )
+                })
+                .unwrap();
+            let many = rayon::ThreadPoolBuilder::new()
+                .num_threads(4)
+                .build()
+                .unwrap()
+                .install(|| {
+                    PasswordGenerator::generate_multiple_par_with_rng(
+                        &config,
+                        amount,
+                        &mut StdRng::seed_from_u64(5),
+                    )
+                })
+                .unwrap();
+            assert_eq!(single.len(), amount);
+            assert!(single
+                .iter()
+                .zip(&many)
+                .all(|(a, b)| a.expose_secret() == b.expose_secret()));
+        }
+
+        #[test]
+        fn test_parallel_passphrases() {
+            let config = PassphraseConfig::new(4, "-".to_string(), WordList::Default);
+            let passphrases = PassphraseGenerator::generate_multiple_par(&config, 100).unwrap();
+            assert_eq!(passphrases.len(), 100);
+            assert!(PassphraseGenerator::generate_multiple_par(&config, 1).is_err());
+        }
+    }
+}
@@ -3,14 +3,29 @@
 //! It provides a `PassphraseGenerator` struct that implements the `Generator` trait,
 //! allowing for flexible and customizable passphrase generation using word lists.
 
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    format,
+    string::{FromUtf8Error, String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::mem;
 
-use crate::config::{PassphraseConfig, WordList};
-use crate::generator::Generator;
-use crate::PassForgeError;
-use rand::seq::SliceRandom;
-use rand::thread_rng;
+use spin::Once;
+
+use crate::config::pronounceable::log2_binomial;
+use crate::config::{
+    Capitalization, Insertion, LoadedWordList, PassphraseConfig, Sampling, WordList,
+};
+use crate::generator::{Generator, Prepared};
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::{PassForgeError, SecretString};
+use rand::seq::{index, SliceRandom};
+use rand::{CryptoRng, Rng, RngCore};
+use zeroize::Zeroize;
 
 /// The default word list used for passphrase generation.
 const DEFAULT_WORD_LIST: &str = include_str!("../../resources/eff_large_wordlist.txt");
@@ -18,35 +33,340 @@ const DEFAULT_WORD_LIST: &str = include_str!("../../resources/eff_large_wordlist
 /// Struct for generating passphrases based on specified configurations.
 pub struct PassphraseGenerator;
 
+/// The sizes that determine how many passphrases a configuration can produce.
+struct Shape {
+    /// The number of words.
+    words: usize,
+    /// The number of inserted digits and symbols.
+    extras: usize,
+    /// The number of separators between the words and tokens.
+    gaps: usize,
+    /// The number of characters in each separator.
+    separator_length: usize,
+    separator_choices: usize,
+    symbol_choices: usize,
+}
+
+impl Shape {
+    fn new(config: &PassphraseConfig) -> Self {
+        let words = config.words;
+        let extras = config.digits + config.symbols;
+        let gaps = match config.insertion {
+            Insertion::Tokens => words + extras - 1,
+            Insertion::Anywhere => words - 1,
+        };
+        let (separator_length, separator_choices) = match &config.separators {
+            Some(separators) => (1, unique_chars(separators).len()),
+            None => (config.separator.chars().count(), 1),
+        };
+        Self {
+            words,
+            extras,
+            gaps,
+            separator_length,
+            separator_choices,
+            symbol_choices: unique_chars(&config.symbol_set).len(),
+        }
+    }
+
+    /// Returns the length of the passphrase after insertion, given the total length of the
+    /// words.
+    fn length(&self, word_chars: usize) -> usize {
+        word_chars + self.gaps * self.separator_length + self.extras
+    }
+}
+
 impl PassphraseGenerator {
-    /// Creates a passphrase from the given word list and configuration.
+    /// Creates a passphrase from the given word list and configuration. The chosen words and
+    /// every buffer the passphrase is assembled in are wiped before returning.
     ///
     /// # Arguments
     ///
     /// * `word_list` - A vector of words to choose from.
-    /// * `words` - The number of words to include in the passphrase.
-    /// * `separator` - The string used to separate words in the passphrase.
+    /// * `config` - A reference to the `PassphraseConfig` specifying the words and styling.
+    /// * `rng` - The random number generator used to pick the words.
     ///
     /// # Returns
     ///
-    /// Returns a `Result` containing the generated passphrase as a `String` if successful,
+    /// Returns a `Result` containing the generated passphrase as a `SecretString` if successful,
     /// or a `PassForgeError` if an error occurred during generation.
-    fn create_passphrase(
-        word_list: &Vec<String>,
-        words: usize,
-        separator: &String,
-    ) -> Result<String, PassForgeError> {
-        let mut rng = thread_rng();
+    fn create_passphrase<R: RngCore + CryptoRng + ?Sized>(
+        word_list: &[String],
+        config: &PassphraseConfig,
+        rng: &mut R,
+    ) -> Result<SecretString, PassForgeError> {
+        let mut words: Vec<String> = match config.sampling {
+            Sampling::WithoutReplacement => word_list
+                .choose_multiple(rng, config.words)
+                .cloned()
+                .collect(),
+            Sampling::WithReplacement => (0..config.words)
+                .filter_map(|_| word_list.choose(rng).cloned())
+                .collect(),
+        };
+        match config.capitalization {
+            Capitalization::RandomWord => {
+                if let Some(word) = words.choose_mut(rng) {
+                    let upper = word.to_uppercase();
+                    mem::replace(word, upper).zeroize();
+                }
+            }
+            capitalization => {
+                for word in &mut words {
+                    let capitalized = Self::capitalize(word, capitalization);
+                    mem::replace(word, capitalized).zeroize();
+                }
+            }
+        }
 
-        let passphrase_words: Vec<&str> = word_list
-            .choose_multiple(&mut rng, words)
-            .map(String::as_str)
-            .collect();
+        let symbols = unique_chars(&config.symbol_set);
+        let mut extras: Vec<char> = Vec::with_capacity(config.digits + config.symbols);
+        for _ in 0..config.digits {
+            extras.push(char::from(b'0' + rng.gen_range(0..10)));
+        }
+        for _ in 0..config.symbols {
+            extras.push(symbols[rng.gen_range(0..symbols.len())]);
+        }
+        extras.shuffle(rng);
+
+        let passphrase = match config.insertion {
+            Insertion::Tokens => {
+                let mut extras: Vec<String> = extras.drain(..).map(String::from).collect();
+                let mut tokens = Self::interleave(&words, &extras, rng);
+                let passphrase = SecretString::new(Self::join(&tokens, config, rng));
+                extras.zeroize();
+                tokens.zeroize();
+                passphrase
+            }
+            Insertion::Anywhere => {
+                let mut joined = Self::join(&words, config, rng);
+                let mut base: Vec<char> = Vec::with_capacity(joined.len());
+                base.extend(joined.chars());
+                joined.zeroize();
+                let passphrase = SecretString::from_chars(Self::interleave(&base, &extras, rng));
+                base.zeroize();
+                passphrase
+            }
+        };
+        words.zeroize();
+        extras.zeroize();
+        Ok(passphrase)
+    }
 
-        Ok(passphrase_words.join(separator))
+    /// Applies a capitalization that does not involve randomness to a single word.
+    fn capitalize(word: &str, capitalization: Capitalization) -> String {
+        match capitalization {
+            Capitalization::FirstLetter => {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            }
+            Capitalization::All => word.to_uppercase(),
+            Capitalization::None | Capitalization::RandomWord => word.to_string(),
+        }
+    }
+
+    /// Places the extra items at uniformly random positions among the base items, keeping the
+    /// order of both. The result is allocated at its final size, so the items are copied once.
+    fn interleave<T: Clone, R: RngCore + CryptoRng + ?Sized>(
+        base: &[T],
+        extras: &[T],
+        rng: &mut R,
+    ) -> Vec<T> {
+        let total = base.len() + extras.len();
+        let mut positions = index::sample(rng, total, extras.len()).into_vec();
+        positions.sort_unstable();
+        let mut positions = positions.into_iter().peekable();
+        let mut base = base.iter();
+        let mut extras = extras.iter();
+        let mut interleaved = Vec::with_capacity(total);
+        for i in 0..total {
+            let item = if positions.next_if_eq(&i).is_some() {
+                extras.next()
+            } else {
+                base.next()
+            };
+            interleaved.extend(item.cloned());
+        }
+        interleaved
+    }
+
+    /// Joins the tokens with the fixed separator, or with a random separator per gap.
+    fn join<R: RngCore + CryptoRng + ?Sized>(
+        tokens: &[String],
+        config: &PassphraseConfig,
+        rng: &mut R,
+    ) -> String {
+        let Some(separators) = &config.separators else {
+            return tokens.join(&config.separator);
+        };
+        let separators = unique_chars(separators);
+        let longest_separator = separators.iter().map(|c| c.len_utf8()).max().unwrap_or(0);
+        let mut passphrase = String::with_capacity(
+            tokens.iter().map(String::len).sum::<usize>() + tokens.len() * longest_separator,
+        );
+        for (i, token) in tokens.iter().enumerate() {
+            if i > 0 {
+                passphrase.push(separators[rng.gen_range(0..separators.len())]);
+            }
+            passphrase.push_str(token);
+        }
+        passphrase
+    }
+
+    /// Checks that the configuration can be applied to the word list, and that every
+    /// inserted character can be told apart from the words and separators. The latter keeps
+    /// distinct choices from producing the same passphrase, so the entropy is exact.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the number of words is less than or equal to 1, or more than
+    /// the word list holds when sampling without replacement, if a character set is empty,
+    /// or if an inserted character can appear in the words or separators.
+    fn check_config(config: &PassphraseConfig, word_list: &[String]) -> Result<(), PassForgeError> {
+        if config.words <= 1 {
+            return Err(PassForgeError::InvalidWordCount(
+                "Amount of words cannot be smaller than 1".into(),
+            ));
+        }
+        if config.sampling == Sampling::WithoutReplacement && config.words > word_list.len() {
+            return Err(PassForgeError::InvalidWordCount(format!(
+                "Cannot pick {} distinct words from a list of {} words, \
+                 sample with replacement to allow repeated words",
+                config.words,
+                word_list.len()
+            )));
+        }
+        if config.separators.as_ref().is_some_and(String::is_empty) {
+            return Err(PassForgeError::InvalidConfig(
+                "Separator set cannot be empty".into(),
+            ));
+        }
+        if config.symbols > 0 && config.symbol_set.is_empty() {
+            return Err(PassForgeError::InvalidConfig(
+                "Symbol set cannot be empty when inserting symbols".into(),
+            ));
+        }
+        let mut inserted = String::new();
+        if config.digits > 0 {
+            inserted.push_str("0123456789");
+        }
+        if config.symbols > 0 {
+            inserted.push_str(&config.symbol_set);
+        }
+        let separators = config.separators.as_ref().unwrap_or(&config.separator);
+        let clash = inserted.chars().find(|c| {
+            separators.contains(*c)
+                || word_list
+                    .iter()
+                    .any(|word| word.contains(*c) || word.to_uppercase().contains(*c))
+        });
+        if let Some(c) = clash {
+            return Err(PassForgeError::InvalidConfig(format!(
+                "Inserted character {:?} also appears in the words or separators",
+                c
+            )));
+        }
+        Ok(())
+    }
+
+    /// Computes the entropy of the passphrases described by the configuration in bits.
+    pub(crate) fn entropy_bits(config: &PassphraseConfig) -> Result<f64, PassForgeError> {
+        let words = Self::get_word_list(&config.word_list)?;
+        let word_list = words.words();
+        Self::check_config(config, word_list)?;
+        let shape = Shape::new(config);
+
+        let mut bits: f64 = match config.sampling {
+            Sampling::WithoutReplacement => (0..shape.words)
+                .map(|i| ((word_list.len() - i) as f64).log2())
+                .sum(),
+            Sampling::WithReplacement => shape.words as f64 * (word_list.len() as f64).log2(),
+        };
+        if config.capitalization == Capitalization::RandomWord {
+            bits += (shape.words as f64).log2();
+        }
+        bits += shape.gaps as f64 * (shape.separator_choices as f64).log2();
+        bits += log2_binomial(shape.extras, config.digits)
+            + config.digits as f64 * 10f64.log2()
+            + config.symbols as f64 * (shape.symbol_choices as f64).log2();
+        bits += match config.insertion {
+            Insertion::Tokens => log2_binomial(shape.words + shape.extras, shape.extras),
+            Insertion::Anywhere if shape.extras == 0 => 0.0,
+            Insertion::Anywhere => {
+                // The number of positions depends on the length of the chosen words, so the
+                // average is taken over the distribution of their total length.
+                let lengths: Vec<usize> = word_list.iter().map(|w| w.chars().count()).collect();
+                length_distribution(&lengths, shape.words, config.sampling)
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, probability)| **probability > 0.0)
+                    .map(|(total, probability)| {
+                        probability * log2_binomial(shape.length(total), shape.extras)
+                    })
+                    .sum()
+            }
+        };
+        Ok(bits)
+    }
+
+    /// Computes the number of distinct passphrases described by the configuration,
+    /// or `None` if it does not fit in a `u128`.
+    pub(crate) fn keyspace(config: &PassphraseConfig) -> Result<Option<u128>, PassForgeError> {
+        let words = Self::get_word_list(&config.word_list)?;
+        let word_list = words.words();
+        Self::check_config(config, word_list)?;
+        let shape = Shape::new(config);
+
+        let capitals = match config.capitalization {
+            Capitalization::RandomWord => shape.words as u128,
+            _ => 1,
+        };
+        let styling = [
+            Some(capitals),
+            (shape.separator_choices as u128).checked_pow(shape.gaps as u32),
+            binomial(shape.extras, config.digits),
+            10u128.checked_pow(config.digits as u32),
+            (shape.symbol_choices as u128).checked_pow(config.symbols as u32),
+        ]
+        .into_iter()
+        .try_fold(1u128, |acc, factor| factor.and_then(|f| acc.checked_mul(f)));
+
+        let arrangements = match config.insertion {
+            Insertion::Tokens => match config.sampling {
+                Sampling::WithoutReplacement => (0..shape.words).try_fold(1u128, |acc, i| {
+                    acc.checked_mul((word_list.len() - i) as u128)
+                }),
+                Sampling::WithReplacement => {
+                    (word_list.len() as u128).checked_pow(shape.words as u32)
+                }
+            }
+            .zip(binomial(shape.words + shape.extras, shape.extras))
+            .and_then(|(words, positions)| words.checked_mul(positions)),
+            Insertion::Anywhere => {
+                let lengths: Vec<usize> = word_list.iter().map(|w| w.chars().count()).collect();
+                length_counts(&lengths, shape.words, config.sampling).and_then(|counts| {
+                    counts
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, count)| **count > 0)
+                        .try_fold(0u128, |acc, (total, count)| {
+                            binomial(shape.length(total), shape.extras)
+                                .and_then(|positions| positions.checked_mul(*count))
+                                .and_then(|ways| acc.checked_add(ways))
+                        })
+                })
+            }
+        };
+        Ok(styling
+            .zip(arrangements)
+            .and_then(|(styling, arrangements)| styling.checked_mul(arrangements)))
     }
 
     /// Loads and processes the word list based on the specified `WordList` type.
+    /// The bundled lists are parsed on first use and shared afterwards.
     ///
     /// # Arguments
     ///
@@ -54,26 +374,154 @@ impl PassphraseGenerator {
     ///
     /// # Returns
     ///
-    /// Returns a `Result` containing a vector of words if successful,
+    /// Returns a `Result` containing the loaded words if successful,
     /// or a `PassForgeError` if an error occurred during loading or processing.
-    fn get_word_list(word_list: &WordList) -> Result<Vec<String>, PassForgeError> {
-        let words: Vec<String> = PassphraseGenerator::load_file(word_list)?
+    pub(crate) fn get_word_list(word_list: &WordList) -> Result<LoadedWordList, PassForgeError> {
+        if let Some((text, cache)) = Self::bundled(word_list) {
+            if let Some(words) = cache.get() {
+                return Ok(words.clone());
+            }
+            let words = LoadedWordList::parse(text.lines())?;
+            return Ok(cache.call_once(|| words).clone());
+        }
+        match word_list {
+            WordList::Loaded(words) => Ok(words.clone()),
+            _ => LoadedWordList::parse(Self::load_file(word_list)?.into_iter().flatten()),
+        }
+    }
+
+    /// Returns the text of a bundled word list together with the cache of its parsed words,
+    /// or `None` for word lists that are not bundled.
+    fn bundled(word_list: &WordList) -> Option<(&'static str, &'static Once<LoadedWordList>)> {
+        macro_rules! bundled {
+            ($text:expr) => {{
+                static CACHE: Once<LoadedWordList> = Once::new();
+                Some(($text, &CACHE))
+            }};
+        }
+        match word_list {
+            WordList::Default => bundled!(DEFAULT_WORD_LIST),
+            #[cfg(feature = "std")]
+            WordList::Custom(_) => None,
+            WordList::Loaded(_) => None,
+        }
+    }
+
+    /// Loads the dice roll keys of a diceware word list, such as the default EFF list.
+    ///
+    /// # Arguments
+    ///
+    /// * `word_list` - A reference to the `WordList` enum specifying the source of words.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the words by roll and the number of dice per roll
+    /// if successful, or a `PassForgeError` if the list has no consistent roll keys.
+    fn get_dice_word_list(
+        word_list: &WordList,
+    ) -> Result<(BTreeMap<String, String>, usize), PassForgeError> {
+        let mut words = BTreeMap::new();
+        let mut dice = None;
+        for line in PassphraseGenerator::load_file(word_list)?
             .into_iter()
-            .filter_map(|line| {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                match parts.len() {
-                    1 => Some(parts[0].to_string()),
-                    2 => parts[1].parse().ok(),
-                    _ => None, // Invalid format, skip this line
-                }
-            })
-            .collect();
-        if words.is_empty() {
-            return Err(PassForgeError::WordListError(
-                "Word list is empty or invalid".into(),
+            .flatten()
+        {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let [roll, word] = parts[..] else {
+                continue;
+            };
+            if !Self::is_dice_roll(roll) {
+                continue;
+            }
+            if *dice.get_or_insert(roll.len()) != roll.len() {
+                return Err(PassForgeError::WordListError(
+                    "Word list mixes dice rolls of different lengths".into(),
+                ));
+            }
+            words.insert(roll.to_string(), word.to_string());
+        }
+        match dice {
+            Some(dice) => Ok((words, dice)),
+            None => Err(PassForgeError::WordListError(
+                "Word list has no dice roll keys".into(),
+            )),
+        }
+    }
+
+    /// Returns `true` if the value consists only of die faces 1 to 6.
+    fn is_dice_roll(value: &str) -> bool {
+        !value.is_empty() && value.chars().all(|c| ('1'..='6').contains(&c))
+    }
+
+    /// Creates a passphrase from physical dice rolls instead of software randomness.
+    ///
+    /// Each roll is looked up in the roll column of the configured diceware word list, so the
+    /// passphrase is exactly as random as the dice. The default list needs five dice per word.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the `PassphraseConfig` specifying the word count,
+    ///   separator and word list.
+    /// * `rolls` - One roll per word, written as the faces in order, e.g. `"16655"`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the passphrase as a `SecretString` if successful,
+    /// or a `PassForgeError` if the rolls or the word list are invalid.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the number of words is less than or equal to 1, if the number
+    /// of rolls does not match it, if a roll has the wrong number of dice or a face outside 1
+    /// to 6, or if the word list has no word for a roll.
+    pub fn from_dice_rolls<S: AsRef<str>>(
+        config: &PassphraseConfig,
+        rolls: &[S],
+    ) -> Result<SecretString, PassForgeError> {
+        if config.words <= 1 {
+            return Err(PassForgeError::InvalidWordCount(
+                "Amount of words cannot be smaller than 1".into(),
             ));
         }
-        Ok(words)
+        if rolls.len() != config.words {
+            return Err(PassForgeError::InvalidDiceRoll(format!(
+                "Expected {} rolls, one per word, but got {}",
+                config.words,
+                rolls.len()
+            )));
+        }
+        if config.capitalization == Capitalization::RandomWord
+            || config.digits > 0
+            || config.symbols > 0
+            || config.separators.is_some()
+        {
+            return Err(PassForgeError::InvalidConfig(
+                "Dice rolls cannot pick a random capitalized word, insertions or separators".into(),
+            ));
+        }
+        let (word_list, dice) = Self::get_dice_word_list(&config.word_list)?;
+        let mut words = rolls
+            .iter()
+            .map(|roll| {
+                let roll = roll.as_ref();
+                if roll.chars().count() != dice || !Self::is_dice_roll(roll) {
+                    return Err(PassForgeError::InvalidDiceRoll(format!(
+                        "\"{}\" is not {} dice with faces 1 to 6",
+                        roll, dice
+                    )));
+                }
+                word_list.get(roll).ok_or_else(|| {
+                    PassForgeError::InvalidDiceRoll(format!(
+                        "No word in the list for roll {}",
+                        roll
+                    ))
+                })
+            })
+            .map(|word| word.map(|word| Self::capitalize(word, config.capitalization)))
+            .collect::<Result<Vec<String>, PassForgeError>>()?;
+        let passphrase = SecretString::new(words.join(&config.separator));
+        words.zeroize();
+        Ok(passphrase)
     }
 
     /// Loads the word list file into memory.
@@ -84,126 +532,523 @@ impl PassphraseGenerator {
     ///
     /// # Returns
     ///
-    /// Returns a `Result` containing a vector of lines from the file if successful,
-    /// or a `PassForgeError` if an error occurred during file reading.
-    fn load_file(word_list: &WordList) -> Result<Vec<String>, PassForgeError> {
-        let line = match word_list {
-            WordList::Default => DEFAULT_WORD_LIST.lines().map(String::from).collect(),
+    /// Returns a `Result` containing the lines of the file if successful, or a
+    /// `PassForgeError` if an error occurred during file reading. Each line is an error of its
+    /// own if it is not valid UTF-8, so the lines after it are still read.
+    pub(crate) fn load_file(
+        word_list: &WordList,
+    ) -> Result<Vec<Result<String, FromUtf8Error>>, PassForgeError> {
+        if let Some((text, _)) = Self::bundled(word_list) {
+            return Ok(text.lines().map(|line| Ok(line.to_string())).collect());
+        }
+        match word_list {
+            #[cfg(feature = "std")]
             WordList::Custom(path) => {
-                let file = File::open(path)?;
-                let reader = BufReader::new(file);
-                reader.lines().map_while(Result::ok).collect()
+                let bytes = std::fs::read(path)?;
+                Ok(bytes
+                    .split(|byte| *byte == b'\n')
+                    .map(|line| {
+                        String::from_utf8(line.strip_suffix(b"\r").unwrap_or(line).to_vec())
+                    })
+                    .collect())
             }
-        };
-        Ok(line)
+            WordList::Loaded(words) => Ok(words.words().iter().cloned().map(Ok).collect()),
+            _ => Ok(Vec::new()),
+        }
     }
 }
 
 impl Generator for PassphraseGenerator {
     type Config = PassphraseConfig;
-    type Output = String;
+    type Output = SecretString;
 
-    /// Generates a single passphrase based on the provided configuration.
+    /// Generates a single passphrase based on the provided configuration, drawing randomness
+    /// from the supplied random number generator.
     ///
     /// # Arguments
     ///
     /// * `config` - A reference to the `PassphraseConfig` specifying generation parameters.
+    /// * `rng` - The cryptographically secure random number generator to draw from.
     ///
     /// # Returns
     ///
-    /// Returns a `Result` containing the generated passphrase as a `String` if successful,
+    /// Returns a `Result` containing the generated passphrase as a `SecretString` if successful,
     /// or a `PassForgeError` if an error occurred during generation.
     ///
     /// # Errors
     ///
     /// Will return an error if the specified number of words is less than or equal to 1.
-    fn generate(config: &Self::Config) -> Result<Self::Output, PassForgeError> {
-        if config.words <= 1 {
-            return Err(PassForgeError::InvalidWordCount(
-                "Amount of words cannot be smaller than 1".into(),
-            ));
-        }
-        let word_list = PassphraseGenerator::get_word_list(&config.word_list)?;
-        PassphraseGenerator::create_passphrase(&word_list, config.words, &config.separator)
+    fn generate_with_rng<R: RngCore + CryptoRng + ?Sized>(
+        config: &Self::Config,
+        rng: &mut R,
+    ) -> Result<Self::Output, PassForgeError> {
+        PassphraseGenerator::prepare(config)?(rng)
     }
 
-    /// Generates multiple passphrases based on the provided configuration.
+    /// Loads the word list once and checks the configuration against it, so that generating
+    /// many passphrases does not load the list again.
     ///
     /// # Arguments
     ///
     /// * `config` - A reference to the `PassphraseConfig` specifying generation parameters.
-    /// * `amount` - The number of passphrases to generate.
     ///
     /// # Returns
     ///
-    /// Returns a `Result` containing a vector of generated passphrases as `String`s if successful,
-    /// or a `PassForgeError` if an error occurred during generation.
+    /// Returns a `Result` containing a function that generates one passphrase per call if
+    /// successful, or a `PassForgeError` if the configuration is invalid.
     ///
     /// # Errors
     ///
-    /// Will return an error if the specified amount is less than or equal to 1,
-    /// or if the specified number of words per passphrase is less than or equal to 1.
-    fn generate_multiple(
-        config: &Self::Config,
-        amount: usize,
-    ) -> Result<Vec<Self::Output>, PassForgeError> {
-        if amount <= 1 {
-            return Err(PassForgeError::InvalidGenAmount(
-                "Amount cannot be smaller than 1".into(),
-            ));
+    /// Will return an error if the word list cannot be loaded, or if the configuration is invalid.
+    fn prepare<'a, R: RngCore + CryptoRng + ?Sized + 'a>(
+        config: &'a Self::Config,
+    ) -> Result<Prepared<'a, R, Self::Output>, PassForgeError> {
+        let words = PassphraseGenerator::get_word_list(&config.word_list)?;
+        PassphraseGenerator::check_config(config, words.words())?;
+        Ok(Box::new(move |rng| {
+            PassphraseGenerator::create_passphrase(words.words(), config, rng)
+        }))
+    }
+}
+
+/// Returns the distinct characters of the string in order of first appearance.
+fn unique_chars(s: &str) -> Vec<char> {
+    let mut chars: Vec<char> = Vec::new();
+    for c in s.chars() {
+        if !chars.contains(&c) {
+            chars.push(c);
         }
-        if config.words <= 1 {
-            return Err(PassForgeError::InvalidWordCount(
-                "Amount of words cannot be smaller than 1".into(),
-            ));
+    }
+    chars
+}
+
+/// Computes the binomial coefficient, or `None` if it does not fit in a `u128`.
+fn binomial(n: usize, k: usize) -> Option<u128> {
+    (0..k).try_fold(1u128, |acc, i| {
+        acc.checked_mul((n - i) as u128)
+            .map(|product| product / (i as u128 + 1))
+    })
+}
+
+/// Computes the probability that `words` words drawn from a list with the given word lengths
+/// have each total length, indexed by that total.
+fn length_distribution(lengths: &[usize], words: usize, sampling: Sampling) -> Vec<f64> {
+    if sampling == Sampling::WithReplacement {
+        let probability = 1.0 / lengths.len() as f64;
+        let mut word = Vec::new();
+        for length in lengths {
+            if word.len() <= *length {
+                word.resize(length + 1, 0.0);
+            }
+            word[*length] += probability;
+        }
+        let mut total = vec![1.0];
+        for _ in 0..words {
+            let mut next = vec![0.0; total.len() + word.len() - 1];
+            for (i, p) in total.iter().enumerate() {
+                for (j, q) in word.iter().enumerate() {
+                    next[i + j] += p * q;
+                }
+            }
+            total = next;
+        }
+        return total;
+    }
+    let longest = words * lengths.iter().max().copied().unwrap_or(0);
+    // `table[c][s]` is the probability that `c` words drawn from the words seen so far have
+    // total length `s`. Adding a word keeps a subset of `c` out of `i + 1` words with
+    // probability `1 - c / (i + 1)`.
+    let mut table = vec![vec![0.0; longest + 1]; words + 1];
+    table[0][0] = 1.0;
+    let mut seen = 0;
+    for (i, length) in lengths.iter().enumerate() {
+        for c in (1..=words.min(i + 1)).rev() {
+            let with = c as f64 / (i + 1) as f64;
+            for s in (0..=longest.min(seen + length)).rev() {
+                let mut probability = (1.0 - with) * table[c][s];
+                if s >= *length {
+                    probability += with * table[c - 1][s - length];
+                }
+                table[c][s] = probability;
+            }
         }
-        let word_list = PassphraseGenerator::get_word_list(&config.word_list)?;
+        seen += length;
+    }
+    table.swap_remove(words)
+}
 
-        (0..amount)
-            .map(|_| {
-                PassphraseGenerator::create_passphrase(&word_list, config.words, &config.separator)
-            })
-            .collect()
+/// Counts the sequences of `words` words from a list with the given word lengths by their
+/// total length, or returns `None` if a count does not fit in a `u128`.
+fn length_counts(lengths: &[usize], words: usize, sampling: Sampling) -> Option<Vec<u128>> {
+    if sampling == Sampling::WithReplacement {
+        let mut word = Vec::new();
+        for length in lengths {
+            if word.len() <= *length {
+                word.resize(length + 1, 0u128);
+            }
+            word[*length] += 1;
+        }
+        let mut total = vec![1u128];
+        for _ in 0..words {
+            let mut next = vec![0u128; total.len() + word.len() - 1];
+            for (i, a) in total.iter().enumerate() {
+                for (j, b) in word.iter().enumerate() {
+                    next[i + j] = next[i + j].checked_add(a.checked_mul(*b)?)?;
+                }
+            }
+            total = next;
+        }
+        return Some(total);
     }
+    // Count the sets of distinct words, each of which can be ordered in `words!` ways.
+    let orderings = (1..=words as u128).try_fold(1u128, |acc, i| acc.checked_mul(i))?;
+    let longest = words * lengths.iter().max().copied().unwrap_or(0);
+    let mut table = vec![vec![0u128; longest + 1]; words + 1];
+    table[0][0] = 1;
+    for length in lengths {
+        for c in (1..=words).rev() {
+            for s in (*length..=longest).rev() {
+                table[c][s] = table[c][s].checked_add(table[c - 1][s - length])?;
+            }
+        }
+    }
+    table
+        .swap_remove(words)
+        .into_iter()
+        .map(|sets| sets.checked_mul(orderings))
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     mod passphrase_generator_tests {
+        use rand::{rngs::StdRng, SeedableRng};
+
         use super::*;
 
+        #[cfg(feature = "std")]
         #[test]
         fn test_passphrase_generation() {
             let config = PassphraseConfig::new(4, "-".to_string(), WordList::Default);
             let passphrase = PassphraseGenerator::generate(&config).unwrap();
+            let passphrase = passphrase.expose_secret();
             let words: Vec<&str> = passphrase.split('-').collect();
             assert_eq!(words.len(), 4);
         }
 
+        #[cfg(feature = "std")]
         #[test]
         fn test_passphrase_generation_custom_separator() {
             let config = PassphraseConfig::new(4, "_".to_string(), WordList::Default);
             let passphrase = PassphraseGenerator::generate(&config).unwrap();
+            let passphrase = passphrase.expose_secret();
             let words: Vec<&str> = passphrase.split('_').collect();
             assert_eq!(words.len(), 4);
         }
 
+        #[cfg(feature = "std")]
         #[test]
         fn test_generate_multiple_passphrases() {
             let config = PassphraseConfig::new(4, "-".to_string(), WordList::Default);
             let passphrases = PassphraseGenerator::generate_multiple(&config, 5).unwrap();
             assert_eq!(passphrases.len(), 5);
             for passphrase in passphrases {
-                let words: Vec<&str> = passphrase.split('-').collect();
+                let words: Vec<&str> = passphrase.expose_secret().split('-').collect();
                 assert_eq!(words.len(), 4);
             }
         }
 
+        #[test]
+        fn test_seeded_generation_is_reproducible() {
+            let config = PassphraseConfig::new(4, "-".to_string(), WordList::Default);
+            let first = PassphraseGenerator::generate_multiple_with_rng(
+                &config,
+                3,
+                &mut StdRng::seed_from_u64(7),
+            )
+            .unwrap();
+            let second = PassphraseGenerator::generate_multiple_with_rng(
+                &config,
+                3,
+                &mut StdRng::seed_from_u64(7),
+            )
+            .unwrap();
+            assert!(first
+                .iter()
+                .zip(&second)
+                .all(|(a, b)| a.expose_secret() == b.expose_secret()));
+        }
+
+        #[test]
+        fn test_passphrase_from_dice_rolls() {
+            let config = PassphraseConfig::new(3, " ".to_string(), WordList::Default);
+            let passphrase =
+                PassphraseGenerator::from_dice_rolls(&config, &["11111", "11112", "66666"])
+                    .unwrap();
+            assert_eq!(passphrase.expose_secret(), "abacus abdomen zoom");
+        }
+
+        #[test]
+        fn test_invalid_dice_rolls() {
+            let config = PassphraseConfig::new(2, "-".to_string(), WordList::Default);
+            for rolls in [
+                vec!["11111"],
+                vec!["11111", "11112", "11113"],
+                vec!["11111", "1111"],
+                vec!["11111", "11117"],
+                vec!["11111", "1111a"],
+            ] {
+                assert!(matches!(
+                    PassphraseGenerator::from_dice_rolls(&config, &rolls),
+                    Err(PassForgeError::InvalidDiceRoll(_))
+                ));
+            }
+        }
+
+        #[test]
+        fn test_word_list_from_name() {
+            assert!(matches!(
+                WordList::from_name("Default"),
+                Ok(WordList::Default)
+            ));
+            assert!(matches!(
+                WordList::from_name("klingon"),
+                Err(PassForgeError::WordListError(_))
+            ));
+            for name in WordList::NAMES {
+                if let Ok(word_list) = WordList::from_name(name) {
+                    let (words, dice) =
+                        PassphraseGenerator::get_dice_word_list(&word_list).unwrap();
+                    assert_eq!(words.len(), 6usize.pow(dice as u32));
+                    assert_eq!(
+                        PassphraseGenerator::get_word_list(&word_list)
+                            .unwrap()
+                            .len(),
+                        words.len()
+                    );
+                }
+            }
+        }
+
+        #[cfg(feature = "std")]
         #[test]
         fn test_invalid_word_count() {
             let config = PassphraseConfig::new(0, "-".to_string(), WordList::Default);
             assert!(PassphraseGenerator::generate(&config).is_err());
         }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_sampling_modes() {
+            let words = LoadedWordList::from_words(["alpha", "bravo", "charlie"]).unwrap();
+            let config = PassphraseConfig::new(5, "-".to_string(), words.into());
+            assert!(matches!(
+                PassphraseGenerator::generate(&config),
+                Err(PassForgeError::InvalidWordCount(_))
+            ));
+
+            let config = PassphraseConfig {
+                sampling: Sampling::WithReplacement,
+                ..config
+            };
+            let passphrase = PassphraseGenerator::generate(&config).unwrap();
+            let passphrase = passphrase.expose_secret();
+            assert_eq!(passphrase.split('-').count(), 5);
+            assert_eq!(PassphraseGenerator::keyspace(&config).unwrap(), Some(243));
+
+            let config = PassphraseConfig::builder()
+                .words(4)
+                .sampling(Sampling::WithReplacement)
+                .build();
+            let bits = PassphraseGenerator::entropy_bits(&config).unwrap();
+            assert!((bits - 4.0 * 7776f64.log2()).abs() < 1e-9);
+            assert_eq!(
+                PassphraseGenerator::keyspace(&config).unwrap(),
+                Some(7776u128.pow(4))
+            );
+        }
+    }
+
+    #[cfg(feature = "std")]
+    mod passphrase_styling_tests {
+        use std::collections::HashSet;
+
+        use rand::{rngs::StdRng, SeedableRng};
+
+        use super::*;
+
+        /// Writes a word list to a temporary file and returns it as a custom `WordList`.
+        fn custom_word_list(name: &str, words: &[&str]) -> WordList {
+            let path = std::env::temp_dir().join(format!("passforge_{}.txt", name));
+            std::fs::write(&path, words.join("\n")).unwrap();
+            WordList::Custom(path)
+        }
+
+        #[test]
+        fn test_capitalization() {
+            for (capitalization, expected) in [
+                (Capitalization::None, "abacus abdomen"),
+                (Capitalization::FirstLetter, "Abacus Abdomen"),
+                (Capitalization::All, "ABACUS ABDOMEN"),
+            ] {
+                let config = PassphraseConfig::builder()
+                    .words(2)
+                    .separator(" ".into())
+                    .capitalization(capitalization)
+                    .build();
+                let passphrase =
+                    PassphraseGenerator::from_dice_rolls(&config, &["11111", "11112"]).unwrap();
+                assert_eq!(passphrase.expose_secret(), expected);
+            }
+
+            let config = PassphraseConfig::builder()
+                .separator(" ".into())
+                .capitalization(Capitalization::RandomWord)
+                .build();
+            for _ in 0..20 {
+                let passphrase = PassphraseGenerator::generate(&config).unwrap();
+                let passphrase = passphrase.expose_secret();
+                let upper = passphrase
+                    .split(' ')
+                    .filter(|word| word.chars().all(|c| c.is_ascii_uppercase()))
+                    .count();
+                assert_eq!(upper, 1);
+            }
+        }
+
+        #[test]
+        fn test_insertion() {
+            let config = PassphraseConfig::builder()
+                .words(4)
+                .digits(2)
+                .symbols(1)
+                .symbol_set("!".into())
+                .build();
+            for _ in 0..20 {
+                let passphrase = PassphraseGenerator::generate(&config).unwrap();
+                let passphrase = passphrase.expose_secret();
+                assert_eq!(passphrase.chars().filter(|c| c.is_ascii_digit()).count(), 2);
+                assert_eq!(passphrase.matches('!').count(), 1);
+            }
+
+            let config = PassphraseConfig::builder()
+                .words(3)
+                .separator(" ".into())
+                .digits(1)
+                .symbols(1)
+                .insertion(Insertion::Tokens)
+                .build();
+            for _ in 0..20 {
+                let passphrase = PassphraseGenerator::generate(&config).unwrap();
+                let passphrase = passphrase.expose_secret();
+                let tokens: Vec<&str> = passphrase.split(' ').collect();
+                assert_eq!(tokens.len(), 5);
+                assert_eq!(tokens.iter().filter(|token| token.len() == 1).count(), 2);
+            }
+        }
+
+        #[test]
+        fn test_random_separators() {
+            let config = PassphraseConfig::builder()
+                .words(5)
+                .separators(".,".into())
+                .build();
+            for _ in 0..20 {
+                let passphrase = PassphraseGenerator::generate(&config).unwrap();
+                let passphrase = passphrase.expose_secret();
+                assert_eq!(passphrase.split(['.', ',']).count(), 5);
+            }
+        }
+
+        #[test]
+        fn test_entropy_matches_distinct_passphrases() {
+            let word_list = custom_word_list("styling_words", &["ab", "cde", "f"]);
+            let anywhere = PassphraseConfig::builder()
+                .words(2)
+                .word_list(word_list.clone())
+                .capitalization(Capitalization::RandomWord)
+                .digits(1)
+                .build();
+            let tokens = PassphraseConfig::builder()
+                .words(2)
+                .word_list(word_list)
+                .symbols(1)
+                .symbol_set("!?".into())
+                .separators("-_".into())
+                .insertion(Insertion::Tokens)
+                .build();
+
+            let repeated = PassphraseConfig::builder()
+                .words(2)
+                .word_list(custom_word_list("styling_words", &["ab", "cde", "f"]))
+                .sampling(Sampling::WithReplacement)
+                .digits(1)
+                .build();
+
+            let mut rng = StdRng::seed_from_u64(1);
+            for config in [&anywhere, &tokens, &repeated] {
+                let distinct: HashSet<String> =
+                    PassphraseGenerator::generate_multiple_with_rng(config, 20_000, &mut rng)
+                        .unwrap()
+                        .iter()
+                        .map(|passphrase| passphrase.expose_secret().to_string())
+                        .collect();
+                let keyspace = PassphraseGenerator::keyspace(config).unwrap().unwrap();
+                assert_eq!(distinct.len() as u128, keyspace);
+            }
+
+            // 720 passphrases whose insertion point is uniform given the words, which leave
+            // 7, 5 or 6 places for the digit.
+            let expected =
+                6f64.log2() + 1.0 + 10f64.log2() + (7f64.log2() + 5f64.log2() + 6f64.log2()) / 3.0;
+            let bits = PassphraseGenerator::entropy_bits(&anywhere).unwrap();
+            assert!((bits - expected).abs() < 1e-9);
+            let bits = PassphraseGenerator::entropy_bits(&tokens).unwrap();
+            assert!((bits - 144f64.log2()).abs() < 1e-9);
+            // 9 ordered pairs whose lengths of 3 to 7 leave 4 to 8 places for the digit.
+            let bits = PassphraseGenerator::entropy_bits(&repeated).unwrap();
+            let expected = 9f64.log2()
+                + 10f64.log2()
+                + [5, 6, 4, 6, 7, 5, 4, 5, 3]
+                    .iter()
+                    .map(|length: &i32| f64::from(length + 1).log2())
+                    .sum::<f64>()
+                    / 9.0;
+            assert!((bits - expected).abs() < 1e-9);
+        }
+
+        #[test]
+        fn test_invalid_styling() {
+            let word_list = custom_word_list("styling_clash", &["r2d2", "c3po"]);
+            let clashes = [
+                PassphraseConfig::builder()
+                    .words(2)
+                    .word_list(word_list)
+                    .digits(1)
+                    .build(),
+                PassphraseConfig::builder()
+                    .symbols(1)
+                    .separator("!".into())
+                    .symbol_set("!".into())
+                    .build(),
+                PassphraseConfig::builder()
+                    .symbols(1)
+                    .symbol_set(String::new())
+                    .build(),
+                PassphraseConfig::builder()
+                    .separators(String::new())
+                    .build(),
+            ];
+            for config in clashes {
+                assert!(matches!(
+                    PassphraseGenerator::generate(&config),
+                    Err(PassForgeError::InvalidConfig(_))
+                ));
+            }
+
+            let config = PassphraseConfig::builder().words(2).digits(1).build();
+            assert!(PassphraseGenerator::from_dice_rolls(&config, &["11111", "11112"]).is_err());
+        }
     }
 }
@@ -3,113 +3,364 @@
 //! It provides a `PasswordGenerator` struct that implements the `Generator` trait,
 //! allowing for customizable password generation.
 
-use rand::Rng;
+use alloc::{boxed::Box, format, vec, vec::Vec};
+use core::ops::RangeInclusive;
 
-use crate::config::PasswordConfig;
-use crate::generator::Generator;
-use crate::PassForgeError;
+use rand::seq::SliceRandom;
+use rand::{CryptoRng, Rng, RngCore};
+
+use crate::config::{CharClass, Length, PasswordConfig};
+use crate::generator::{Generator, Prepared};
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::{PassForgeError, SecretString};
+
+mod positional;
+
+use positional::PositionalSampler;
 
 /// Struct for generating passwords based on specified configurations.
 pub struct PasswordGenerator;
 
 impl PasswordGenerator {
-    /// Lowercase letters used in password generation.
-    const LOWERCASE: &'static [u8] = b"abcdefghijklmnopqrstuvwxyz";
-    /// Uppercase letters used in password generation.
-    const UPPERCASE: &'static [u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
-    /// Numbers used in password generation.
-    const NUMBERS: &'static [u8] = b"0123456789";
-    /// Symbols used in password generation.
-    const SYMBOLS: &'static [u8] = b"!@#$%^&*()-_=+[]{}|;:,.<>?";
-}
-
-impl Generator for PasswordGenerator {
-    type Config = PasswordConfig;
-    type Output = String;
-
-    /// Generates a single password based on the provided configuration.
+    /// Validates the length specification and returns every possible length.
     ///
-    /// # Arguments
+    /// # Errors
     ///
-    /// * `config` - A reference to the `PasswordConfig` specifying generation parameters.
+    /// Will return an error if the range is empty or allows a length less than 1.
+    fn lengths(config: &PasswordConfig) -> Result<RangeInclusive<usize>, PassForgeError> {
+        let lengths = match &config.length {
+            Length::Single(length) => *length..=*length,
+            Length::Range(range) => range.clone(),
+        };
+        if lengths.is_empty() {
+            return Err(PassForgeError::InvalidLength(
+                "Length range cannot be empty".into(),
+            ));
+        }
+        if *lengths.start() < 1 {
+            return Err(PassForgeError::InvalidLength(
+                "Length of password cannot be less than 1".into(),
+            ));
+        }
+        Ok(lengths)
+    }
+
+    /// Builds the sampler for the positional constraints of the configuration, if it has any,
+    /// and checks that every possible length can be satisfied.
     ///
-    /// # Returns
+    /// # Errors
     ///
-    /// Returns a `Result` containing the generated password as a `String` if successful,
-    /// or a `PassForgeError` if an error occurred during generation.
+    /// Will return an error if the constraints cannot be sampled exactly, or if some length
+    /// has no valid password.
+    fn positional_sampler(
+        config: &PasswordConfig,
+        classes: &[CharClass],
+        lengths: &RangeInclusive<usize>,
+    ) -> Result<Option<PositionalSampler>, PassForgeError> {
+        if config.max_consecutive.is_none() && !config.start_with_letter {
+            return Ok(None);
+        }
+        let sampler = PositionalSampler::new(
+            classes,
+            config.max_consecutive,
+            config.start_with_letter,
+            *lengths.end(),
+        )?;
+        if let Some(length) = lengths
+            .clone()
+            .find(|length| sampler.ln_count(*length) == f64::NEG_INFINITY)
+        {
+            return Err(PassForgeError::InvalidConfig(format!(
+                "No password of length {} satisfies the character restrictions",
+                length
+            )));
+        }
+        Ok(Some(sampler))
+    }
+
+    /// Returns, for every possible length, the natural logarithm of the number of distinct
+    /// passwords the configuration can produce.
     ///
     /// # Errors
     ///
-    /// Will return an error if the specified password length is less than 1.
-    fn generate(config: &Self::Config) -> Result<Self::Output, PassForgeError> {
-        let mut rng = rand::thread_rng();
-        let length = config.length.get_length();
+    /// Will return an error if the configuration cannot produce a password.
+    pub(crate) fn ln_counts(config: &PasswordConfig) -> Result<Vec<f64>, PassForgeError> {
+        let lengths = Self::lengths(config)?;
+        let alphabet = config.alphabet()?;
+        let classes = alphabet.classes();
+        Self::check_length(classes, *lengths.start())?;
+        Self::check_length(classes, *lengths.end())?;
 
-        if length < 1 {
-            return Err(PassForgeError::InvalidLength(
-                "Length of password cannot be less than 1".into(),
-            ));
+        if let Some(sampler) = Self::positional_sampler(config, classes, &lengths)? {
+            return Ok(lengths.map(|length| sampler.ln_count(length)).collect());
         }
-
-        // Calculate total character set length to pre-allocate memory
-        let mut total_len = Self::LOWERCASE.len();
-        if config.capitals {
-            total_len += Self::UPPERCASE.len();
+        if classes.iter().all(|class| class.count.is_unconstrained()) {
+            let ln_size = (alphabet.len() as f64).ln();
+            return Ok(lengths.map(|length| length as f64 * ln_size).collect());
         }
-        if config.numbers {
-            total_len += Self::NUMBERS.len();
+        let ln_factorials = ln_factorials(*lengths.end());
+        let table = Self::composition_table(classes, *lengths.end(), &ln_factorials);
+        Ok(lengths
+            .map(|length| ln_factorials[length] + table[classes.len()][length])
+            .collect())
+    }
+
+    /// Returns, for every possible length, the exact number of distinct passwords the
+    /// configuration can produce, or `None` where it does not fit in a `u128`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the configuration cannot produce a password.
+    pub(crate) fn counts(config: &PasswordConfig) -> Result<Vec<Option<u128>>, PassForgeError> {
+        let lengths = Self::lengths(config)?;
+        let alphabet = config.alphabet()?;
+        let classes = alphabet.classes();
+        Self::check_length(classes, *lengths.start())?;
+        Self::check_length(classes, *lengths.end())?;
+
+        if let Some(sampler) = Self::positional_sampler(config, classes, &lengths)? {
+            return Ok(sampler.counts(*lengths.end())[*lengths.start()..].to_vec());
         }
-        if config.symbols {
-            total_len += Self::SYMBOLS.len();
+        // Entry `[total]` counts the passwords of `total` characters over the classes so far,
+        // choosing which positions the next class takes and which characters fill them.
+        let mut counts: Vec<Option<u128>> = vec![Some(0); *lengths.end() + 1];
+        counts[0] = Some(1);
+        for class in classes {
+            let size = class.chars.len() as u128;
+            counts = (0..counts.len())
+                .map(|total| {
+                    let upper = class.count.max.unwrap_or(total).min(total);
+                    let mut sum = Some(0u128);
+                    let mut binomial = Some(1u128);
+                    for k in 0..=upper {
+                        if k > 0 {
+                            binomial = binomial
+                                .and_then(|b| b.checked_mul((total - k + 1) as u128))
+                                .map(|b| b / k as u128);
+                        }
+                        if k < class.count.min || counts[total - k] == Some(0) {
+                            continue;
+                        }
+                        let term = binomial
+                            .zip(size.checked_pow(k as u32))
+                            .and_then(|(b, power)| b.checked_mul(power))
+                            .zip(counts[total - k])
+                            .and_then(|(ways, rest)| ways.checked_mul(rest));
+                        sum = sum.zip(term).and_then(|(sum, term)| sum.checked_add(term));
+                    }
+                    sum
+                })
+                .collect();
         }
+        Ok(counts[*lengths.start()..].to_vec())
+    }
 
-        // Create a single Vec<u8> with all allowed characters
-        let mut chars = Vec::with_capacity(total_len);
-        chars.extend_from_slice(Self::LOWERCASE);
-        if config.capitals {
-            chars.extend_from_slice(Self::UPPERCASE);
+    /// Checks that a password of the given length can satisfy every class bound.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the length is shorter than the sum of the minimums,
+    /// or longer than the sum of the maximums.
+    fn check_length(classes: &[CharClass], length: usize) -> Result<(), PassForgeError> {
+        let required: usize = classes.iter().map(|class| class.count.min).sum();
+        if length < required {
+            return Err(PassForgeError::InvalidLength(format!(
+                "Length {} is too short to fit the {} required characters",
+                length, required
+            )));
+        }
+        let allowed: Option<usize> = classes.iter().map(|class| class.count.max).sum();
+        if let Some(allowed) = allowed {
+            if length > allowed {
+                return Err(PassForgeError::InvalidLength(format!(
+                    "Length {} exceeds the {} characters allowed by the maximum of each class",
+                    length, allowed
+                )));
+            }
         }
-        if config.numbers {
-            chars.extend_from_slice(Self::NUMBERS);
+        Ok(())
+    }
+
+    /// Computes, in log space, the weighted number of ways to split `total` positions over the
+    /// first `i` classes. Entry `[i][total]` is `ln(sum(prod(size^k / k!)))` over all valid splits,
+    /// so multiplying by `total!` yields the number of distinct passwords.
+    fn composition_table(
+        classes: &[CharClass],
+        length: usize,
+        ln_factorials: &[f64],
+    ) -> Vec<Vec<f64>> {
+        let mut table = vec![vec![f64::NEG_INFINITY; length + 1]; classes.len() + 1];
+        table[0][0] = 0.0;
+        for (i, class) in classes.iter().enumerate() {
+            let ln_size = (class.chars.len() as f64).ln();
+            for total in 0..=length {
+                let upper = class.count.max.unwrap_or(total).min(total);
+                if class.count.min > upper {
+                    continue;
+                }
+                let terms: Vec<f64> = (class.count.min..=upper)
+                    .map(|k| k as f64 * ln_size - ln_factorials[k] + table[i][total - k])
+                    .collect();
+                table[i + 1][total] = log_sum_exp(&terms);
+            }
         }
-        if config.symbols {
-            chars.extend_from_slice(Self::SYMBOLS);
+        table
+    }
+
+    /// Draws how many characters each class contributes to a password of the given length.
+    ///
+    /// Each split is weighted by the number of passwords having it, so that after filling and
+    /// shuffling, every password satisfying the bounds is equally likely. The `table` and
+    /// `ln_factorials` must cover at least `length`, as built by `prepare` for the longest
+    /// allowed length.
+    fn sample_class_counts<R: RngCore + CryptoRng + ?Sized>(
+        classes: &[CharClass],
+        length: usize,
+        table: &[Vec<f64>],
+        ln_factorials: &[f64],
+        rng: &mut R,
+    ) -> Vec<usize> {
+        let mut counts = vec![0; classes.len()];
+        let mut remaining = length;
+        for (i, class) in classes.iter().enumerate().rev() {
+            let ln_size = (class.chars.len() as f64).ln();
+            let total = table[i + 1][remaining];
+            let upper = class.count.max.unwrap_or(remaining).min(remaining);
+            let mut target: f64 = rng.gen();
+            let mut chosen = None;
+            for k in class.count.min..=upper {
+                let ln_weight = k as f64 * ln_size - ln_factorials[k] + table[i][remaining - k];
+                if ln_weight == f64::NEG_INFINITY {
+                    continue;
+                }
+                chosen = Some(k);
+                let probability = (ln_weight - total).exp();
+                if target < probability {
+                    break;
+                }
+                target -= probability;
+            }
+            // `check_length` guarantees a valid split exists, so a count is always chosen.
+            let chosen = chosen.unwrap_or(class.count.min);
+            counts[i] = chosen;
+            remaining -= chosen;
         }
+        counts
+    }
+}
+
+/// Computes `ln(k!)` for every `k` up to and including `n`.
+fn ln_factorials(n: usize) -> Vec<f64> {
+    core::iter::once(0.0)
+        .chain((1..=n).scan(0.0, |acc, k| {
+            *acc += (k as f64).ln();
+            Some(*acc)
+        }))
+        .collect()
+}
 
-        // Generate password using byte operations for efficiency
-        let result: String = (0..length)
-            .map(|_| chars[rng.gen_range(0..chars.len())] as char)
-            .collect();
-        Ok(result)
+/// Computes `ln(sum(exp(x)))` without overflowing for large inputs.
+fn log_sum_exp(values: &[f64]) -> f64 {
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return max;
     }
+    max + values.iter().map(|v| (v - max).exp()).sum::<f64>().ln()
+}
+
+impl Generator for PasswordGenerator {
+    type Config = PasswordConfig;
+    type Output = SecretString;
 
-    /// Generates multiple passwords based on the provided configuration.
+    /// Generates a single password based on the provided configuration, drawing randomness
+    /// from the supplied random number generator.
+    ///
+    /// Characters are drawn uniformly from all enabled classes. When per-class minimums or
+    /// maximums are configured, the password is drawn uniformly from all passwords satisfying
+    /// them, so the required characters do not bias the rest of the output. Limits on
+    /// consecutive repeats and a required leading letter are enforced while building the
+    /// password, again drawing uniformly from all passwords that satisfy them. The characters
+    /// are drawn into a buffer of the final length, which is wiped once the password is built.
     ///
     /// # Arguments
     ///
     /// * `config` - A reference to the `PasswordConfig` specifying generation parameters.
-    /// * `amount` - The number of passwords to generate.
+    /// * `rng` - The cryptographically secure random number generator to draw from.
     ///
     /// # Returns
     ///
-    /// Returns a `Result` containing a vector of generated passwords as `String`s if successful,
+    /// Returns a `Result` containing the generated password as a `SecretString` if successful,
     /// or a `PassForgeError` if an error occurred during generation.
     ///
     /// # Errors
     ///
-    /// Will return an error if the specified amount is less than or equal to 1.
-    fn generate_multiple(
+    /// Will return an error if the specified password length is less than 1, or if the
+    /// per-class bounds or positional constraints cannot be satisfied by the configured length.
+    fn generate_with_rng<R: RngCore + CryptoRng + ?Sized>(
         config: &Self::Config,
-        amount: usize,
-    ) -> Result<Vec<Self::Output>, PassForgeError> {
-        if amount <= 1 {
-            return Err(PassForgeError::InvalidGenAmount(
-                "Amount cannot be smaller than 1".into(),
-            ));
+        rng: &mut R,
+    ) -> Result<Self::Output, PassForgeError> {
+        Self::prepare(config)?(rng)
+    }
+
+    /// Resolves the alphabet and builds the tables for the configuration once, so that
+    /// generating many passwords does not repeat the work.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the `PasswordConfig` specifying generation parameters.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing a function that generates one password per call if
+    /// successful, or a `PassForgeError` if the configuration is invalid.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error for the same configurations as `generate_with_rng`.
+    fn prepare<'a, R: RngCore + CryptoRng + ?Sized + 'a>(
+        config: &'a Self::Config,
+    ) -> Result<Prepared<'a, R, Self::Output>, PassForgeError> {
+        let lengths = Self::lengths(config)?;
+        let alphabet = config.alphabet()?;
+        let classes = alphabet.classes();
+        Self::check_length(classes, *lengths.start())?;
+        Self::check_length(classes, *lengths.end())?;
+
+        if let Some(sampler) = Self::positional_sampler(config, classes, &lengths)? {
+            return Ok(Box::new(move |rng| {
+                let length = config.length.get_length_with_rng(rng);
+                Ok(SecretString::from_chars(sampler.sample(length, rng)))
+            }));
+        }
+
+        if classes.iter().all(|class| class.count.is_unconstrained()) {
+            // Create a single Vec<char> with all allowed characters
+            let chars: Vec<char> = alphabet.chars().collect();
+
+            return Ok(Box::new(move |rng| {
+                let length = config.length.get_length_with_rng(rng);
+                let mut password = Vec::with_capacity(length);
+                password.extend((0..length).map(|_| chars[rng.gen_range(0..chars.len())]));
+                Ok(SecretString::from_chars(password))
+            }));
         }
-        (0..amount)
-            .map(|_| PasswordGenerator::generate(config))
-            .collect()
+
+        let ln_factorials = ln_factorials(*lengths.end());
+        let table = Self::composition_table(classes, *lengths.end(), &ln_factorials);
+        Ok(Box::new(move |rng| {
+            let length = config.length.get_length_with_rng(rng);
+            let classes = alphabet.classes();
+            let counts = Self::sample_class_counts(classes, length, &table, &ln_factorials, rng);
+            let mut password = Vec::with_capacity(length);
+            for (class, count) in classes.iter().zip(counts) {
+                password
+                    .extend((0..count).map(|_| class.chars[rng.gen_range(0..class.chars.len())]));
+            }
+            password.shuffle(rng);
+            Ok(SecretString::from_chars(password))
+        }))
     }
 }
 
@@ -118,60 +369,236 @@ mod tests {
     use super::*;
 
     mod password_generator_tests {
+        use alloc::collections::BTreeSet;
+
+        use rand::{rngs::StdRng, SeedableRng};
+
         use crate::Length;
 
         use super::*;
 
+        #[cfg(feature = "std")]
         #[test]
         fn test_password_generation() {
             let config = PasswordConfig::new(Length::Single(16), true, true, true);
             let password = PasswordGenerator::generate(&config).unwrap();
+            let password = password.expose_secret();
             assert_eq!(password.len(), 16);
         }
 
+        #[cfg(feature = "std")]
         #[test]
         fn test_password_generation_no_capitals() {
             let config = PasswordConfig::new(Length::Single(16), false, true, true);
             let password = PasswordGenerator::generate(&config).unwrap();
+            let password = password.expose_secret();
             assert_eq!(password.len(), 16);
             assert!(!password.chars().any(|c| c.is_uppercase()));
         }
 
+        #[cfg(feature = "std")]
         #[test]
         fn test_password_generation_no_numbers() {
             let config = PasswordConfig::new(Length::Single(16), true, false, true);
             let password = PasswordGenerator::generate(&config).unwrap();
+            let password = password.expose_secret();
             assert_eq!(password.len(), 16);
             assert!(!password.chars().any(|c| c.is_numeric()));
         }
 
+        #[cfg(feature = "std")]
         #[test]
         fn test_password_generation_no_symbols() {
             let config = PasswordConfig::new(Length::Single(16), true, true, false);
             let password = PasswordGenerator::generate(&config).unwrap();
+            let password = password.expose_secret();
             assert_eq!(password.len(), 16);
             assert!(!password
                 .chars()
                 .any(|c| "!@#$%^&*()-_=+[]{}|;:,.<>?".contains(c)));
         }
 
+        #[cfg(feature = "std")]
         #[test]
         fn test_password_generation_range_length() {
             let config = PasswordConfig::new(Length::Range(10..=20), true, true, true);
             let password = PasswordGenerator::generate(&config).unwrap();
+            let password = password.expose_secret();
             assert!(password.len() >= 10 && password.len() <= 20);
         }
 
+        #[cfg(feature = "std")]
         #[test]
         fn test_generate_multiple_passwords() {
             let config = PasswordConfig::new(Length::Single(16), true, true, true);
             let passwords = PasswordGenerator::generate_multiple(&config, 5).unwrap();
             assert_eq!(passwords.len(), 5);
             for password in passwords {
-                assert_eq!(password.len(), 16);
+                assert_eq!(password.expose_secret().len(), 16);
+            }
+        }
+
+        #[test]
+        fn test_seeded_generation_is_reproducible() {
+            let config = PasswordConfig::new(Length::Range(10..=20), true, true, true);
+            let first =
+                PasswordGenerator::generate_with_rng(&config, &mut StdRng::seed_from_u64(42))
+                    .unwrap();
+            let second =
+                PasswordGenerator::generate_with_rng(&config, &mut StdRng::seed_from_u64(42))
+                    .unwrap();
+            assert_eq!(first.expose_secret(), second.expose_secret());
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_password_generation_class_minimums() {
+            let config = PasswordConfig::builder()
+                .length(Length::Single(8))
+                .min_capitals(2)
+                .min_numbers(3)
+                .min_symbols(1)
+                .build();
+            for _ in 0..50 {
+                let password = PasswordGenerator::generate(&config).unwrap();
+                let password = password.expose_secret();
+                assert_eq!(password.len(), 8);
+                assert!(password.chars().filter(|c| c.is_ascii_uppercase()).count() >= 2);
+                assert!(password.chars().filter(|c| c.is_ascii_digit()).count() >= 3);
+                assert!(password.chars().any(|c| !c.is_ascii_alphanumeric()));
+            }
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_password_generation_class_maximums() {
+            let config = PasswordConfig::builder()
+                .length(Length::Single(12))
+                .max_lowercase(2)
+                .max_capitals(2)
+                .max_symbols(0)
+                .build();
+            for _ in 0..50 {
+                let password = PasswordGenerator::generate(&config).unwrap();
+                let password = password.expose_secret();
+                assert!(password.chars().filter(|c| c.is_ascii_lowercase()).count() <= 2);
+                assert!(password.chars().filter(|c| c.is_ascii_uppercase()).count() <= 2);
+                assert!(password.chars().filter(|c| c.is_ascii_digit()).count() >= 8);
+            }
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_invalid_class_bounds() {
+            let disabled = PasswordConfig::builder()
+                .symbols(false)
+                .min_symbols(1)
+                .build();
+            assert!(PasswordGenerator::generate(&disabled).is_err());
+
+            let too_short = PasswordConfig::builder()
+                .length(Length::Range(4..=10))
+                .min_numbers(3)
+                .min_symbols(3)
+                .build();
+            assert!(PasswordGenerator::generate(&too_short).is_err());
+
+            let too_long = PasswordConfig::builder()
+                .length(Length::Single(10))
+                .max_lowercase(2)
+                .max_capitals(2)
+                .max_numbers(2)
+                .max_symbols(2)
+                .build();
+            assert!(PasswordGenerator::generate(&too_long).is_err());
+        }
+
+        #[test]
+        fn test_class_bounds_with_length_range() {
+            let config = PasswordConfig::builder()
+                .length(Length::Range(4..=8))
+                .min_numbers(2)
+                .max_symbols(1)
+                .build();
+            let mut rng = StdRng::seed_from_u64(7);
+            let mut generate = PasswordGenerator::prepare(&config).unwrap();
+            let mut lengths = BTreeSet::new();
+            for _ in 0..200 {
+                let password = generate(&mut rng).unwrap();
+                let password = password.expose_secret();
+                lengths.insert(password.len());
+                assert!(password.chars().filter(char::is_ascii_digit).count() >= 2);
+                assert!(password.chars().filter(char::is_ascii_punctuation).count() <= 1);
+            }
+            assert_eq!(lengths.into_iter().collect::<Vec<_>>(), [4, 5, 6, 7, 8]);
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_password_generation_positional_constraints() {
+            let config = PasswordConfig::builder()
+                .length(Length::Range(6..=12))
+                .charset("ab12".into())
+                .min_numbers(2)
+                .max_consecutive(1)
+                .start_with_letter(true)
+                .build();
+            for _ in 0..50 {
+                let password: Vec<char> = PasswordGenerator::generate(&config)
+                    .unwrap()
+                    .expose_secret()
+                    .chars()
+                    .collect();
+                assert!(password[0].is_alphabetic());
+                assert!(password.windows(2).all(|pair| pair[0] != pair[1]));
+                assert!(password.iter().filter(|c| c.is_ascii_digit()).count() >= 2);
+            }
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_unsatisfiable_positional_constraints() {
+            let config = PasswordConfig::builder()
+                .length(Length::Single(4))
+                .charset("a1".into())
+                .max_numbers(1)
+                .max_consecutive(1)
+                .build();
+            assert!(PasswordGenerator::generate(&config).is_err());
+
+            let no_letters = PasswordConfig::builder()
+                .charset("123".into())
+                .start_with_letter(true)
+                .build();
+            assert!(PasswordGenerator::generate(&no_letters).is_err());
+        }
+
+        #[test]
+        fn test_counts_match_brute_force() {
+            let config = PasswordConfig::builder()
+                .length(Length::Range(1..=4))
+                .charset("ab1!".into())
+                .min_lowercase(1)
+                .max_numbers(1)
+                .build();
+            let counts = PasswordGenerator::counts(&config).unwrap();
+            let ln_counts = PasswordGenerator::ln_counts(&config).unwrap();
+            for length in 1..=4usize {
+                let expected = (0..4usize.pow(length as u32))
+                    .filter(|index| {
+                        let digits: Vec<usize> = (0..length)
+                            .map(|i| index / 4usize.pow(i as u32) % 4)
+                            .collect();
+                        digits.iter().any(|d| *d < 2)
+                            && digits.iter().filter(|d| **d == 2).count() <= 1
+                    })
+                    .count() as u128;
+                assert_eq!(counts[length - 1], Some(expected));
+                assert!((ln_counts[length - 1] - (expected as f64).ln()).abs() < 1e-9);
             }
         }
 
+        #[cfg(feature = "std")]
         #[test]
         fn test_invalid_length() {
             let config = PasswordConfig::new(Length::Single(0), true, true, true);
@@ -0,0 +1,383 @@
+//! Exact sampling of passwords under positional constraints.
+//!
+//! Limits on consecutive repeats and a required leading letter depend on where characters
+//! end up, so they cannot be handled by drawing class counts and shuffling. Instead, the
+//! number of valid completions is counted for every generation state (how many characters
+//! each class has contributed so far, and the run of the last character), and the password
+//! is built one position at a time with each step weighted by the completions it leaves.
+//! Every password satisfying the constraints is therefore equally likely, without retrying.
+
+use alloc::{vec, vec::Vec};
+
+use rand::{CryptoRng, Rng, RngCore};
+
+use crate::config::CharClass;
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::PassForgeError;
+
+use super::log_sum_exp;
+
+/// The largest table, in states times positions, that is built before giving up.
+const MAX_TABLE_SIZE: usize = 4_000_000;
+
+/// How a character is chosen relative to the previous one.
+#[derive(Clone, Copy)]
+enum Step {
+    /// Any character of the class.
+    Fresh,
+    /// Any character of the class except the previous one, which belongs to the same class.
+    Different,
+    /// The previous character again.
+    Repeat,
+}
+
+/// A possible choice for the next position.
+struct Transition {
+    class: usize,
+    step: Step,
+    choices: usize,
+    next: usize,
+}
+
+/// Counts and samples passwords satisfying class bounds, a maximum run of identical
+/// characters and optionally a leading letter.
+pub(crate) struct PositionalSampler {
+    classes: Vec<CharClass>,
+    letters: Vec<Vec<char>>,
+    /// The highest count tracked per class: the maximum if bounded, the minimum otherwise.
+    caps: Vec<usize>,
+    strides: Vec<usize>,
+    max_run: Option<usize>,
+    run_states: usize,
+    start_with_letter: bool,
+    /// `table[remaining][state]` is the log of the number of ways to fill `remaining`
+    /// positions from `state` and end with every class minimum met.
+    table: Vec<Vec<f64>>,
+}
+
+impl PositionalSampler {
+    /// Builds the completion table for passwords of up to `longest` characters.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the maximum run is 0, or if the constraints need a table too
+    /// large to build.
+    pub(crate) fn new(
+        classes: &[CharClass],
+        max_run: Option<usize>,
+        start_with_letter: bool,
+        longest: usize,
+    ) -> Result<Self, PassForgeError> {
+        if max_run == Some(0) {
+            return Err(PassForgeError::InvalidConfig(
+                "Maximum number of consecutive characters cannot be 0".into(),
+            ));
+        }
+        let caps: Vec<usize> = classes
+            .iter()
+            .map(|class| class.count.max.unwrap_or(class.count.min))
+            .collect();
+        let mut strides = Vec::with_capacity(caps.len());
+        let mut count_states = 1usize;
+        for cap in &caps {
+            strides.push(count_states);
+            count_states = count_states.saturating_mul(cap + 1);
+        }
+        let run_states = max_run.map_or(1, |max_run| 1 + classes.len() * max_run);
+        let states = count_states.saturating_mul(run_states);
+        if states.saturating_mul(longest + 1) > MAX_TABLE_SIZE {
+            return Err(PassForgeError::InvalidConfig(
+                "The password constraints are too complex to generate exactly".into(),
+            ));
+        }
+
+        let mut sampler = Self {
+            classes: classes.to_vec(),
+            letters: classes
+                .iter()
+                .map(|class| {
+                    class
+                        .chars
+                        .iter()
+                        .copied()
+                        .filter(|c| c.is_alphabetic())
+                        .collect()
+                })
+                .collect(),
+            caps,
+            strides,
+            max_run,
+            run_states,
+            start_with_letter,
+            table: Vec::with_capacity(longest + 1),
+        };
+        let complete: Vec<f64> = (0..states)
+            .map(|state| {
+                if sampler.is_complete(state) {
+                    0.0
+                } else {
+                    f64::NEG_INFINITY
+                }
+            })
+            .collect();
+        sampler.table.push(complete);
+        for remaining in 1..=longest {
+            let row: Vec<f64> = (0..states)
+                .map(|state| {
+                    let terms: Vec<f64> = sampler
+                        .transitions(state, false)
+                        .iter()
+                        .map(|t| (t.choices as f64).ln() + sampler.table[remaining - 1][t.next])
+                        .collect();
+                    log_sum_exp(&terms)
+                })
+                .collect();
+            sampler.table.push(row);
+        }
+        Ok(sampler)
+    }
+
+    /// Returns the natural logarithm of the number of valid passwords of the given length,
+    /// which is negative infinity if there are none.
+    pub(crate) fn ln_count(&self, length: usize) -> f64 {
+        if length == 0 {
+            return f64::NEG_INFINITY;
+        }
+        let terms: Vec<f64> = self
+            .transitions(0, self.start_with_letter)
+            .iter()
+            .map(|t| (t.choices as f64).ln() + self.table[length - 1][t.next])
+            .collect();
+        log_sum_exp(&terms)
+    }
+
+    /// Returns the exact number of valid passwords of every length up to and including
+    /// `longest`, or `None` where it does not fit in a `u128`.
+    pub(crate) fn counts(&self, longest: usize) -> Vec<Option<u128>> {
+        let states = self.table[0].len();
+        let mut row: Vec<Option<u128>> = (0..states)
+            .map(|state| Some(self.is_complete(state) as u128))
+            .collect();
+        let mut counts = vec![Some(0)];
+        for _ in 1..=longest {
+            let total = |row: &[Option<u128>], state: usize, first: bool| {
+                self.transitions(state, first)
+                    .iter()
+                    .filter(|t| row[t.next] != Some(0))
+                    .try_fold(0u128, |sum, t| {
+                        row[t.next]
+                            .and_then(|rest| rest.checked_mul(t.choices as u128))
+                            .and_then(|ways| sum.checked_add(ways))
+                    })
+            };
+            counts.push(total(&row, 0, self.start_with_letter));
+            row = (0..states).map(|state| total(&row, state, false)).collect();
+        }
+        counts
+    }
+
+    /// Draws a password of the given length uniformly from all valid passwords.
+    ///
+    /// The length must have a finite `ln_count`.
+    pub(crate) fn sample<R: RngCore + CryptoRng + ?Sized>(
+        &self,
+        length: usize,
+        rng: &mut R,
+    ) -> Vec<char> {
+        let mut password: Vec<char> = Vec::with_capacity(length);
+        let mut state = 0;
+        for position in 0..length {
+            let first = position == 0 && self.start_with_letter;
+            let remaining = length - position - 1;
+            let options: Vec<(Transition, f64)> = self
+                .transitions(state, first)
+                .into_iter()
+                .map(|t| {
+                    let weight = (t.choices as f64).ln() + self.table[remaining][t.next];
+                    (t, weight)
+                })
+                .filter(|(_, weight)| *weight > f64::NEG_INFINITY)
+                .collect();
+            let total = log_sum_exp(&options.iter().map(|(_, w)| *w).collect::<Vec<_>>());
+
+            let mut target: f64 = rng.gen();
+            let mut chosen = options.len() - 1;
+            for (index, (_, weight)) in options.iter().enumerate() {
+                let probability = (weight - total).exp();
+                if target < probability {
+                    chosen = index;
+                    break;
+                }
+                target -= probability;
+            }
+            let (transition, _) = &options[chosen];
+
+            let class = &self.classes[transition.class];
+            let c = match transition.step {
+                Step::Fresh if first => {
+                    let letters = &self.letters[transition.class];
+                    letters[rng.gen_range(0..letters.len())]
+                }
+                Step::Fresh => class.chars[rng.gen_range(0..class.chars.len())],
+                Step::Different => {
+                    let previous = password[position - 1];
+                    let others: Vec<char> = class
+                        .chars
+                        .iter()
+                        .copied()
+                        .filter(|c| *c != previous)
+                        .collect();
+                    others[rng.gen_range(0..others.len())]
+                }
+                Step::Repeat => password[position - 1],
+            };
+            password.push(c);
+            state = transition.next;
+        }
+        password
+    }
+
+    /// Returns `true` if every class minimum is met in the given state.
+    fn is_complete(&self, state: usize) -> bool {
+        let counts = state / self.run_states;
+        self.classes
+            .iter()
+            .enumerate()
+            .all(|(i, class)| (counts / self.strides[i]) % (self.caps[i] + 1) >= class.count.min)
+    }
+
+    /// Lists the choices for the next position from the given state. When `first` is set,
+    /// only letters may be chosen.
+    fn transitions(&self, state: usize, first: bool) -> Vec<Transition> {
+        let counts = state / self.run_states;
+        let run = state % self.run_states;
+        let mut transitions = Vec::new();
+        for (i, class) in self.classes.iter().enumerate() {
+            let count = (counts / self.strides[i]) % (self.caps[i] + 1);
+            let next_counts = if count < self.caps[i] {
+                counts + self.strides[i]
+            } else if class.count.max.is_none() {
+                counts
+            } else {
+                continue;
+            };
+            let size = if first {
+                self.letters[i].len()
+            } else {
+                class.chars.len()
+            };
+            let Some(max_run) = self.max_run else {
+                transitions.push(Transition {
+                    class: i,
+                    step: Step::Fresh,
+                    choices: size,
+                    next: next_counts,
+                });
+                continue;
+            };
+            // Run state 0 means no character yet, otherwise it encodes the class of the last
+            // character and how many times in a row it has appeared.
+            let fresh_run = 1 + i * max_run;
+            let next = next_counts * self.run_states + fresh_run;
+            if run == 0 || (run - 1) / max_run != i {
+                transitions.push(Transition {
+                    class: i,
+                    step: Step::Fresh,
+                    choices: size,
+                    next,
+                });
+                continue;
+            }
+            let length = (run - 1) % max_run + 1;
+            transitions.push(Transition {
+                class: i,
+                step: Step::Different,
+                choices: size - 1,
+                next,
+            });
+            if length < max_run {
+                transitions.push(Transition {
+                    class: i,
+                    step: Step::Repeat,
+                    choices: 1,
+                    next: next + length,
+                });
+            }
+        }
+        transitions.retain(|t| t.choices > 0);
+        transitions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ClassCount;
+
+    mod positional_sampler_tests {
+        use super::*;
+
+        /// Counts the strings over the classes that satisfy the constraints by brute force.
+        fn brute_force(
+            classes: &[CharClass],
+            max_run: Option<usize>,
+            start_with_letter: bool,
+            length: usize,
+        ) -> usize {
+            let chars: Vec<(usize, char)> = classes
+                .iter()
+                .enumerate()
+                .flat_map(|(i, class)| class.chars.iter().map(move |c| (i, *c)))
+                .collect();
+            let total = chars.len().pow(length as u32);
+            (0..total)
+                .filter(|index| {
+                    let mut rest = *index;
+                    let password: Vec<(usize, char)> = (0..length)
+                        .map(|_| {
+                            let c = chars[rest % chars.len()];
+                            rest /= chars.len();
+                            c
+                        })
+                        .collect();
+                    let counts_ok = classes.iter().enumerate().all(|(i, class)| {
+                        let count = password.iter().filter(|(class, _)| *class == i).count();
+                        count >= class.count.min && class.count.max.is_none_or(|max| count <= max)
+                    });
+                    let runs_ok = max_run.is_none_or(|max_run| {
+                        password
+                            .windows(max_run + 1)
+                            .all(|window| window.iter().any(|(_, c)| *c != window[0].1))
+                    });
+                    let start_ok = !start_with_letter || password[0].1.is_alphabetic();
+                    counts_ok && runs_ok && start_ok
+                })
+                .count()
+        }
+
+        #[test]
+        fn test_counts_match_brute_force() {
+            let classes = [
+                CharClass::new("letters", "ab", ClassCount::new(0, Some(3))),
+                CharClass::new("digits", "123", ClassCount::at_least(1)),
+            ];
+            for (max_run, start_with_letter) in [(None, true), (Some(1), false), (Some(2), true)] {
+                let sampler =
+                    PositionalSampler::new(&classes, max_run, start_with_letter, 5).unwrap();
+                for length in 1..=5 {
+                    let expected = brute_force(&classes, max_run, start_with_letter, length);
+                    let counted = sampler.ln_count(length).exp();
+                    assert!((counted - expected as f64).abs() < 1e-6 * (expected as f64 + 1.0));
+                    assert_eq!(sampler.counts(5)[length], Some(expected as u128));
+                }
+            }
+        }
+
+        #[test]
+        fn test_zero_run_is_rejected() {
+            let classes = [CharClass::new("letters", "ab", ClassCount::default())];
+            assert!(PositionalSampler::new(&classes, Some(0), false, 4).is_err());
+        }
+    }
+}
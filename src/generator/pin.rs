@@ -0,0 +1,96 @@
+//! This module implements PIN generation functionality.
+//!
+//! It provides a `PinGenerator` struct that implements the `Generator` trait,
+//! producing digit-only codes that avoid easily guessed patterns.
+
+use alloc::{format, string::String};
+
+use rand::{CryptoRng, Rng, RngCore};
+use zeroize::Zeroize;
+
+use crate::config::PinConfig;
+use crate::generator::Generator;
+use crate::{PassForgeError, SecretString};
+
+/// Struct for generating PINs based on specified configurations.
+pub struct PinGenerator;
+
+impl PinGenerator {
+    /// The maximum number of draws before giving up on finding a PIN that is not weak.
+    const MAX_ATTEMPTS: usize = 10_000;
+}
+
+impl Generator for PinGenerator {
+    type Config = PinConfig;
+    type Output = SecretString;
+
+    /// Generates a single PIN based on the provided configuration, drawing randomness from the
+    /// supplied random number generator.
+    ///
+    /// Digits are drawn uniformly and the PIN is redrawn while it matches a rejected pattern,
+    /// so every remaining PIN is equally likely.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the `PinConfig` specifying generation parameters.
+    /// * `rng` - The cryptographically secure random number generator to draw from.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the generated PIN as a `SecretString` if successful,
+    /// or a `PassForgeError` if an error occurred during generation.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the length is not between 1 and `PinConfig::MAX_LENGTH`,
+    /// or if no PIN that is not weak could be found.
+    fn generate_with_rng<R: RngCore + CryptoRng + ?Sized>(
+        config: &Self::Config,
+        rng: &mut R,
+    ) -> Result<Self::Output, PassForgeError> {
+        config.check_length()?;
+        // Rejected PINs are overwritten in place, and the buffer is wiped if none is accepted.
+        let mut pin = String::with_capacity(config.length);
+        for _ in 0..Self::MAX_ATTEMPTS {
+            pin.clear();
+            pin.extend((0..config.length).map(|_| char::from(b'0' + rng.gen_range(0..10))));
+            if config.weakness(&pin).is_none() {
+                return Ok(SecretString::new(pin));
+            }
+        }
+        pin.zeroize();
+        Err(PassForgeError::InvalidConfig(format!(
+            "Could not find a PIN of length {} that is not rejected as weak",
+            config.length
+        )))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    mod pin_generator_tests {
+        use crate::config::WeakPinPattern;
+
+        use super::*;
+
+        #[test]
+        fn test_pin_generation() {
+            let config = PinConfig::builder().length(4).build();
+            let pins = PinGenerator::generate_multiple(&config, 200).unwrap();
+            for pin in pins {
+                let pin = pin.expose_secret();
+                assert_eq!(pin.len(), 4);
+                assert!(pin.chars().all(|c| c.is_ascii_digit()));
+                assert!(WeakPinPattern::ALL.iter().all(|p| !p.matches(pin)));
+            }
+        }
+
+        #[test]
+        fn test_invalid_pin_config() {
+            let config = PinConfig::new(1, vec![WeakPinPattern::AllSame]);
+            assert!(PinGenerator::generate(&config).is_err());
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! This module implements pronounceable password generation functionality.
+//!
+//! It provides a `PronounceableGenerator` struct that implements the `Generator` trait,
+//! producing passwords from alternating consonant and vowel units that are easy to read aloud.
+
+use alloc::{vec, vec::Vec};
+
+use rand::seq::{index, SliceRandom};
+use rand::{CryptoRng, Rng, RngCore};
+use zeroize::Zeroize;
+
+use crate::config::pronounceable::{CONSONANTS, VOWELS};
+use crate::config::PronounceableConfig;
+use crate::generator::Generator;
+use crate::{PassForgeError, SecretString};
+
+/// Struct for generating pronounceable passwords based on specified configurations.
+pub struct PronounceableGenerator;
+
+impl PronounceableGenerator {
+    /// Builds a lowercase sequence of alternating consonant and vowel units.
+    ///
+    /// The first unit type is picked at random, and each unit is drawn uniformly from the units
+    /// of the current type that still fit in the remaining length.
+    ///
+    /// # Arguments
+    ///
+    /// * `letters` - The exact number of letters to produce.
+    /// * `rng` - The random number generator used to pick the units.
+    fn create_letters<R: RngCore + CryptoRng + ?Sized>(letters: usize, rng: &mut R) -> Vec<char> {
+        let mut result = Vec::with_capacity(letters);
+        let mut vowel = rng.gen_bool(0.5);
+        while result.len() < letters {
+            let remaining = letters - result.len();
+            let units: Vec<&str> = if vowel { VOWELS } else { CONSONANTS }
+                .iter()
+                .copied()
+                .filter(|unit| unit.len() <= remaining)
+                .collect();
+            // Every unit list contains single letters, so there is always a unit that fits.
+            if let Some(unit) = units.choose(rng) {
+                result.extend(unit.chars());
+            }
+            vowel = !vowel;
+        }
+        result
+    }
+}
+
+impl Generator for PronounceableGenerator {
+    type Config = PronounceableConfig;
+    type Output = SecretString;
+
+    /// Generates a single pronounceable password based on the provided configuration, drawing
+    /// randomness from the supplied random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the `PronounceableConfig` specifying generation parameters.
+    /// * `rng` - The cryptographically secure random number generator to draw from.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the generated password as a `SecretString` if successful,
+    /// or a `PassForgeError` if an error occurred during generation.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the length cannot hold the configured letters and digits.
+    fn generate_with_rng<R: RngCore + CryptoRng + ?Sized>(
+        config: &Self::Config,
+        rng: &mut R,
+    ) -> Result<Self::Output, PassForgeError> {
+        let length = config.length.get_length_with_rng(rng);
+        config.check_length(length)?;
+
+        let mut letters = Self::create_letters(length - config.numbers, rng);
+        for position in index::sample(rng, letters.len(), config.capitals) {
+            letters[position] = letters[position].to_ascii_uppercase();
+        }
+
+        let mut is_digit = vec![false; length];
+        for position in index::sample(rng, length, config.numbers) {
+            is_digit[position] = true;
+        }
+        let mut next_letter = 0;
+        let chars: Vec<char> = is_digit
+            .into_iter()
+            .map(|digit| {
+                if digit {
+                    char::from(b'0' + rng.gen_range(0..10))
+                } else {
+                    next_letter += 1;
+                    letters[next_letter - 1]
+                }
+            })
+            .collect();
+        letters.zeroize();
+        Ok(SecretString::from_chars(chars))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    mod pronounceable_generator_tests {
+        use crate::Length;
+
+        use super::*;
+
+        #[test]
+        fn test_pronounceable_generation() {
+            let config = PronounceableConfig::new(Length::Single(14), 2, 3);
+            for _ in 0..50 {
+                let password = PronounceableGenerator::generate(&config).unwrap();
+                let password = password.expose_secret();
+                assert_eq!(password.len(), 14);
+                assert_eq!(password.chars().filter(char::is_ascii_digit).count(), 3);
+                assert_eq!(password.chars().filter(char::is_ascii_uppercase).count(), 2);
+            }
+        }
+
+        #[test]
+        fn test_letters_alternate_between_consonants_and_vowels() {
+            let config = PronounceableConfig::new(Length::Range(8..=16), 0, 0);
+            let passwords = PronounceableGenerator::generate_multiple(&config, 20).unwrap();
+            for password in passwords {
+                let password = password.expose_secret();
+                let is_vowel = |c: char| "aeiou".contains(c);
+                let runs = password
+                    .as_bytes()
+                    .windows(2)
+                    .filter(|pair| is_vowel(pair[0] as char) != is_vowel(pair[1] as char))
+                    .count();
+                // Units are at most two letters long, so the type switches at least every two letters.
+                assert!(runs + 1 >= password.len() / 2);
+            }
+        }
+
+        #[test]
+        fn test_invalid_length() {
+            let config = PronounceableConfig::new(Length::Single(3), 0, 3);
+            assert!(PronounceableGenerator::generate(&config).is_err());
+        }
+    }
+}
@@ -0,0 +1,291 @@
+//! This module implements recovery code generation.
+//!
+//! It provides a `RecoveryCodesGenerator` struct that implements the `Generator` trait,
+//! producing a set of distinct one-time recovery codes, optionally with a salted hash of each
+//! for storage on the server, and a `verify_recovery_code` function that checks a code a user
+//! entered against such a hash.
+
+use alloc::{format, string::String, vec::Vec};
+
+use rand::{CryptoRng, Rng, RngCore};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+
+use crate::config::{RecoveryCodesConfig, TokenEncoding};
+use crate::generator::Generator;
+use crate::{PassForgeError, SecretString};
+
+/// The number of random salt bytes in a recovery code hash.
+const SALT_BYTES: usize = 16;
+
+/// A generated set of recovery codes.
+#[derive(Debug)]
+pub struct RecoveryCodes {
+    /// The codes to hand to the user, all distinct.
+    pub codes: Vec<SecretString>,
+    /// When hashing is enabled, the hash of each code in the same order, written as
+    /// `sha256$<salt>$<digest>` with the salt and digest in hex.
+    pub hashes: Option<Vec<String>>,
+}
+
+/// Struct for generating recovery code sets based on specified configurations.
+pub struct RecoveryCodesGenerator;
+
+impl RecoveryCodesGenerator {
+    /// Draws a single code, with the separator between its groups.
+    fn generate_code<R: RngCore + CryptoRng + ?Sized>(
+        config: &RecoveryCodesConfig,
+        alphabet: &[char],
+        rng: &mut R,
+    ) -> SecretString {
+        let separator_len = config.separator.chars().count();
+        let mut chars =
+            Vec::with_capacity(config.code_length() + (config.groups - 1) * separator_len);
+        for group in 0..config.groups {
+            if group > 0 {
+                chars.extend(config.separator.chars());
+            }
+            for _ in 0..config.group_length {
+                chars.push(alphabet[rng.gen_range(0..alphabet.len())]);
+            }
+        }
+        SecretString::from_chars(chars)
+    }
+}
+
+impl Generator for RecoveryCodesGenerator {
+    type Config = RecoveryCodesConfig;
+    type Output = RecoveryCodes;
+
+    /// Generates a set of recovery codes based on the provided configuration, using the
+    /// supplied random number generator.
+    ///
+    /// A code that is already in the set is drawn again, so the set holds `config.count`
+    /// distinct codes. The hash salts are drawn from the same random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the `RecoveryCodesConfig` specifying generation parameters.
+    /// * `rng` - The cryptographically secure random number generator to draw from.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the `RecoveryCodes` if successful,
+    /// or a `PassForgeError` if an error occurred during generation.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the count is not between 1 and `RecoveryCodesConfig::MAX_COUNT`,
+    /// a code would be empty, the alphabet has fewer than two distinct characters or shares
+    /// characters with the separator, or there are fewer distinct codes than `config.count`.
+    fn generate_with_rng<R: RngCore + CryptoRng + ?Sized>(
+        config: &Self::Config,
+        rng: &mut R,
+    ) -> Result<Self::Output, PassForgeError> {
+        config.check()?;
+        let alphabet: Vec<char> = config.alphabet.chars().collect();
+        let mut codes: Vec<SecretString> = Vec::with_capacity(config.count);
+        while codes.len() < config.count {
+            let code = Self::generate_code(config, &alphabet, rng);
+            if codes
+                .iter()
+                .all(|other| other.expose_secret() != code.expose_secret())
+            {
+                codes.push(code);
+            }
+        }
+
+        let hashes = if config.hashed {
+            let mut hashes = Vec::with_capacity(codes.len());
+            for code in &codes {
+                let mut salt = [0u8; SALT_BYTES];
+                rng.try_fill_bytes(&mut salt)
+                    .map_err(|_| PassForgeError::RandomError)?;
+                hashes.push(hash_code(config, code.expose_secret(), &salt));
+            }
+            Some(hashes)
+        } else {
+            None
+        };
+        Ok(RecoveryCodes { codes, hashes })
+    }
+}
+
+/// Checks a recovery code entered by a user against a stored hash.
+///
+/// The separator and whitespace are ignored, and so is case when the alphabet has no
+/// uppercase letters, so `7KQ4 M2XZ` matches the hash of `7kq4-m2xz`. The digests are
+/// compared in constant time.
+///
+/// # Arguments
+///
+/// * `config` - A reference to the `RecoveryCodesConfig` the code was generated with.
+/// * `code` - The code as entered.
+/// * `hash` - The stored hash, as returned in `RecoveryCodes::hashes`.
+///
+/// # Returns
+///
+/// Returns `Ok(true)` if the code matches the hash, and `Ok(false)` otherwise.
+///
+/// # Errors
+///
+/// Will return an error if the hash is not in the `sha256$<salt>$<digest>` format.
+pub fn verify_recovery_code(
+    config: &RecoveryCodesConfig,
+    code: &str,
+    hash: &str,
+) -> Result<bool, PassForgeError> {
+    let invalid = || PassForgeError::InvalidConfig("Recovery code hash is malformed".into());
+    let mut parts = hash.split('$');
+    let (Some("sha256"), Some(salt), Some(digest), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+    let salt = TokenEncoding::Hex.decode(salt).ok_or_else(invalid)?;
+    let expected = TokenEncoding::Hex.decode(digest).ok_or_else(invalid)?;
+    if expected.len() != 32 {
+        return Err(invalid());
+    }
+
+    let mut normalized = normalize(config, code);
+    let actual = digest_code(&normalized, &salt);
+    normalized.zeroize();
+    let difference = actual
+        .iter()
+        .zip(&expected)
+        .fold(0, |difference, (a, b)| difference | (a ^ b));
+    Ok(difference == 0)
+}
+
+/// Hashes a code as `sha256$<salt>$<digest>`.
+fn hash_code(config: &RecoveryCodesConfig, code: &str, salt: &[u8]) -> String {
+    let mut normalized = normalize(config, code);
+    let digest = digest_code(&normalized, salt);
+    normalized.zeroize();
+    format!(
+        "sha256${}${}",
+        TokenEncoding::Hex.encode(salt),
+        TokenEncoding::Hex.encode(&digest)
+    )
+}
+
+/// Computes SHA-256 over the salt followed by the normalized code.
+fn digest_code(normalized: &str, salt: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(salt);
+    hasher.update(normalized.as_bytes());
+    hasher.finalize().into()
+}
+
+/// Removes the separator and whitespace from a code, and lowercases it when the alphabet has
+/// no uppercase letters.
+fn normalize(config: &RecoveryCodesConfig, code: &str) -> String {
+    let lowercase = !config.alphabet.chars().any(char::is_uppercase);
+    let mut stripped = if config.separator.is_empty() {
+        String::from(code)
+    } else {
+        code.replace(config.separator.as_str(), "")
+    };
+    let mut normalized = String::with_capacity(stripped.len());
+    for c in stripped.chars().filter(|c| !c.is_whitespace()) {
+        if lowercase {
+            normalized.extend(c.to_lowercase());
+        } else {
+            normalized.push(c);
+        }
+    }
+    stripped.zeroize();
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod recovery_codes_generator_tests {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        use super::*;
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_default_codes() {
+            let config = RecoveryCodesConfig::builder().build();
+            let set = RecoveryCodesGenerator::generate(&config).unwrap();
+            assert_eq!(set.codes.len(), 10);
+            assert!(set.hashes.is_none());
+            for code in &set.codes {
+                let code = code.expose_secret();
+                assert_eq!(code.len(), 9);
+                assert_eq!(&code[4..5], "-");
+                assert!(code
+                    .chars()
+                    .filter(|c| *c != '-')
+                    .all(|c| RecoveryCodesConfig::DEFAULT_ALPHABET.contains(c)));
+            }
+        }
+
+        #[test]
+        fn test_codes_are_unique() {
+            // Every one of the 16 possible codes must appear exactly once.
+            let config = RecoveryCodesConfig::builder()
+                .count(16)
+                .groups(2)
+                .group_length(2)
+                .alphabet("ab".into())
+                .separator(" ".into())
+                .build();
+            let mut rng = StdRng::seed_from_u64(24);
+            let set = RecoveryCodesGenerator::generate_with_rng(&config, &mut rng).unwrap();
+            let mut codes: Vec<&str> = set.codes.iter().map(|c| c.expose_secret()).collect();
+            codes.sort_unstable();
+            codes.dedup();
+            assert_eq!(codes.len(), 16);
+            assert!(codes.contains(&"ab ba"));
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_hashes_verify() {
+            let config = RecoveryCodesConfig::builder().count(3).hashed(true).build();
+            let set = RecoveryCodesGenerator::generate(&config).unwrap();
+            let hashes = set.hashes.as_ref().unwrap();
+            assert_eq!(hashes.len(), 3);
+            for (code, hash) in set.codes.iter().zip(hashes) {
+                let code = code.expose_secret();
+                assert!(hash.starts_with("sha256$"));
+                assert_eq!(hash.len(), "sha256$$".len() + 32 + 64);
+                assert!(verify_recovery_code(&config, code, hash).unwrap());
+                let retyped = code.to_uppercase().replace('-', " ");
+                assert!(verify_recovery_code(&config, &retyped, hash).unwrap());
+            }
+            assert!(
+                !verify_recovery_code(&config, set.codes[1].expose_secret(), &hashes[0]).unwrap()
+            );
+            // The same code hashes differently under each salt.
+            assert_ne!(hashes[0], hashes[1]);
+        }
+
+        #[test]
+        fn test_malformed_hash() {
+            let config = RecoveryCodesConfig::builder().build();
+            assert!(verify_recovery_code(&config, "7kq4-m2xz", "7kq4-m2xz").is_err());
+            assert!(verify_recovery_code(&config, "7kq4-m2xz", "sha256$zz$00").is_err());
+            assert!(verify_recovery_code(&config, "7kq4-m2xz", "sha256$00$00").is_err());
+        }
+
+        #[test]
+        fn test_known_hash() {
+            let config = RecoveryCodesConfig::builder().build();
+            let salt = [0u8; SALT_BYTES];
+            let hash = hash_code(&config, "7kq4-m2xz", &salt);
+            assert_eq!(
+                hash,
+                "sha256$00000000000000000000000000000000$\
+                 7209a27203cdd2ecc9b7947478e57578e25bd57e2cee0585d641701fcb96353b"
+            );
+            assert_eq!(hash, hash_code(&config, "7KQ4M2XZ", &salt));
+        }
+    }
+}
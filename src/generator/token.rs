@@ -0,0 +1,281 @@
+//! This module implements API token and raw key generation.
+//!
+//! It provides a `TokenGenerator` struct that implements the `Generator` trait, producing
+//! random bytes in a text encoding with an optional prefix and checksum, and a
+//! `validate_token` function that checks a token against the configuration it was made with.
+
+use alloc::{format, string::String, vec};
+
+use rand::{CryptoRng, RngCore};
+use zeroize::Zeroize;
+
+use crate::config::TokenConfig;
+use crate::generator::Generator;
+use crate::{PassForgeError, SecretString};
+
+/// Struct for generating tokens based on specified configurations.
+pub struct TokenGenerator;
+
+impl TokenGenerator {
+    /// Computes the CRC-32 (IEEE 802.3) checksum of the data.
+    fn crc32(data: &[u8]) -> u32 {
+        let mut crc = u32::MAX;
+        for byte in data {
+            crc ^= *byte as u32;
+            for _ in 0..8 {
+                crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+            }
+        }
+        !crc
+    }
+}
+
+impl Generator for TokenGenerator {
+    type Config = TokenConfig;
+    type Output = SecretString;
+
+    /// Generates a single token based on the provided configuration, drawing the random bytes
+    /// from the supplied random number generator.
+    ///
+    /// The token is the prefix, the encoded bytes and, if enabled, the CRC-32 of the prefix and
+    /// encoded bytes written in the same encoding. The raw bytes are wiped once encoded.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - A reference to the `TokenConfig` specifying generation parameters.
+    /// * `rng` - The cryptographically secure random number generator to draw from.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the generated token as a `SecretString` if successful,
+    /// or a `PassForgeError` if an error occurred during generation.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the number of bytes is not between 1 and
+    /// `TokenConfig::MAX_BYTES`, or if the prefix contains other characters than ASCII
+    /// letters, digits, `_` and `-`.
+    fn generate_with_rng<R: RngCore + CryptoRng + ?Sized>(
+        config: &Self::Config,
+        rng: &mut R,
+    ) -> Result<Self::Output, PassForgeError> {
+        config.check()?;
+        let mut bytes = vec![0u8; config.bytes];
+        rng.try_fill_bytes(&mut bytes)
+            .map_err(|_| PassForgeError::RandomError)?;
+        let mut body = config.encoding.encode(&bytes);
+        bytes.zeroize();
+
+        let checksum_len = if config.checksum {
+            config.encoding.checksum_len()
+        } else {
+            0
+        };
+        let mut token = String::with_capacity(config.prefix.len() + body.len() + checksum_len);
+        token.push_str(&config.prefix);
+        token.push_str(&body);
+        body.zeroize();
+        if config.checksum {
+            let checksum = config
+                .encoding
+                .encode_checksum(Self::crc32(token.as_bytes()));
+            token.push_str(&checksum);
+        }
+        Ok(SecretString::new(token))
+    }
+}
+
+/// Checks that a token has the prefix, encoding, number of bytes and, if enabled, the valid
+/// checksum of the configuration.
+///
+/// This is meant for telling tokens apart from other strings, for example in a secret
+/// scanner. It does not tell whether the token was ever issued.
+///
+/// # Arguments
+///
+/// * `config` - A reference to the `TokenConfig` the token was generated with.
+/// * `token` - The token to check.
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the token matches the configuration, or a
+/// `PassForgeError::InvalidToken` describing the first mismatch.
+///
+/// # Errors
+///
+/// Will also return an error if the configuration itself is invalid.
+pub fn validate_token(config: &TokenConfig, token: &str) -> Result<(), PassForgeError> {
+    config.check()?;
+    let encoding = config.encoding;
+    let rest = token.strip_prefix(config.prefix.as_str()).ok_or_else(|| {
+        PassForgeError::InvalidToken(format!(
+            "Token does not start with the prefix \"{}\"",
+            config.prefix
+        ))
+    })?;
+    let mut rest = encoding.normalize(rest);
+
+    let checksum_len = if config.checksum {
+        encoding.checksum_len()
+    } else {
+        0
+    };
+    let result = match rest.len().checked_sub(checksum_len) {
+        Some(body_len) if rest.is_char_boundary(body_len) => {
+            let (body, checksum) = rest.split_at(body_len);
+            check_body(config, body).and_then(|()| {
+                if !config.checksum {
+                    return Ok(());
+                }
+                let mut text = String::with_capacity(config.prefix.len() + body.len());
+                text.push_str(&config.prefix);
+                text.push_str(body);
+                let expected = encoding.encode_checksum(TokenGenerator::crc32(text.as_bytes()));
+                text.zeroize();
+                if checksum == expected {
+                    Ok(())
+                } else {
+                    Err(PassForgeError::InvalidToken(
+                        "Checksum does not match".into(),
+                    ))
+                }
+            })
+        }
+        _ => Err(PassForgeError::InvalidToken(
+            "Token is too short for its checksum".into(),
+        )),
+    };
+    rest.zeroize();
+    result
+}
+
+/// Checks that the body decodes to exactly the configured number of bytes.
+fn check_body(config: &TokenConfig, body: &str) -> Result<(), PassForgeError> {
+    match config.encoding.decode(body) {
+        Some(mut bytes) => {
+            let len = bytes.len();
+            bytes.zeroize();
+            if len == config.bytes {
+                Ok(())
+            } else {
+                Err(PassForgeError::InvalidToken(format!(
+                    "Token holds {} bytes instead of {}",
+                    len, config.bytes
+                )))
+            }
+        }
+        None => Err(PassForgeError::InvalidToken(format!(
+            "Token is not valid {} text",
+            config.encoding.name()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod token_generator_tests {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        use crate::config::TokenEncoding;
+
+        use super::*;
+
+        #[test]
+        fn test_crc32() {
+            assert_eq!(TokenGenerator::crc32(b""), 0);
+            assert_eq!(TokenGenerator::crc32(b"123456789"), 0xcbf4_3926);
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_token_generation() {
+            let config = TokenConfig::new(16, TokenEncoding::Hex);
+            let token = TokenGenerator::generate(&config).unwrap();
+            let token = token.expose_secret();
+            assert_eq!(token.len(), 32);
+            assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+
+            let config = TokenConfig::builder().build();
+            let token = TokenGenerator::generate(&config).unwrap();
+            assert_eq!(token.expose_secret().len(), 43);
+        }
+
+        #[test]
+        fn test_generated_tokens_validate() {
+            let mut rng = StdRng::seed_from_u64(11);
+            for encoding in TokenEncoding::ALL {
+                for checksum in [false, true] {
+                    let config = TokenConfig::builder()
+                        .bytes(24)
+                        .encoding(encoding)
+                        .prefix("pf_live_".into())
+                        .checksum(checksum)
+                        .build();
+                    for _ in 0..20 {
+                        let token = TokenGenerator::generate_with_rng(&config, &mut rng).unwrap();
+                        let token = token.expose_secret();
+                        assert!(token.starts_with("pf_live_"));
+                        assert!(validate_token(&config, token).is_ok(), "{}", token);
+                    }
+                }
+            }
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_tampered_tokens_fail() {
+            let config = TokenConfig::builder()
+                .encoding(TokenEncoding::Base58)
+                .prefix("pf_test_".into())
+                .checksum(true)
+                .build();
+            let token = TokenGenerator::generate(&config).unwrap();
+            let token = token.expose_secret();
+
+            let mut tampered: String = token.into();
+            let last = tampered.pop().unwrap();
+            tampered.push(if last == 'z' { 'y' } else { 'z' });
+            assert!(matches!(
+                validate_token(&config, &tampered),
+                Err(PassForgeError::InvalidToken(_))
+            ));
+
+            let live = token.replacen("pf_test_", "pf_live_", 1);
+            assert!(validate_token(&config, &live).is_err());
+            assert!(validate_token(&config, "pf_test_").is_err());
+            assert!(validate_token(&config, "pf_test_ü").is_err());
+
+            let unchecked = TokenConfig {
+                checksum: false,
+                ..config.clone()
+            };
+            assert!(validate_token(&unchecked, token).is_err());
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_crockford_aliases_validate() {
+            let config = TokenConfig::builder()
+                .bytes(10)
+                .encoding(TokenEncoding::Base32Crockford)
+                .checksum(true)
+                .build();
+            let token = TokenGenerator::generate(&config).unwrap();
+            let retyped = token
+                .expose_secret()
+                .to_lowercase()
+                .replace('1', "l")
+                .replace('0', "o");
+            assert!(validate_token(&config, &retyped).is_ok());
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_invalid_token_config() {
+            let config = TokenConfig::new(0, TokenEncoding::Base64);
+            assert!(TokenGenerator::generate(&config).is_err());
+        }
+    }
+}
@@ -8,8 +8,28 @@
 //! ## Features
 //!
 //! - Generate passwords with customizable length and character sets
+//! - Describe site password policies, generate conforming passwords and validate existing ones
+//! - Import site password requirements written in the `passwordrules` syntax
 //! - Create passphrases using a word list
+//! - Style passphrases with capitalization, inserted digits and symbols, and random separators
+//! - Check word lists for skipped lines, duplicates and ambiguous words
+//! - Turn physical diceware rolls into passphrases
+//! - Generate passwords following a hashcat-style mask
+//! - Generate pronounceable passwords with exact entropy reporting
+//! - Generate numeric PINs that avoid weak patterns
+//! - Generate API tokens in several encodings, with a prefix and a checksum that
+//!   `validate_token` can verify
+//! - Derive site passwords deterministically from a master secret with Argon2id
+//! - Generate sets of distinct recovery codes, with salted hashes for server-side storage
+//! - Generate HOTP/TOTP secrets, `otpauth://` provisioning URIs and the codes for a secret
 //! - Evaluate password strength using the zxcvbn algorithm
+//! - Return passwords, passphrases and PINs as a `SecretString` that is wiped on drop and redacted
+//!   in `Debug` output
+//! - Report the exact entropy and keyspace of a configuration
+//! - Stream any number of items through an iterator or straight into a writer
+//! - Generate large batches across all cores with the `parallel` feature
+//! - Build for `no_std` + `alloc` targets by disabling the default `std` feature
+//! - Call the generators and strength evaluation from C through the `ffi` feature
 //! - Command-line interface for easy use
 //! - Extendible through `Generator` and `StrengthEvaluator` traits.
 //!
@@ -33,7 +53,7 @@
 //!
 //! let config = PasswordConfig::new(Length::Single(16), true, true, true);
 //! let password = PasswordGenerator::generate(&config).expect("Failed to generate password");
-//! println!("Generated password: {}", password);
+//! println!("Generated password: {}", password.expose_secret());
 //! ```
 //!
 //! Creating a Passphrase
@@ -43,26 +63,86 @@
 //!
 //! let config = PassphraseConfig::new(4, "-".to_string(), WordList::Default);
 //! let passphrase = PassphraseGenerator::generate(&config).expect("Failed to generate passphrase");
-//! println!("Generated passphrase: {}", passphrase);
+//! println!("Generated passphrase: {}", passphrase.expose_secret());
+//! ```
+//!
+//! Generating with a caller-supplied random number generator
+//!
+//! ```
+//! use passforge::{PasswordConfig, PasswordGenerator, Generator, Length};
+//! use rand::rngs::OsRng;
+//!
+//! let config = PasswordConfig::new(Length::Single(16), true, true, true);
+//! let password = PasswordGenerator::generate_with_rng(&config, &mut OsRng)
+//!     .expect("Failed to generate password");
+//! println!("Generated password: {}", password.expose_secret());
+//! ```
+//!
+//! Streaming Passwords
+//!
+//! ```
+//! use passforge::{PasswordConfig, PasswordGenerator, Generator, Length};
+//!
+//! let config = PasswordConfig::new(Length::Single(16), true, true, true);
+//! let mut output = Vec::new();
+//! PasswordGenerator::generate_into(&config, &mut output, 1000).expect("Failed to write passwords");
+//!
+//! let starts_with_digit = PasswordGenerator::iter(&config)
+//!     .expect("Invalid configuration")
+//!     .filter_map(Result::ok)
+//!     .find(|password| password.expose_secret().starts_with(|c: char| c.is_ascii_digit()));
+//! println!("Found: {:?}", starts_with_digit);
+//! ```
+//!
+//! Generating Without the Standard Library
+//!
+//! ```
+//! use passforge::{Generator, PassphraseConfig, PassphraseGenerator, WordList};
+//! use rand::{rngs::StdRng, SeedableRng};
+//!
+//! // On a device, seed from its hardware entropy source instead.
+//! let seed = [7u8; 32];
+//! let mut rng = StdRng::from_seed(seed);
+//! let config = PassphraseConfig::new(5, "-".to_string(), WordList::Default);
+//! let passphrase = PassphraseGenerator::generate_with_rng(&config, &mut rng)
+//!     .expect("Failed to generate passphrase");
+//! println!("Generated passphrase: {}", passphrase.expose_secret());
 //! ```
 //!
 //! Evaluating Password Strength
 //!
 //! ```
-//! use passforge::{ZxcvbnAnalysis, StrengthEvaluator};
+//! use passforge::{SecretString, ZxcvbnAnalysis, StrengthEvaluator};
 //!
-//! let password = "example_password".into();
+//! let password = SecretString::from("example_password");
 //! let strength = ZxcvbnAnalysis::evaluate(&password).expect("Failed to evaluate password");
 //! println!("Password strength: {}", strength);
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 // Re-export main structs and traits for easier access
 pub use config::{
-    Length, PassphraseConfig, PassphraseConfigBuilder, PasswordConfig, PasswordConfigBuilder,
-    WordList,
+    Alphabet, Capitalization, CharClass, ClassCount, DerivedConfig, DerivedConfigBuilder,
+    DerivedTemplate, Insertion, Length, LoadedWordList, MaskConfig, MaskConfigBuilder, MaskSyntax,
+    OtpAlgorithm, OtpConfig, OtpConfigBuilder, PassphraseConfig, PassphraseConfigBuilder,
+    PasswordConfig, PasswordConfigBuilder, PasswordPolicy, PasswordPolicyBuilder, PinConfig,
+    PinConfigBuilder, PolicyViolation, PronounceableConfig, PronounceableConfigBuilder,
+    RecoveryCodesConfig, RecoveryCodesConfigBuilder, Sampling, TokenConfig, TokenConfigBuilder,
+    TokenEncoding, WeakPinPattern, WordList, WordListReport,
 };
 pub use error::PassForgeError;
-pub use generator::{Generator, PassphraseGenerator, PasswordGenerator};
+#[cfg(feature = "parallel")]
+pub use generator::ParallelGenerator;
+pub use generator::{
+    hotp_code, otpauth_uri, totp_code, validate_token, verify_recovery_code, DerivedGenerator,
+    Generator, GeneratorIter, MaskGenerator, OtpGenerator, PassphraseGenerator, PasswordGenerator,
+    PinGenerator, PronounceableGenerator, RecoveryCodes, RecoveryCodesGenerator, TokenGenerator,
+};
+pub use secret::SecretString;
+#[cfg(feature = "std")]
 pub use strength_evaluator::{StrengthEvaluator, ZxcvbnAnalysis};
 
 /// Configuration structures for password and passphrase generation,
@@ -76,7 +156,20 @@ pub mod error;
 /// implementing the Generator trait for different types of generators.
 pub mod generator;
 
+/// A C ABI over the password and passphrase generators and strength evaluation, with the
+/// declarations in `include/passforge.h`.
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+/// Floating point functions that `core` does not provide without `std`.
+#[cfg(not(any(feature = "std", test)))]
+mod math;
+
+/// The zeroizing string type in which generated passwords and passphrases are returned.
+pub mod secret;
+
 /// Password strength evaluation functionality using the zxcvbn algorithm,
 /// providing detailed analysis of password security. Extendible by implementing the
 /// `StrengthEvaluator trait`
+#[cfg(feature = "std")]
 pub mod strength_evaluator;
@@ -0,0 +1,30 @@
+//! Logarithms and exponentials for `no_std` builds.
+//!
+//! `core` has no floating point functions that need a math library, so without `std` they
+//! are provided by `libm` under the same method names. Modules that use them import `Float`
+//! when `std` is disabled, and keep calling the inherent methods otherwise. Tests always link
+//! `std`, whose inherent methods take precedence, so the trait is left out of test builds.
+
+/// The floating point methods used by the entropy and sampling calculations.
+pub(crate) trait Float {
+    /// Returns the natural logarithm.
+    fn ln(self) -> Self;
+    /// Returns the base 2 logarithm.
+    fn log2(self) -> Self;
+    /// Returns `e` raised to the given power.
+    fn exp(self) -> Self;
+}
+
+impl Float for f64 {
+    fn ln(self) -> Self {
+        libm::log(self)
+    }
+
+    fn log2(self) -> Self {
+        libm::log2(self)
+    }
+
+    fn exp(self) -> Self {
+        libm::exp(self)
+    }
+}
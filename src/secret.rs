@@ -0,0 +1,101 @@
+//! This module defines the `SecretString` type returned by the generators.
+//!
+//! A generated password should not outlive its use, so `SecretString` wipes its memory when
+//! it is dropped, never prints its contents through `Debug`, and cannot be cloned. The value
+//! can only be read through `expose_secret`, which makes every place that handles the plain
+//! text easy to find.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// A string holding a password or passphrase, which is zeroized when dropped.
+pub struct SecretString(String);
+
+impl SecretString {
+    /// Wraps a string, taking ownership of its buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret` - The string to protect.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `SecretString` that will wipe the buffer when dropped.
+    pub fn new(secret: String) -> Self {
+        Self(secret)
+    }
+
+    /// Collects the characters into a `SecretString` and wipes the buffer they were held in.
+    /// The string is allocated at its final size, so growing it leaves no partial copies.
+    pub(crate) fn from_chars(mut chars: Vec<char>) -> Self {
+        let mut secret = String::with_capacity(chars.iter().map(|c| c.len_utf8()).sum());
+        secret.extend(chars.iter());
+        chars.zeroize();
+        Self(secret)
+    }
+
+    /// Returns the secret as a string slice.
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        Self::new(secret)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(secret: &str) -> Self {
+        Self::new(secret.into())
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretString([REDACTED])")
+    }
+}
+
+impl Zeroize for SecretString {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretString {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod secret_string_tests {
+        use alloc::format;
+
+        use super::*;
+
+        #[test]
+        fn test_debug_is_redacted() {
+            let secret = SecretString::from("hunter2");
+            assert_eq!(format!("{:?}", secret), "SecretString([REDACTED])");
+            assert!(!format!("{:?}", Some(secret)).contains("hunter2"));
+        }
+
+        #[test]
+        fn test_zeroize_wipes_contents() {
+            let mut secret = SecretString::from("hunter2");
+            assert_eq!(secret.expose_secret(), "hunter2");
+            secret.zeroize();
+            assert!(secret.expose_secret().is_empty());
+        }
+    }
+}
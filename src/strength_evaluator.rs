@@ -13,7 +13,8 @@ use crate::PassForgeError;
 /// This trait allows for a common interface across different types of strength evaluators,
 /// enabling easy swapping and extension of evaluation algorithms.
 pub trait StrengthEvaluator {
-    /// The input type for the strength evaluator.
+    /// The input type for the strength evaluator, such as the `SecretString` produced by the
+    /// generators.
     type Input;
     /// The output type produced by the strength evaluator, which must implement `Display`.
     type Output: Display;
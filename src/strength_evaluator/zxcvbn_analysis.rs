@@ -5,7 +5,7 @@
 
 use zxcvbn::zxcvbn;
 
-use crate::{strength_evaluator::StrengthEvaluator, PassForgeError};
+use crate::{strength_evaluator::StrengthEvaluator, PassForgeError, SecretString};
 
 /// Struct for evaluating password strength using the zxcvbn algorithm.
 pub struct ZxcvbnAnalysis;
@@ -16,7 +16,7 @@ impl ZxcvbnAnalysis {
 }
 
 impl StrengthEvaluator for ZxcvbnAnalysis {
-    type Input = String;
+    type Input = SecretString;
     type Output = String;
 
     /// Checks if the password passes the minimum strength threshold.
@@ -34,6 +34,7 @@ impl StrengthEvaluator for ZxcvbnAnalysis {
     ///
     /// Will return an error if the input password is empty.
     fn passes_threshold(input: &Self::Input) -> Result<bool, PassForgeError> {
+        let input = input.expose_secret();
         if input.is_empty() {
             return Err(PassForgeError::InvalidLength(
                 "Password cannot be empty".into(),
@@ -57,6 +58,7 @@ impl StrengthEvaluator for ZxcvbnAnalysis {
     ///
     /// Will return an error if the input password is empty.
     fn evaluate(input: &Self::Input) -> Result<Self::Output, PassForgeError> {
+        let input = input.expose_secret();
         if input.is_empty() {
             return Err(PassForgeError::InvalidLength(
                 "Input password cannot be empty".into(),
@@ -74,25 +76,25 @@ impl StrengthEvaluator for ZxcvbnAnalysis {
 #[cfg(test)]
 mod tests {
     mod strength_evaluator_tests {
-        use crate::{StrengthEvaluator, ZxcvbnAnalysis};
+        use crate::{SecretString, StrengthEvaluator, ZxcvbnAnalysis};
 
         #[test]
         fn test_zxcvbn_evaluation() {
-            let password = "w".to_string();
+            let password = SecretString::from("w");
             let evaluation = ZxcvbnAnalysis::evaluate(&password).unwrap();
             assert!(evaluation.contains("Score: 0/4"));
 
-            let password = "StrongP@ssw0rdsAreAmazing@#!!!@#$!".to_string();
+            let password = SecretString::from("StrongP@ssw0rdsAreAmazing@#!!!@#$!");
             let evaluation = ZxcvbnAnalysis::evaluate(&password).unwrap();
             assert!(evaluation.contains("Score: 4/4"));
         }
 
         #[test]
         fn test_passes_threshold() {
-            let weak_password = "weak".to_string();
+            let weak_password = SecretString::from("weak");
             assert!(!ZxcvbnAnalysis::passes_threshold(&weak_password).unwrap());
 
-            let strong_password = "StrongP@ssw0rd!".to_string();
+            let strong_password = SecretString::from("StrongP@ssw0rd!");
             assert!(ZxcvbnAnalysis::passes_threshold(&strong_password).unwrap());
         }
     }
@@ -81,6 +81,51 @@ fn test_cli_custom_passphrase() {
         .stdout(predicate::str::is_match(r"^(\w+(_\w+){4})\n$").unwrap());
 }
 
+#[test]
+fn test_cli_styled_passphrase() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args([
+        "--passphrase",
+        "--words",
+        "3",
+        "--capitalize",
+        "first-letter",
+        "--add-digits",
+        "1",
+        "--add-symbols",
+        "1",
+        "--insert-symbols-set",
+        "!",
+        "--insert",
+        "tokens",
+        "--random-separators",
+        ".,",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::is_match(r"^[A-Z0-9!][a-z-]*([.,][A-Z0-9!][a-z-]*){4}\n$").unwrap());
+
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--passphrase", "--capitalize", "title"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_cli_word_list_name() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--passphrase", "--word-list-name", "default"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"^(\w+(-\w+){5})\n$").unwrap());
+
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--passphrase", "--word-list-name", "klingon"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown word list"));
+}
+
 #[test]
 fn test_cli_password_length_range() {
     let mut cmd = Command::cargo_bin("passforge").unwrap();
@@ -127,3 +172,512 @@ fn test_cli_extreme_length() {
         predicate::str::is_match(r"^[a-zA-Z0-9!@#$%^&*()-_=+\[\]{}|;:,.<>?]{1000}\n$").unwrap(),
     );
 }
+
+#[test]
+fn test_cli_class_minimums() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--length", "8", "--min-digits", "8"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"^[0-9]{8}\n$").unwrap());
+}
+
+#[test]
+fn test_cli_unsatisfiable_class_minimums() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--length", "4", "--min-digits", "3", "--min-symbols", "3"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Error: Invalid password length"));
+}
+
+#[test]
+fn test_cli_charset() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--charset", "ABCDEF0123456789"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"^[A-F0-9]{18}\n$").unwrap());
+}
+
+#[test]
+fn test_cli_symbols_set_and_exclude_chars() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--symbols-set", "!@#$", "--exclude-chars", "aeiouAEIOU"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(r"^[b-df-hj-np-tv-zB-DF-HJ-NP-TV-Z0-9!@#$]{18}\n$").unwrap(),
+        );
+}
+
+#[test]
+fn test_cli_no_ambiguous() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--no-ambiguous", "--length", "200"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r#"^[^0Oo1lIi|!5S2Z8B`'"]{200}\n$"#).unwrap());
+}
+
+#[test]
+fn test_cli_positional_constraints() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args([
+        "--charset",
+        "ab",
+        "--no-lowercase",
+        "--max-consecutive",
+        "1",
+        "--start-with-letter",
+        "--length",
+        "6",
+    ])
+    .assert()
+    .failure();
+
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args([
+        "--charset",
+        "a1",
+        "--max-consecutive",
+        "1",
+        "--start-with-letter",
+    ])
+    .args(["--length", "7"])
+    .assert()
+    .success()
+    .stdout(predicate::str::is_match(r"^a1a1a1a\n$").unwrap());
+}
+
+#[test]
+fn test_cli_rules() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args([
+        "--rules",
+        "minlength: 20; maxlength: 20; required: lower; required: digit; allowed: [-_]",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::is_match(r"^[a-z0-9_-]{20}\n$").unwrap());
+
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--rules", "minlength: twelve"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid password rules"));
+}
+
+#[test]
+fn test_cli_show_entropy() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--mask", "?d?d?d?d", "--show-entropy"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(r"^[0-9]{4}\nEntropy: 13\.29 bits\nKeyspace: 10000\n$")
+                .unwrap(),
+        );
+
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--passphrase", "--words", "2", "--show-entropy"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Keyspace: 60458400\n"));
+
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args([
+        "--passphrase",
+        "--words",
+        "2",
+        "--with-replacement",
+        "--show-entropy",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Keyspace: 60466176\n"));
+}
+
+#[test]
+fn test_cli_dice() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--dice", "11111 66666", "--words", "2"])
+        .assert()
+        .success()
+        .stdout("abacus-zoom\n");
+
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--dice", "--words", "2", "--separator", " "])
+        .write_stdin("11111\n66666\n")
+        .assert()
+        .success()
+        .stdout("abacus zoom\n");
+
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--dice", "11111 66667", "--words", "2"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid dice roll"));
+}
+
+#[test]
+fn test_cli_mask() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--mask", "?u?l?l?l-?d?d?d?d", "--count", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"^([A-Z][a-z]{3}-[0-9]{4}\n){2}$").unwrap());
+}
+
+#[test]
+fn test_cli_mask_custom_charset() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--mask", "?1?1?1?1", "-1", "?dxyz"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"^[0-9xyz]{4}\n$").unwrap());
+}
+
+#[test]
+fn test_cli_mask_template() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--mask", "Aaaa-9999-aaaa", "--template", "--show-entropy"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"^[A-Z][a-z]{3}-[0-9]{4}-[a-z]{4}\n").unwrap())
+        .stdout(predicate::str::contains("Keyspace: 2088270645760000\n"));
+}
+
+#[test]
+fn test_cli_invalid_mask() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--mask", "?q"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Error: Invalid configuration"));
+}
+
+#[test]
+fn test_cli_pronounceable() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args([
+        "--pronounceable",
+        "--length",
+        "10",
+        "--no-capitals",
+        "--no-numbers",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::is_match(r"^[a-z]{10}\n$").unwrap());
+}
+
+#[test]
+fn test_cli_pronounceable_exact_counts() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    let output = cmd
+        .args([
+            "--pronounceable",
+            "--length",
+            "12",
+            "--capitals",
+            "3",
+            "--numbers",
+            "2",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let password = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(password.trim_end().chars().count(), 12);
+    assert_eq!(password.chars().filter(char::is_ascii_uppercase).count(), 3);
+    assert_eq!(password.chars().filter(char::is_ascii_digit).count(), 2);
+
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--pronounceable", "--min-uppercase", "3"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_cli_pin() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--pin", "4", "--count", "3"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"^([0-9]{4}\n){3}$").unwrap());
+}
+
+#[test]
+fn test_cli_pin_default_length() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.arg("--pin")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"^[0-9]{6}\n$").unwrap());
+}
+
+#[test]
+fn test_cli_wordlist_check() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["wordlist", "check", "resources/eff_large_wordlist.txt"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Words: 7776\n"))
+        .stdout(predicate::str::contains("Verdict: PASS\n"));
+
+    let path = std::env::temp_dir().join("passforge_cli_wordlist_check.txt");
+    std::fs::write(&path, "apple\npear tree here\napple\napplesauce\n").unwrap();
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["wordlist", "check"])
+        .arg(&path)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("line 2: \"pear tree here\""))
+        .stdout(predicate::str::contains("\"apple\" on lines 1, 3"))
+        .stdout(predicate::str::contains(
+            "\"apple\" is a prefix of \"applesauce\"",
+        ))
+        .stdout(predicate::str::contains("Verdict: FAIL\n"));
+}
+
+#[test]
+fn test_cli_wordlist_check_invalid_utf8() {
+    let path = std::env::temp_dir().join("passforge_cli_wordlist_check_utf8.txt");
+    std::fs::write(&path, b"apple\npear\n\xff\xfe bad\nplum\nplum\n").unwrap();
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["wordlist", "check"])
+        .arg(&path)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Words: 3\n"))
+        .stdout(predicate::str::contains("Skipped lines: 1\n"))
+        .stdout(predicate::str::contains("line 3: \"\u{fffd}\u{fffd} bad\""))
+        .stdout(predicate::str::contains("\"plum\" on lines 4, 5"))
+        .stdout(predicate::str::contains("Verdict: FAIL\n"));
+}
+
+#[test]
+fn test_cli_token() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.arg("--token")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"^[A-Za-z0-9_-]{43}\n$").unwrap());
+
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args([
+        "--token",
+        "16",
+        "--encoding",
+        "hex",
+        "--prefix",
+        "pf_live_",
+        "--checksum",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::is_match(r"^pf_live_[0-9a-f]{40}\n$").unwrap());
+}
+
+#[test]
+fn test_cli_token_check() {
+    let args = ["--encoding", "base58", "--prefix", "pf_test_", "--checksum"];
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    let output = cmd
+        .arg("--token")
+        .args(args)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let token = String::from_utf8(output).unwrap().trim_end().to_string();
+
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["token", "check", &token])
+        .args(args)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Verdict: PASS\n"));
+
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["token", "check"])
+        .args(args)
+        .write_stdin(format!("{}\n", token.replacen("pf_test_", "pf_live_", 1)))
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Verdict: FAIL\n"));
+}
+
+#[test]
+fn test_cli_otp_new() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["otp", "new", "--issuer", "ACME Co", "--account", "svc-backup"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(
+                r"^Secret: [A-Z2-7]{32}\nURI: otpauth://totp/ACME%20Co:svc-backup\?secret=[A-Z2-7]{32}&issuer=ACME%20Co&algorithm=SHA1&digits=6&period=30\n$",
+            )
+            .unwrap(),
+        );
+}
+
+#[test]
+fn test_cli_otp_code() {
+    // The RFC 6238 SHA-1 seed, "12345678901234567890", in base32.
+    let secret = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args([
+        "otp",
+        "code",
+        secret,
+        "--time",
+        "1111111109",
+        "--digits",
+        "8",
+    ])
+    .assert()
+    .success()
+    .stdout("07081804\n");
+
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["otp", "code", "--counter", "1"])
+        .write_stdin(format!("{}\n", secret))
+        .assert()
+        .success()
+        .stdout("287082\n");
+
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["otp", "code", "not base32!"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("OTP secret is not valid base32"));
+}
+
+#[test]
+fn test_cli_recovery_codes() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    let output = cmd
+        .arg("--recovery-codes")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+    let codes: Vec<&str> = output.lines().collect();
+    assert_eq!(codes.len(), 10);
+    assert!(codes
+        .iter()
+        .all(|code| code.len() == 9 && &code[4..5] == "-"));
+    let mut unique = codes.clone();
+    unique.sort_unstable();
+    unique.dedup();
+    assert_eq!(unique.len(), 10);
+
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args([
+        "--recovery-codes",
+        "3",
+        "--groups",
+        "3",
+        "--group-length",
+        "2",
+    ])
+    .arg("--hash-codes")
+    .assert()
+    .success()
+    .stdout(
+        predicate::str::is_match(
+            r"^([a-z2-9]{2}-[a-z2-9]{2}-[a-z2-9]{2}\tsha256\$[0-9a-f]{32}\$[0-9a-f]{64}\n){3}$",
+        )
+        .unwrap(),
+    );
+}
+
+#[test]
+fn test_cli_derive() {
+    // The same vector as the library test with the default Argon2id parameters.
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["derive", "example.com", "--login", "alice"])
+        .write_stdin("correct horse battery staple\n")
+        .assert()
+        .success()
+        .stdout("xBzaFaH;B_w}kS>20.\n");
+
+    // Only the line ending is removed from a piped master secret.
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["derive", "example.com", "--login", "alice"])
+        .write_stdin("correct horse battery staple\r\n")
+        .assert()
+        .success()
+        .stdout("xBzaFaH;B_w}kS>20.\n");
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["derive", "example.com", "--login", "alice"])
+        .write_stdin("correct horse battery staple \n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("xBzaFaH;B_w}kS>20.").not());
+
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["derive", "example.com"])
+        .write_stdin("\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Master secret cannot be empty"));
+}
+
+#[test]
+fn test_cli_derive_restrictions() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    let output = cmd
+        .args([
+            "derive",
+            "example.com",
+            "--exclude-chars",
+            "aeiou",
+            "--no-ambiguous",
+        ])
+        .args(["--min-digits", "4", "--no-symbols"])
+        .write_stdin("correct horse battery staple\n")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let password = String::from_utf8(output.stdout).unwrap();
+    let password = password.trim_end();
+    assert_eq!(password.len(), 18);
+    assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+    assert!(!password.chars().any(|c| "aeiou01lIO".contains(c)));
+    assert!(password.chars().filter(char::is_ascii_digit).count() >= 4);
+
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["derive", "example.com", "--passphrase", "--separator", ""])
+        .write_stdin("correct horse battery staple\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("needs a separator"));
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_cli_threads() {
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    let output = cmd
+        .args(["--count", "3000", "--threads", "4", "--length", "12"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(output.lines().count(), 3000);
+    assert!(output.lines().all(|line| line.chars().count() == 12));
+
+    let mut cmd = Command::cargo_bin("passforge").unwrap();
+    cmd.args(["--passphrase", "--count", "5", "--threads", "0"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"^([a-z-]+\n){5}$").unwrap());
+}
@@ -0,0 +1,207 @@
+//! Calls the library through the C declarations of `include/passforge.h`, as a C caller would.
+#![cfg(feature = "ffi")]
+
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+// Linked for the exported symbols declared below.
+extern crate passforge;
+
+#[repr(C)]
+struct PasswordConfig {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+struct PassphraseConfig {
+    _private: [u8; 0],
+}
+
+const OK: i32 = 0;
+const NULL_POINTER: i32 = 1;
+const IO: i32 = 3;
+const INVALID_LENGTH: i32 = 4;
+const INVALID_WORD_COUNT: i32 = 5;
+const WORD_LIST: i32 = 8;
+
+extern "C" {
+    fn passforge_password_config_new(
+        length: usize,
+        capitals: bool,
+        numbers: bool,
+        symbols: bool,
+    ) -> *mut PasswordConfig;
+    fn passforge_password_config_set_length_range(
+        config: *mut PasswordConfig,
+        min: usize,
+        max: usize,
+    ) -> i32;
+    fn passforge_password_config_set_excluded_chars(
+        config: *mut PasswordConfig,
+        chars: *const c_char,
+    ) -> i32;
+    fn passforge_password_config_free(config: *mut PasswordConfig);
+    fn passforge_password_generate(config: *const PasswordConfig, out: *mut *mut c_char) -> i32;
+    fn passforge_passphrase_config_new(
+        words: usize,
+        separator: *const c_char,
+    ) -> *mut PassphraseConfig;
+    fn passforge_passphrase_config_set_word_list_file(
+        config: *mut PassphraseConfig,
+        path: *const c_char,
+    ) -> i32;
+    fn passforge_passphrase_config_set_word_list_name(
+        config: *mut PassphraseConfig,
+        name: *const c_char,
+    ) -> i32;
+    fn passforge_passphrase_config_free(config: *mut PassphraseConfig);
+    fn passforge_passphrase_generate(config: *const PassphraseConfig, out: *mut *mut c_char)
+        -> i32;
+    fn passforge_evaluate_strength(password: *const c_char, out: *mut *mut c_char) -> i32;
+    fn passforge_passes_threshold(password: *const c_char, passes: *mut bool) -> i32;
+    fn passforge_string_free(value: *mut c_char);
+    fn passforge_last_error_message() -> *const c_char;
+}
+
+/// Copies and frees a string returned by the library.
+unsafe fn take_string(value: *mut c_char) -> String {
+    let copy = CStr::from_ptr(value).to_str().unwrap().to_string();
+    passforge_string_free(value);
+    copy
+}
+
+unsafe fn last_error() -> String {
+    CStr::from_ptr(passforge_last_error_message())
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn test_ffi_password_generation() {
+    unsafe {
+        let config = passforge_password_config_new(20, true, true, false);
+        assert!(!config.is_null());
+        let excluded = CString::new("abc").unwrap();
+        assert_eq!(
+            passforge_password_config_set_excluded_chars(config, excluded.as_ptr()),
+            OK
+        );
+
+        let mut out = ptr::null_mut();
+        assert_eq!(passforge_password_generate(config, &mut out), OK);
+        assert!(passforge_last_error_message().is_null());
+        let password = take_string(out);
+        assert_eq!(password.len(), 20);
+        assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert!(!password.contains(['a', 'b', 'c']));
+
+        assert_eq!(
+            passforge_password_config_set_length_range(config, 8, 12),
+            OK
+        );
+        let mut out = ptr::null_mut();
+        assert_eq!(passforge_password_generate(config, &mut out), OK);
+        assert!((8..=12).contains(&take_string(out).len()));
+
+        assert_eq!(
+            passforge_password_config_set_length_range(config, 12, 8),
+            INVALID_LENGTH
+        );
+        assert!(last_error().contains("Maximum length"));
+        passforge_password_config_free(config);
+    }
+}
+
+#[test]
+fn test_ffi_passphrase_generation() {
+    unsafe {
+        let separator = CString::new(" ").unwrap();
+        let config = passforge_passphrase_config_new(5, separator.as_ptr());
+        assert!(!config.is_null());
+        let mut out = ptr::null_mut();
+        assert_eq!(passforge_passphrase_generate(config, &mut out), OK);
+        assert_eq!(take_string(out).split(' ').count(), 5);
+
+        let path = std::env::temp_dir().join("passforge_ffi_words.txt");
+        std::fs::write(&path, "alpha\nbravo\ncharlie\ndelta\necho\n").unwrap();
+        let path = CString::new(path.to_str().unwrap()).unwrap();
+        assert_eq!(
+            passforge_passphrase_config_set_word_list_file(config, path.as_ptr()),
+            OK
+        );
+        let mut out = ptr::null_mut();
+        assert_eq!(passforge_passphrase_generate(config, &mut out), OK);
+        let mut words: Vec<String> = take_string(out).split(' ').map(String::from).collect();
+        words.sort();
+        assert_eq!(words, ["alpha", "bravo", "charlie", "delta", "echo"]);
+
+        let missing = CString::new("/nonexistent/passforge_words.txt").unwrap();
+        assert_eq!(
+            passforge_passphrase_config_set_word_list_file(config, missing.as_ptr()),
+            IO
+        );
+        let unknown = CString::new("klingon").unwrap();
+        assert_eq!(
+            passforge_passphrase_config_set_word_list_name(config, unknown.as_ptr()),
+            WORD_LIST
+        );
+        passforge_passphrase_config_free(config);
+
+        let config = passforge_passphrase_config_new(1, separator.as_ptr());
+        let mut out = ptr::null_mut();
+        assert_eq!(
+            passforge_passphrase_generate(config, &mut out),
+            INVALID_WORD_COUNT
+        );
+        assert!(out.is_null());
+        passforge_passphrase_config_free(config);
+    }
+}
+
+#[test]
+fn test_ffi_strength_evaluation() {
+    unsafe {
+        let weak = CString::new("password").unwrap();
+        let mut out = ptr::null_mut();
+        assert_eq!(passforge_evaluate_strength(weak.as_ptr(), &mut out), OK);
+        assert!(take_string(out).contains("Score: 0/4"));
+
+        let strong = CString::new("StrongP@ssw0rdsAreAmazing@#!!!@#$!").unwrap();
+        let mut passes = false;
+        assert_eq!(passforge_passes_threshold(strong.as_ptr(), &mut passes), OK);
+        assert!(passes);
+    }
+}
+
+#[test]
+fn test_ffi_null_arguments() {
+    unsafe {
+        assert!(passforge_passphrase_config_new(4, ptr::null()).is_null());
+        assert!(last_error().contains("separator cannot be null"));
+
+        let mut out = ptr::null_mut();
+        assert_eq!(
+            passforge_password_generate(ptr::null(), &mut out),
+            NULL_POINTER
+        );
+        assert_eq!(
+            passforge_evaluate_strength(ptr::null(), &mut out),
+            NULL_POINTER
+        );
+        passforge_password_config_free(ptr::null_mut());
+        passforge_passphrase_config_free(ptr::null_mut());
+        passforge_string_free(ptr::null_mut());
+    }
+}
+
+#[test]
+fn test_ffi_header_is_up_to_date() {
+    let generated = concat!(env!("OUT_DIR"), "/passforge.h");
+    assert!(
+        include_str!(concat!(env!("OUT_DIR"), "/passforge.h"))
+            == include_str!("../include/passforge.h"),
+        "include/passforge.h is out of date, copy {} over it",
+        generated
+    );
+}
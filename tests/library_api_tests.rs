@@ -1,6 +1,11 @@
 use passforge::{
-    Generator, Length, PassphraseConfig, PassphraseGenerator, PasswordConfig, PasswordGenerator,
-    StrengthEvaluator, WordList, ZxcvbnAnalysis,
+    validate_token, Generator, Length, LoadedWordList, PassphraseConfig, PassphraseGenerator,
+    PasswordConfig, PasswordGenerator, SecretString, StrengthEvaluator, TokenConfig, TokenEncoding,
+    TokenGenerator, WordList, ZxcvbnAnalysis,
+};
+use rand::{
+    rngs::{OsRng, StdRng},
+    SeedableRng,
 };
 
 #[test]
@@ -9,7 +14,7 @@ fn test_password_generation_api() {
 
     let password = PasswordGenerator::generate(&config).expect("Failed to generate password");
 
-    assert_eq!(password.len(), 16);
+    assert_eq!(password.expose_secret().len(), 16);
 }
 
 #[test]
@@ -18,6 +23,7 @@ fn test_password_generation_no_symbols() {
 
     let password = PasswordGenerator::generate(&config).expect("Failed to generate password");
 
+    let password = password.expose_secret();
     assert_eq!(password.len(), 16);
     assert!(!password
         .chars()
@@ -30,7 +36,7 @@ fn test_password_generation_range_length() {
 
     let password = PasswordGenerator::generate(&config).expect("Failed to generate password");
 
-    assert!(password.len() >= 10 && password.len() <= 20);
+    assert!((10..=20).contains(&password.expose_secret().len()));
 }
 
 #[test]
@@ -42,17 +48,32 @@ fn test_multiple_password_generation() {
 
     assert_eq!(passwords.len(), 5);
     for password in passwords {
-        assert_eq!(password.len(), 16);
+        assert_eq!(password.expose_secret().len(), 16);
     }
 }
 
+#[test]
+fn test_password_generation_with_supplied_rng() {
+    let config = PasswordConfig::new(Length::Single(16), true, true, true);
+
+    let seeded = PasswordGenerator::generate_with_rng(&config, &mut StdRng::seed_from_u64(1))
+        .expect("Failed to generate password");
+    let reseeded = PasswordGenerator::generate_with_rng(&config, &mut StdRng::seed_from_u64(1))
+        .expect("Failed to generate password");
+    let from_os = PasswordGenerator::generate_with_rng(&config, &mut OsRng)
+        .expect("Failed to generate password");
+
+    assert_eq!(seeded.expose_secret(), reseeded.expose_secret());
+    assert_eq!(from_os.expose_secret().len(), 16);
+}
+
 #[test]
 fn test_passphrase_generation_api() {
     let config = PassphraseConfig::new(4, "-".to_string(), WordList::Default);
 
     let passphrase = PassphraseGenerator::generate(&config).expect("Failed to generate passphrase");
 
-    let words: Vec<&str> = passphrase.split('-').collect();
+    let words: Vec<&str> = passphrase.expose_secret().split('-').collect();
     assert_eq!(words.len(), 4);
 }
 
@@ -62,18 +83,88 @@ fn test_passphrase_custom_separator() {
 
     let passphrase = PassphraseGenerator::generate(&config).expect("Failed to generate passphrase");
 
-    let words: Vec<&str> = passphrase.split('_').collect();
+    let words: Vec<&str> = passphrase.expose_secret().split('_').collect();
     assert_eq!(words.len(), 4);
 }
 
+#[test]
+fn test_loaded_word_list_api() {
+    let words = LoadedWordList::from_words(["alpha", "bravo", "charlie", "delta", "alpha"])
+        .expect("Failed to load word list");
+    assert_eq!(words.len(), 4);
+
+    let config = PassphraseConfig::new(3, " ".to_string(), words.into());
+    let passphrases =
+        PassphraseGenerator::generate_multiple(&config, 5).expect("Failed to generate passphrases");
+    for passphrase in passphrases {
+        assert!(passphrase
+            .expose_secret()
+            .split(' ')
+            .all(|word| ["alpha", "bravo", "charlie", "delta"].contains(&word)));
+    }
+}
+
+#[test]
+fn test_generator_iter_api() {
+    let config = PasswordConfig::new(Length::Single(12), true, true, false);
+    let password = PasswordGenerator::iter(&config)
+        .expect("Failed to prepare generator")
+        .map(|password| password.expect("Failed to generate password"))
+        .find(|password| password.expose_secret().starts_with('a'))
+        .expect("Iterator ended");
+    assert!(password.expose_secret().starts_with('a'));
+
+    let config = PassphraseConfig::new(4, "-".to_string(), WordList::Default);
+    let streamed: Vec<String> =
+        PassphraseGenerator::iter_with_rng(&config, StdRng::seed_from_u64(3))
+            .expect("Failed to prepare generator")
+            .take(5)
+            .map(|passphrase| Ok(passphrase?.expose_secret().to_string()))
+            .collect::<Result<_, passforge::PassForgeError>>()
+            .expect("Failed to generate passphrases");
+    let collected: Vec<String> =
+        PassphraseGenerator::generate_multiple_with_rng(&config, 5, &mut StdRng::seed_from_u64(3))
+            .expect("Failed to generate passphrases")
+            .iter()
+            .map(|passphrase| passphrase.expose_secret().to_string())
+            .collect();
+    assert_eq!(streamed, collected);
+}
+
+#[test]
+fn test_generate_into_api() {
+    let config = PasswordConfig::new(Length::Single(16), true, true, true);
+    let mut output = Vec::new();
+    PasswordGenerator::generate_into(&config, &mut output, 1000)
+        .expect("Failed to write passwords");
+    let output = String::from_utf8(output).expect("Output is not UTF-8");
+    assert_eq!(output.lines().count(), 1000);
+    assert!(output.lines().all(|line| line.chars().count() == 16));
+
+    let config = PassphraseConfig::new(0, "-".to_string(), WordList::Default);
+    assert!(PassphraseGenerator::generate_into(&config, &mut Vec::new(), 10).is_err());
+}
+
+#[test]
+fn test_generated_secrets_are_redacted() {
+    let config = PassphraseConfig::new(4, "-".to_string(), WordList::Default);
+
+    let passphrase = PassphraseGenerator::generate(&config).expect("Failed to generate passphrase");
+
+    assert_eq!(format!("{:?}", passphrase), "SecretString([REDACTED])");
+    let evaluation =
+        ZxcvbnAnalysis::evaluate(&passphrase).expect("Failed to evaluate passphrase strength");
+    assert!(evaluation.starts_with("Score: "));
+}
+
 #[test]
 fn test_strength_evaluation() {
-    let password = "w".to_string();
+    let password = SecretString::from("w");
     let evaluation =
         ZxcvbnAnalysis::evaluate(&password).expect("Failed to evaluate password strength");
     assert!(evaluation.contains("Score: 0/4"));
 
-    let password = "StrongP@ssw0rd!AreAmazing!@#!$!".to_string();
+    let password = SecretString::from("StrongP@ssw0rd!AreAmazing!@#!$!");
     let evaluation =
         ZxcvbnAnalysis::evaluate(&password).expect("Failed to evaluate password strength");
     assert!(evaluation.contains("Score: 4/4"));
@@ -81,9 +172,64 @@ fn test_strength_evaluation() {
 
 #[test]
 fn test_strength_threshold() {
-    let weak_password = "weak".to_string();
+    let weak_password = SecretString::from("weak");
     assert!(!ZxcvbnAnalysis::passes_threshold(&weak_password).expect("Failed to check threshold"));
 
-    let strong_password = "StrongP@ssw0rd!".to_string();
+    let strong_password = SecretString::from("StrongP@ssw0rd!");
     assert!(ZxcvbnAnalysis::passes_threshold(&strong_password).expect("Failed to check threshold"));
 }
+
+#[test]
+fn test_password_entropy_api() {
+    let config = PasswordConfig::new(Length::Single(10), false, true, false);
+    assert_eq!(config.keyspace().unwrap(), Some(36u128.pow(10)));
+    assert!((config.entropy_bits().unwrap() - 10.0 * 36f64.log2()).abs() < 1e-9);
+
+    // A length range adds the choice of length to the entropy.
+    let range = PasswordConfig::new(Length::Range(10..=11), false, true, false);
+    let expected = 1.0 + 10.5 * 36f64.log2();
+    assert!((range.entropy_bits().unwrap() - expected).abs() < 1e-9);
+    assert_eq!(
+        range.keyspace().unwrap(),
+        Some(36u128.pow(10) + 36u128.pow(11))
+    );
+
+    // Requiring a digit removes the all-letter passwords.
+    let constrained = PasswordConfig::builder()
+        .length(Length::Single(10))
+        .capitals(false)
+        .symbols(false)
+        .min_numbers(1)
+        .build();
+    let keyspace = 36u128.pow(10) - 26u128.pow(10);
+    assert_eq!(constrained.keyspace().unwrap(), Some(keyspace));
+    assert!((constrained.entropy_bits().unwrap() - (keyspace as f64).log2()).abs() < 1e-9);
+}
+
+#[test]
+fn test_passphrase_entropy_api() {
+    let config = PassphraseConfig::new(4, "-".to_string(), WordList::Default);
+
+    // The default list has 7776 words, drawn without replacement.
+    let keyspace = 7776u128 * 7775 * 7774 * 7773;
+    assert_eq!(config.keyspace().unwrap(), Some(keyspace));
+    assert!((config.entropy_bits().unwrap() - (keyspace as f64).log2()).abs() < 1e-9);
+}
+
+#[test]
+fn test_token_generation_api() {
+    let config = TokenConfig::builder()
+        .bytes(20)
+        .encoding(TokenEncoding::Base32Crockford)
+        .prefix("pf_live_".to_string())
+        .checksum(true)
+        .build();
+    assert_eq!(config.entropy_bits().unwrap(), 160.0);
+
+    let token = TokenGenerator::generate(&config).expect("Failed to generate token");
+    let token = token.expose_secret();
+    // 20 bytes are 32 Crockford characters, followed by a 7 character checksum.
+    assert_eq!(token.len(), "pf_live_".len() + 32 + 7);
+    assert!(validate_token(&config, token).is_ok());
+    assert!(validate_token(&config, &token.replacen("pf_live_", "pf_test_", 1)).is_err());
+}